@@ -0,0 +1,726 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The static call graph of Wasm code, to find the host functions reachable from the
+//! exported entry points and from the messages and constructors they dispatch to.
+//!
+//! Direct calls are resolved exactly. A `call_indirect` is assumed to call any
+//! function of the matching type which an element segment places in a table or which
+//! is referenced by `ref.func`, so the reachable functions are over-approximated.
+
+use super::{
+    selectors::compared_selectors,
+    KnownSelectors,
+    Module,
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use wasmparser::{
+    ElementItems,
+    ExternalKind,
+    FuncType,
+    Operator,
+    TypeRef,
+};
+
+/// Host functions which give a contract dangerous capabilities: removing the
+/// contract, replacing its code, executing foreign code in its context and
+/// dispatching arbitrary runtime calls.
+///
+/// Matched against the names of the imported host functions without the `seal_`
+/// prefix of ink! 3.
+pub const DANGEROUS_HOST_FUNCTIONS: &[&str] = &[
+    "terminate",
+    "set_code_hash",
+    "delegate_call",
+    "call_runtime",
+];
+
+/// The functions each function of a module may call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    /// The callees by the index of the calling function, in the function index space.
+    callees: Vec<BTreeSet<u32>>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of a module.
+    pub fn new(module: &Module) -> Result<Self> {
+        let imported = module.imported_functions_count();
+        let imported_types: Vec<_> = module
+            .import_sections
+            .iter()
+            .filter_map(|import| {
+                match import.ty {
+                    TypeRef::Func(ty) => Some(ty),
+                    _ => None,
+                }
+            })
+            .collect();
+        let function_type = |function: u32| -> Option<&FuncType> {
+            let function = function as usize;
+            let ty = match function.checked_sub(imported) {
+                None => imported_types.get(function)?,
+                Some(defined) => module.function_sections.get(defined)?,
+            };
+            module.type_sections.get(*ty as usize)
+        };
+
+        // the functions which may be stored in a table and called indirectly
+        let mut indirect = BTreeSet::new();
+        for element in &module.element_sections {
+            match &element.items {
+                ElementItems::Functions(functions) => {
+                    for function in functions.clone() {
+                        indirect.insert(function?);
+                    }
+                }
+                ElementItems::Expressions(_, expressions) => {
+                    for expression in expressions.clone() {
+                        for operator in expression?.get_operators_reader() {
+                            if let Operator::RefFunc { function_index } = operator? {
+                                indirect.insert(function_index);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        for body in &module.code_sections {
+            for operator in body {
+                if let Operator::RefFunc { function_index } = operator {
+                    indirect.insert(*function_index);
+                }
+            }
+        }
+
+        let mut callees = vec![BTreeSet::new(); imported];
+        for body in &module.code_sections {
+            let mut called = BTreeSet::new();
+            for operator in body {
+                match operator {
+                    Operator::Call { function_index }
+                    | Operator::ReturnCall { function_index } => {
+                        called.insert(*function_index);
+                    }
+                    Operator::CallIndirect { type_index, .. }
+                    | Operator::ReturnCallIndirect { type_index, .. } => {
+                        let ty = module.type_sections.get(*type_index as usize);
+                        called.extend(indirect.iter().filter(|function| {
+                            ty.is_some() && function_type(**function) == ty
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+            callees.push(called);
+        }
+        Ok(Self { callees })
+    }
+
+    /// Returns the functions the function may call, directly or through a table.
+    pub fn callees(&self, function: u32) -> impl Iterator<Item = u32> + '_ {
+        self.callees
+            .get(function as usize)
+            .into_iter()
+            .flatten()
+            .copied()
+    }
+
+    /// Returns the functions reachable from the function `root`, including `root`.
+    pub fn reachable(&self, root: u32) -> BTreeSet<u32> {
+        let mut reachable = BTreeSet::from([root]);
+        let mut queue = vec![root];
+        while let Some(function) = queue.pop() {
+            for callee in self.callees(function) {
+                if reachable.insert(callee) {
+                    queue.push(callee);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Checks whether the function `from` may eventually call the function `to`.
+    pub fn reaches(&self, from: u32, to: u32) -> bool {
+        self.reachable(from).contains(&to)
+    }
+}
+
+/// The host functions reachable from an exported function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EntryPoint {
+    /// The name of the exported function, e.g. `call` or `deploy`, respectively the
+    /// label or hex encoded selector of a message or constructor.
+    pub name: String,
+    /// The host functions the entry point may call, formatted as `module::name`.
+    pub host_functions: Vec<String>,
+    /// The names of the reachable [`DANGEROUS_HOST_FUNCTIONS`].
+    pub dangerous: Vec<String>,
+    /// Whether the host functions are reachable from the entry point itself. The
+    /// dispatch to a message or constructor is not resolved, so they are those of the
+    /// exported function dispatching the selector.
+    pub resolved: bool,
+    /// The messages, respectively constructors, the exported function dispatches to.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub selectors: Vec<EntryPoint>,
+}
+
+/// Finds the host functions reachable from each exported function of the Wasm `code`.
+///
+/// The messages of an ink! contract are dispatched within the `call` export, and the
+/// constructors within `deploy`. Each selector compared by the dispatch is reported as
+/// unresolved entry point with the host functions of the export, as ink! only branches
+/// to the message or constructor after decoding the selector into an enum, see
+/// [`Selectors`] for how the selectors are recovered.
+///
+/// [`Selectors`]: super::Selectors
+pub fn analyze_entry_points(code: &[u8]) -> Result<Vec<EntryPoint>> {
+    let module = Module::new(code)?;
+    let graph = CallGraph::new(&module)?;
+    let known = KnownSelectors::builtin();
+    let host_functions: Vec<_> = module
+        .import_sections
+        .iter()
+        .filter(|import| matches!(import.ty, TypeRef::Func(_)))
+        .collect();
+    let entry_point = |name: String, reachable: &BTreeSet<u32>, resolved, selectors| {
+        let reached: Vec<_> = host_functions
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| reachable.contains(&(*index as u32)))
+            .map(|(_, import)| import)
+            .collect();
+        let mut dangerous: Vec<_> = reached
+            .iter()
+            .map(|import| import.name.trim_start_matches("seal_"))
+            .filter(|name| DANGEROUS_HOST_FUNCTIONS.contains(name))
+            .map(ToString::to_string)
+            .collect();
+        dangerous.sort();
+        dangerous.dedup();
+        EntryPoint {
+            name,
+            host_functions: reached
+                .iter()
+                .map(|import| format!("{}::{}", import.module, import.name))
+                .collect(),
+            dangerous,
+            resolved,
+            selectors,
+        }
+    };
+
+    let exports: Vec<_> = module
+        .export_sections
+        .iter()
+        .filter(|export| export.kind == ExternalKind::Func)
+        .collect();
+    let reachable: Vec<_> = exports
+        .iter()
+        .map(|export| graph.reachable(export.index))
+        .collect();
+    let entry_points = exports
+        .iter()
+        .zip(&reachable)
+        .enumerate()
+        .map(|(index, (export, functions))| {
+            // the helpers shared with other exports do not dispatch
+            let shared: BTreeSet<_> = reachable
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .flat_map(|(_, other)| other)
+                .copied()
+                .collect();
+            let compared: BTreeSet<_> = functions
+                .difference(&shared)
+                .flat_map(|function| compared_selectors(&module, *function))
+                .collect();
+            let selectors = compared
+                .into_iter()
+                .map(|selector| {
+                    let name = known.label(&selector).map_or_else(
+                        || format!("0x{}", hex::encode(selector)),
+                        ToString::to_string,
+                    );
+                    entry_point(name, functions, false, Vec::new())
+                })
+                .collect();
+            entry_point(export.name.to_string(), functions, true, selectors)
+        })
+        .collect();
+    Ok(entry_points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Selectors;
+
+    /// `call` reaches `terminate` through a table, `deploy` only `input`.
+    const CODE: &str = r#"
+        (module
+            (type (;0;) (func))
+            (type (;1;) (func (param i32)))
+            (import "seal0" "input" (func (;0;) (type 0)))
+            (import "seal1" "terminate" (func (;1;) (type 1)))
+            (import "seal0" "set_code_hash" (func (;2;) (type 1)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (table 2 funcref)
+            (elem (i32.const 0) func 5 6)
+            (func (;3;) (type 0)
+                call 0
+                i32.const 0
+                call_indirect (type 1))
+            (func (;4;) (type 0)
+                call 0)
+            (func (;5;) (type 1) (param i32)
+                local.get 0
+                call 1)
+            (func (;6;) (type 0)
+                i32.const 0
+                call 2)
+            (export "call" (func 3))
+            (export "deploy" (func 4))
+        )"#;
+
+    #[test]
+    fn resolves_direct_and_indirect_calls() {
+        let code = wat::parse_str(CODE).expect("Invalid wat.");
+        let module = Module::new(&code).unwrap();
+
+        let graph = CallGraph::new(&module).unwrap();
+
+        // only function 5 in the table matches the type of the `call_indirect`
+        assert_eq!(graph.callees(3).collect::<Vec<_>>(), [0, 5]);
+        assert_eq!(graph.reachable(3), BTreeSet::from([0, 1, 3, 5]));
+        assert!(graph.reaches(3, 1));
+        assert!(!graph.reaches(4, 1));
+        assert!(!graph.reaches(3, 2));
+    }
+
+    #[test]
+    fn flags_dangerous_host_functions_of_entry_points() {
+        let code = wat::parse_str(CODE).expect("Invalid wat.");
+
+        let entry_points = analyze_entry_points(&code).unwrap();
+
+        assert_eq!(
+            entry_points,
+            [
+                EntryPoint {
+                    name: "call".to_string(),
+                    host_functions: vec![
+                        "seal0::input".to_string(),
+                        "seal1::terminate".to_string()
+                    ],
+                    dangerous: vec!["terminate".to_string()],
+                    resolved: true,
+                    selectors: Vec::new(),
+                },
+                EntryPoint {
+                    name: "deploy".to_string(),
+                    host_functions: vec!["seal0::input".to_string()],
+                    dangerous: Vec::new(),
+                    resolved: true,
+                    selectors: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    /// The `call` export of the flipper example of ink! 5, with stubs of the functions
+    /// it calls. The selector is decoded into the discriminant in local 2, which is only
+    /// branched on to the inlined messages after the input is decoded.
+    const FLIPPER: &str = r#"
+        (module
+            (type (;0;) (func (param i32 i32 i32) (result i32)))
+            (type (;1;) (func (param i32 i32) (result i32)))
+            (type (;2;) (func (param i32 i32)))
+            (type (;3;) (func (param i32 i32 i32 i32) (result i32)))
+            (type (;4;) (func (param i32 i32 i32)))
+            (type (;5;) (func (param i32)))
+            (type (;6;) (func))
+            (type (;7;) (func (param i32 i32 i32 i32)))
+            (type (;8;) (func (param i32) (result i32)))
+            (type (;9;) (func (result i32)))
+            (import "seal1" "get_storage" (func (;0;) (type 3)))
+            (import "seal0" "input" (func (;1;) (type 2)))
+            (import "seal0" "seal_return" (func (;2;) (type 4)))
+            (import "seal0" "debug_message" (func (;3;) (type 1)))
+            (import "seal2" "set_storage" (func (;4;) (type 3)))
+            (import "seal0" "value_transferred" (func (;5;) (type 2)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (global (;0;) (mut i32) i32.const 65536)
+            (func (;6;) (type 6)
+                (local i32 i32 i32 i32 i32 i32 i32)
+                global.get 0
+                i32.const 48
+                i32.sub
+                local.tee 0
+                global.set 0
+                block
+                    block
+                        block
+                            call 8
+                            i32.const 255
+                            i32.and
+                            i32.const 5
+                            i32.eq
+                            if
+                                local.get 0
+                                i32.const 16384
+                                i32.store offset=36
+                                i32.const 67544
+                                local.get 0
+                                i32.const 36
+                                i32.add
+                                local.tee 1
+                                call 1
+                                local.get 0
+                                i32.const 16
+                                i32.add
+                                local.get 0
+                                i32.load offset=36
+                                i32.const 67544
+                                i32.const 16384
+                                call 9
+                                local.get 0
+                                local.get 0
+                                i64.load offset=16
+                                i64.store offset=36 align=4
+                                local.get 1
+                                local.get 0
+                                i32.const 24
+                                i32.add
+                                call 10
+                                br_if 1
+                                local.get 0
+                                i32.load offset=24
+                                local.tee 1
+                                i32.const 24
+                                i32.shr_u
+                                local.set 2
+                                local.get 1
+                                i32.const 16
+                                i32.shr_u
+                                local.set 3
+                                local.get 1
+                                i32.const 8
+                                i32.shr_u
+                                local.set 4
+                                block (result i32)
+                                    local.get 1
+                                    i32.const 255
+                                    i32.and
+                                    local.tee 1
+                                    i32.const 99
+                                    i32.ne
+                                    if
+                                        local.get 4
+                                        i32.const 255
+                                        i32.and
+                                        i32.const 134
+                                        i32.ne
+                                        local.get 3
+                                        i32.const 255
+                                        i32.and
+                                        i32.const 91
+                                        i32.ne
+                                        i32.or
+                                        local.get 2
+                                        i32.const 217
+                                        i32.ne
+                                        i32.or
+                                        local.get 1
+                                        i32.const 47
+                                        i32.ne
+                                        i32.or
+                                        br_if 3
+                                        i32.const 1
+                                        br 1
+                                    end
+                                    local.get 4
+                                    i32.const 255
+                                    i32.and
+                                    i32.const 58
+                                    i32.ne
+                                    local.get 3
+                                    i32.const 255
+                                    i32.and
+                                    i32.const 165
+                                    i32.ne
+                                    i32.or
+                                    local.get 2
+                                    i32.const 81
+                                    i32.ne
+                                    i32.or
+                                    br_if 2
+                                    i32.const 0
+                                end
+                                local.set 2
+                                local.get 0
+                                i32.const 0
+                                i32.store offset=32
+                                local.get 0
+                                i64.const 16384
+                                i64.store offset=40 align=4
+                                local.get 0
+                                i32.const 67544
+                                i32.store offset=36
+                                local.get 0
+                                i32.const 36
+                                i32.add
+                                local.tee 3
+                                call 11
+                                local.get 0
+                                local.get 0
+                                i64.load offset=36 align=4
+                                i64.store offset=24 align=4
+                                local.get 0
+                                i32.const 8
+                                i32.add
+                                local.get 0
+                                i32.const 24
+                                i32.add
+                                local.get 0
+                                i32.load offset=44
+                                call 12
+                                local.get 0
+                                i32.load offset=12
+                                local.set 4
+                                local.get 0
+                                i32.load offset=8
+                                local.set 5
+                                local.get 0
+                                i32.load offset=24
+                                local.set 1
+                                local.get 0
+                                local.get 0
+                                i32.load offset=28
+                                local.tee 6
+                                i32.store offset=36
+                                local.get 5
+                                local.get 4
+                                local.get 1
+                                local.get 3
+                                call 0
+                                local.set 3
+                                local.get 0
+                                local.get 0
+                                i32.load offset=36
+                                local.get 1
+                                local.get 6
+                                call 9
+                                block
+                                    block
+                                        block
+                                            local.get 3
+                                            br_table 2 1 1 0 1
+                                        end
+                                        i32.const 66724
+                                        i32.const 47
+                                        i32.const 66632
+                                        call 13
+                                        unreachable
+                                    end
+                                    i32.const 66664
+                                    i32.const 57
+                                    i32.const 66692
+                                    call 13
+                                    unreachable
+                                end
+                                local.get 0
+                                i32.load
+                                local.set 1
+                                local.get 0
+                                local.get 0
+                                i32.load offset=4
+                                i32.store offset=40
+                                local.get 0
+                                local.get 1
+                                i32.store offset=36
+                                local.get 0
+                                i32.const 36
+                                i32.add
+                                call 14
+                                i32.const 255
+                                i32.and
+                                local.tee 1
+                                i32.const 2
+                                i32.eq
+                                br_if 2
+                                local.get 0
+                                i32.load offset=40
+                                br_if 2
+                                local.get 2
+                                br_if 3
+                                local.get 1
+                                i32.const -1
+                                i32.xor
+                                i32.const 1
+                                i32.and
+                                call 18
+                                i32.const 0
+                                i32.const 0
+                                call 15
+                                unreachable
+                            end
+                            local.get 0
+                            i32.const 4
+                            i32.store8 offset=36
+                            local.get 0
+                            i32.const 36
+                            i32.add
+                            call 16
+                            unreachable
+                        end
+                        i32.const 1
+                        i32.const 1
+                        call 15
+                        unreachable
+                    end
+                    i32.const 66747
+                    i32.const 79
+                    i32.const 66632
+                    call 13
+                    unreachable
+                end
+                global.get 0
+                i32.const 16
+                i32.sub
+                local.tee 0
+                global.set 0
+                local.get 0
+                i64.const 16384
+                i64.store offset=8 align=4
+                local.get 0
+                i32.const 67544
+                i32.store offset=4
+                local.get 0
+                i32.const 4
+                i32.add
+                local.tee 2
+                i32.const 0
+                call 20
+                local.get 1
+                local.get 2
+                call 19
+                local.get 0
+                i32.load offset=12
+                local.tee 0
+                i32.const 16385
+                i32.ge_u
+                if
+                    i32.const 0
+                    local.get 0
+                    i32.const 16384
+                    i32.const 66648
+                    call 17
+                    unreachable
+                end
+                i32.const 0
+                local.get 0
+                call 21
+                unreachable
+            )
+            (func (;7;) (type 6)
+                i32.const 0
+                i32.const 0
+                call 1)
+            (func (;8;) (type 9) (result i32)
+                i32.const 0
+                i32.const 0
+                call 5
+                i32.const 5)
+            (func (;9;) (type 7) (param i32 i32 i32 i32))
+            (func (;10;) (type 1) (param i32 i32) (result i32)
+                i32.const 0)
+            (func (;11;) (type 5) (param i32))
+            (func (;12;) (type 4) (param i32 i32 i32))
+            (func (;13;) (type 4) (param i32 i32 i32)
+                unreachable)
+            (func (;14;) (type 8) (param i32) (result i32)
+                i32.const 0)
+            (func (;15;) (type 2) (param i32 i32)
+                i32.const 0
+                i32.const 0
+                i32.const 0
+                call 2)
+            (func (;16;) (type 5) (param i32)
+                unreachable)
+            (func (;17;) (type 7) (param i32 i32 i32 i32)
+                unreachable)
+            (func (;18;) (type 5) (param i32)
+                i32.const 0
+                i32.const 0
+                i32.const 0
+                i32.const 0
+                call 4
+                drop)
+            (func (;19;) (type 2) (param i32 i32))
+            (func (;20;) (type 2) (param i32 i32))
+            (func (;21;) (type 2) (param i32 i32)
+                i32.const 0
+                i32.const 0
+                i32.const 0
+                call 2)
+            (export "call" (func 6))
+            (export "deploy" (func 7))
+        )"#;
+
+    #[test]
+    fn reports_selectors_of_ink_dispatch_as_unresolved() {
+        let code = wat::parse_str(FLIPPER).expect("Invalid wat.");
+
+        let selectors = Selectors::recover(&code).unwrap();
+        let entry_points = analyze_entry_points(&code).unwrap();
+
+        let messages: Vec<_> = selectors
+            .messages
+            .iter()
+            .map(|message| message.selector)
+            .collect();
+        assert_eq!(
+            messages,
+            [[0x2f, 0x86, 0x5b, 0xd9], [0x63, 0x3a, 0xa5, 0x51]]
+        );
+        let call = &entry_points[0];
+        assert_eq!(
+            call.host_functions,
+            [
+                "seal1::get_storage",
+                "seal0::input",
+                "seal0::seal_return",
+                "seal2::set_storage",
+                "seal0::value_transferred",
+            ]
+        );
+        let unresolved = |name: &str| {
+            EntryPoint {
+                name: name.to_string(),
+                host_functions: call.host_functions.clone(),
+                dangerous: Vec::new(),
+                resolved: false,
+                selectors: Vec::new(),
+            }
+        };
+        assert_eq!(call.selectors, [unresolved("get"), unresolved("flip")]);
+        assert!(entry_points[1].selectors.is_empty());
+    }
+}
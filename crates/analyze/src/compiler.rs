@@ -0,0 +1,430 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Estimating the compiler of contract code without metadata.
+//!
+//! The host functions of `pallet-contracts` are versioned by their import module, and
+//! each ink! major version uses a different set of them, e.g. ink! 3 imports the
+//! `seal_` prefixed functions that ink! 4 renamed, and ink! 5 the `seal2` versions of
+//! `call` and `instantiate`. Solang records its version in the `producers` section.
+
+use super::{
+    determine_language,
+    determine_target,
+    is_ink_function_present,
+    riscv_imports,
+    Language,
+    Module,
+    SourceTarget,
+};
+use anyhow::Result;
+use contract_metadata::compatibility::check_contract_ink_compatibility;
+use semver::{
+    Version,
+    VersionReq,
+};
+use serde::{
+    Serialize,
+    Serializer,
+};
+use std::fmt::{
+    Display,
+    Formatter,
+    Result as DisplayResult,
+};
+use wasmparser::{
+    ProducersSectionReader,
+    TypeRef,
+};
+
+/// Host functions only imported by ink! 3, which ink! 4 renamed without the `seal_`
+/// prefix.
+///
+/// `seal_return` is not among them: `return` is a keyword, so ink! 4 and 5 still import
+/// it by its prefixed name.
+const INK_3_IMPORTS: &[&str] = &[
+    "seal_address",
+    "seal_balance",
+    "seal_call",
+    "seal_call_chain_extension",
+    "seal_caller",
+    "seal_clear_storage",
+    "seal_debug_message",
+    "seal_deposit_event",
+    "seal_get_storage",
+    "seal_hash_blake2_256",
+    "seal_input",
+    "seal_instantiate",
+    "seal_set_storage",
+    "seal_terminate",
+    "seal_transfer",
+    "seal_value_transferred",
+];
+
+/// Host functions only imported by ink! 5 and later.
+const INK_5_IMPORTS: &[(&str, &str)] = &[
+    ("seal2", "call"),
+    ("seal2", "instantiate"),
+    ("seal0", "lock_delegate_dependency"),
+    ("seal0", "unlock_delegate_dependency"),
+    ("seal0", "xcm_execute"),
+    ("seal0", "xcm_send"),
+];
+
+/// Host functions only imported by ink! 4, which ink! 5 replaced by newer versions.
+const INK_4_IMPORTS: &[(&str, &str)] = &[("seal1", "call"), ("seal1", "instantiate")];
+
+/// The ink! major versions to check the compatibility of an estimate with.
+const INK_MAJOR_VERSIONS: [u64; 3] = [3, 4, 5];
+
+/// How certain an estimate of the compiler is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    /// The evidence is shared by several compiler versions.
+    Low,
+    /// The evidence is typical for the compiler versions.
+    Medium,
+    /// The compiler recorded its version in the code.
+    High,
+}
+
+impl Display for Confidence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+/// The estimated compiler of contract code, with the evidence it is based on.
+#[derive(Clone, Debug, Serialize)]
+pub struct CompilerEstimate {
+    /// The source language of the contract.
+    #[serde(serialize_with = "serialize_language")]
+    pub language: Language,
+    /// The range of the compiler versions, i.e. of ink! for ink! contracts and of
+    /// Solang for Solidity contracts. `None` if the version can not be estimated.
+    pub versions: Option<VersionReq>,
+    /// How certain the estimate is.
+    pub confidence: Confidence,
+    /// The findings in the code the estimate is based on.
+    pub evidence: Vec<String>,
+}
+
+impl CompilerEstimate {
+    /// Checks whether the estimated ink! versions are compatible with the
+    /// `cargo-contract` binary.
+    ///
+    /// The check succeeds if any ink! major version in the estimated range is
+    /// compatible, or if the contract is not an ink! contract of known versions.
+    pub fn check_ink_compatibility(&self) -> Result<()> {
+        let (Language::Ink, Some(versions)) = (&self.language, &self.versions) else {
+            return Ok(())
+        };
+        let mut result = Ok(());
+        for major in INK_MAJOR_VERSIONS {
+            let version = Version::new(major, 0, 0);
+            if versions.matches(&version) {
+                result = check_contract_ink_compatibility(&version, None);
+                if result.is_ok() {
+                    break
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Display for CompilerEstimate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        write!(f, "{}", self.language)?;
+        if let Some(versions) = &self.versions {
+            write!(f, " {versions}")?;
+        }
+        write!(f, " ({} confidence)", self.confidence)
+    }
+}
+
+fn serialize_language<S>(language: &Language, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(language)
+}
+
+/// Estimates the compiler and its version range from the Wasm code or the PolkaVM
+/// program blob of a contract.
+pub fn estimate_compiler(code: &[u8]) -> Result<CompilerEstimate> {
+    if determine_target(code)? == SourceTarget::RiscV {
+        let language = determine_language(code)?;
+        let imports = riscv_imports(code)?
+            .iter()
+            .map(|import| format!("`{import}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut evidence = format!("is a PolkaVM program importing {imports}");
+        let versions = matches!(language, Language::Ink).then(|| {
+            evidence.push_str(", RISC-V is only supported by ink! 5 and later");
+            version_req(">=5.0.0")
+        });
+        return Ok(CompilerEstimate {
+            language,
+            versions,
+            confidence: Confidence::Low,
+            evidence: vec![evidence],
+        })
+    }
+    let module = Module::new(code)?;
+    if let Some(estimate) = estimate_solang(&module)? {
+        return Ok(estimate)
+    }
+    if module.start_section.is_none()
+        && (is_ink_function_present(&module)
+            || matches!(module.has_function_name("ink_env"), Ok(true)))
+    {
+        return Ok(estimate_ink(&module))
+    }
+    let language = determine_language(code)?;
+    let evidence = match language {
+        Language::AssemblyScript => {
+            "has a start function and a `sourceMappingURL` section, the version is \
+            not recorded"
+        }
+        _ => "has a `producers` section without a Solang version",
+    };
+    Ok(CompilerEstimate {
+        language,
+        versions: None,
+        confidence: Confidence::Low,
+        evidence: vec![evidence.to_string()],
+    })
+}
+
+/// Reads the Solang version from the `producers` section, if the code was compiled by
+/// Solang.
+fn estimate_solang(module: &Module) -> Result<Option<CompilerEstimate>> {
+    let Some(producers) = module.custom_sections.get("producers") else {
+        return Ok(None)
+    };
+    for field in ProducersSectionReader::new(producers, 0)? {
+        let field = field?;
+        if field.name != "processed-by" {
+            continue
+        }
+        for value in field.values {
+            let value = value?;
+            if !value.name.eq_ignore_ascii_case("solang") {
+                continue
+            }
+            let version = value.version.trim_start_matches('v');
+            let (versions, confidence) = match Version::parse(version) {
+                Ok(version) => {
+                    (Some(version_req(&format!("={version}"))), Confidence::High)
+                }
+                Err(_) => (None, Confidence::Medium),
+            };
+            return Ok(Some(CompilerEstimate {
+                language: Language::Solidity,
+                versions,
+                confidence,
+                evidence: vec![format!(
+                    "the `producers` section lists `solang {}`",
+                    value.version
+                )],
+            }))
+        }
+    }
+    Ok(None)
+}
+
+/// Estimates the ink! version from the imported host functions.
+fn estimate_ink(module: &Module) -> CompilerEstimate {
+    let imports: Vec<_> = module
+        .import_sections
+        .iter()
+        .filter(|import| matches!(import.ty, TypeRef::Func(_)))
+        .map(|import| (import.module, import.name))
+        .collect();
+    let find = |known: &[(&str, &str)]| {
+        imports
+            .iter()
+            .filter(|import| known.contains(import))
+            .map(|(module, name)| format!("imports `{module}::{name}`"))
+            .collect::<Vec<_>>()
+    };
+
+    let ink_3 = imports
+        .iter()
+        .filter(|(_, name)| INK_3_IMPORTS.contains(name))
+        .map(|(module, name)| format!("imports `{module}::{name}`"))
+        .collect::<Vec<_>>();
+    if !ink_3.is_empty() {
+        return CompilerEstimate {
+            language: Language::Ink,
+            versions: Some(version_req(">=3.0.0, <4.0.0")),
+            confidence: Confidence::High,
+            evidence: ink_3,
+        }
+    }
+    let ink_5 = find(INK_5_IMPORTS);
+    if !ink_5.is_empty() {
+        return CompilerEstimate {
+            language: Language::Ink,
+            versions: Some(version_req(">=5.0.0, <6.0.0")),
+            confidence: Confidence::Medium,
+            evidence: ink_5,
+        }
+    }
+    let ink_4 = find(INK_4_IMPORTS);
+    if !ink_4.is_empty() {
+        return CompilerEstimate {
+            language: Language::Ink,
+            versions: Some(version_req(">=4.0.0, <5.0.0")),
+            confidence: Confidence::Medium,
+            evidence: ink_4,
+        }
+    }
+    CompilerEstimate {
+        language: Language::Ink,
+        versions: Some(version_req(">=4.0.0, <6.0.0")),
+        confidence: Confidence::Low,
+        evidence: vec![
+            "imports the host functions without the `seal_` prefix of ink! 3, but none \
+            specific to ink! 4 or 5"
+                .to_string(),
+        ],
+    }
+}
+
+fn version_req(req: &str) -> VersionReq {
+    VersionReq::parse(req).expect("version requirements are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the Wasm code of an ink! contract importing `value_transferred`,
+    /// `seal0::seal_return` like every ink! version and `imports`.
+    fn ink_contract(value_transferred: &str, imports: &str) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"
+            (module
+                (type (;0;) (func (param i32)))
+                (type (;1;) (func (result i32)))
+                (type (;2;) (func (param i32 i32 i32)))
+                (import "seal0" "{value_transferred}" (func (;0;) (type 0)))
+                (import "seal0" "seal_return" (func (;1;) (type 2)))
+                {imports}
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func (type 1) (result i32)
+                    i32.const 0
+                    call 0
+                    i32.const 0)
+            )"#
+        ))
+        .expect("Invalid wat.")
+    }
+
+    #[test]
+    fn estimates_ink_versions_from_imports() {
+        let ink_3 =
+            estimate_compiler(&ink_contract("seal_value_transferred", "")).unwrap();
+        assert!(matches!(ink_3.language, Language::Ink));
+        assert_eq!(ink_3.to_string(), "ink! >=3.0.0, <4.0.0 (high confidence)");
+        assert_eq!(ink_3.evidence, ["imports `seal0::seal_value_transferred`"]);
+        assert!(ink_3.check_ink_compatibility().is_err());
+
+        let ink_5 = estimate_compiler(&ink_contract(
+            "value_transferred",
+            r#"(import "seal2" "call" (func (;2;) (type 0)))"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            ink_5.to_string(),
+            "ink! >=5.0.0, <6.0.0 (medium confidence)"
+        );
+        assert_eq!(ink_5.evidence, ["imports `seal2::call`"]);
+        assert!(ink_5.check_ink_compatibility().is_ok());
+
+        let ink_4_or_5 =
+            estimate_compiler(&ink_contract("value_transferred", "")).unwrap();
+        assert_eq!(ink_4_or_5.versions, Some(version_req(">=4.0.0, <6.0.0")));
+        assert_eq!(ink_4_or_5.confidence, Confidence::Low);
+        assert!(ink_4_or_5
+            .evidence
+            .iter()
+            .all(|evidence| !evidence.contains("seal_return")));
+        assert!(ink_4_or_5.check_ink_compatibility().is_ok());
+    }
+
+    #[test]
+    fn reads_solang_version_from_producers_section() {
+        // field count, "processed-by" with one value "solang" in version "v0.3.3"
+        let producers: String = [
+            &[1u8, 12][..],
+            b"processed-by",
+            &[1, 6],
+            b"solang",
+            &[6],
+            b"v0.3.3",
+        ]
+        .concat()
+        .iter()
+        .map(|byte| format!("\\{byte:02x}"))
+        .collect();
+        let code = wat::parse_str(format!(
+            r#"
+            (module
+                (import "env" "memory" (memory (;0;) 16 16))
+                (@custom "producers" "{producers}")
+            )"#
+        ))
+        .expect("Invalid wat.");
+
+        let estimate = estimate_compiler(&code).unwrap();
+
+        assert!(matches!(estimate.language, Language::Solidity));
+        assert_eq!(estimate.versions, Some(version_req("=0.3.3")));
+        assert_eq!(estimate.confidence, Confidence::High);
+        assert_eq!(
+            serde_json::to_value(&estimate).unwrap()["language"],
+            "Solidity"
+        );
+    }
+
+    #[test]
+    fn estimates_riscv_contract_from_its_imports() {
+        let code =
+            crate::tests::riscv_program(&["input", "value_transferred", "seal_return"]);
+
+        let estimate = estimate_compiler(&code).unwrap();
+
+        assert!(matches!(estimate.language, Language::Ink));
+        assert_eq!(estimate.versions, Some(version_req(">=5.0.0")));
+        assert_eq!(
+            estimate.evidence,
+            [
+                "is a PolkaVM program importing `input`, `value_transferred`, \
+            `seal_return`, RISC-V is only supported by ink! 5 and later"
+            ]
+        );
+        assert!(estimate_compiler(&crate::tests::riscv_program(&["input"])).is_err());
+    }
+}
@@ -0,0 +1,602 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    inspect::{
+        export_kind,
+        memory_limits,
+    },
+    MemoryLimits,
+    Module,
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+};
+use wasmparser::{
+    Data,
+    DataKind,
+    FunctionBody,
+    Operator,
+    TypeRef,
+};
+
+/// Identifies a function in the function index space of a module.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FunctionId {
+    /// The index of the function, including imported functions.
+    pub index: u32,
+    /// The name of the function, if the module contains a name section.
+    pub name: Option<String>,
+}
+
+impl Display for FunctionId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match &self.name {
+            Some(name) => write!(f, "#{} ({})", self.index, name),
+            None => write!(f, "#{}", self.index),
+        }
+    }
+}
+
+/// The memory limits of a reference module and another module.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MemoryChange {
+    /// The memory limits of the reference module, `None` without memory.
+    pub reference: Option<MemoryLimits>,
+    /// The memory limits of the other module, `None` without memory.
+    pub other: Option<MemoryLimits>,
+}
+
+impl Display for MemoryChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        let limits = |limits: &Option<MemoryLimits>| {
+            limits
+                .as_ref()
+                .map_or_else(|| "none".to_string(), ToString::to_string)
+        };
+        write!(f, "{} -> {}", limits(&self.reference), limits(&self.other))
+    }
+}
+
+/// Section level differences between a reference module and another module.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ModuleDiff {
+    /// Imports only present in the other module, formatted as `module::name`.
+    pub added_imports: Vec<String>,
+    /// Imports only present in the reference module, formatted as `module::name`.
+    pub removed_imports: Vec<String>,
+    /// Exports only present in the other module, formatted as `name (kind)`.
+    pub added_exports: Vec<String>,
+    /// Exports only present in the reference module, formatted as `name (kind)`.
+    pub removed_exports: Vec<String>,
+    /// The memory limits, if they differ between the modules.
+    pub changed_memory: Option<MemoryChange>,
+    /// Functions only present in the other module.
+    pub added_functions: Vec<FunctionId>,
+    /// Functions only present in the reference module.
+    pub removed_functions: Vec<FunctionId>,
+    /// Functions present in both modules, but with a different body.
+    pub changed_functions: Vec<FunctionId>,
+    /// Indices of the data segments only present in the other module.
+    pub added_data_segments: Vec<usize>,
+    /// Indices of the data segments only present in the reference module.
+    pub removed_data_segments: Vec<usize>,
+    /// Indices of the data segments with a different offset or content.
+    pub changed_data_segments: Vec<usize>,
+}
+
+impl ModuleDiff {
+    /// Returns `true` if no differences were found.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl Display for ModuleDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        fn write_list<T: Display>(
+            f: &mut Formatter<'_>,
+            title: &str,
+            items: &[T],
+        ) -> DisplayResult {
+            if items.is_empty() {
+                return Ok(());
+            }
+            writeln!(f, "{title} ({}):", items.len())?;
+            for item in items {
+                writeln!(f, "  - {item}")?;
+            }
+            Ok(())
+        }
+
+        if self.is_empty() {
+            return writeln!(f, "No section level differences found.");
+        }
+        write_list(f, "Added imports", &self.added_imports)?;
+        write_list(f, "Removed imports", &self.removed_imports)?;
+        write_list(f, "Added exports", &self.added_exports)?;
+        write_list(f, "Removed exports", &self.removed_exports)?;
+        if let Some(memory) = &self.changed_memory {
+            writeln!(f, "Changed memory: {memory}")?;
+        }
+        write_list(f, "Added functions", &self.added_functions)?;
+        write_list(f, "Removed functions", &self.removed_functions)?;
+        write_list(f, "Changed functions", &self.changed_functions)?;
+        write_list(f, "Added data segments", &self.added_data_segments)?;
+        write_list(f, "Removed data segments", &self.removed_data_segments)?;
+        write_list(f, "Changed data segments", &self.changed_data_segments)
+    }
+}
+
+/// Compares the imports, exports, memory limits, function bodies and data segments of
+/// two modules.
+///
+/// Functions are matched by their name if both modules contain a name section,
+/// otherwise by their body, and the remaining functions by their position in the code
+/// section. Data segments are matched by their position in the data section.
+///
+/// The bodies are compared with the called functions and the accessed globals resolved
+/// from their index, see [`normalized_bodies`], so that inserting a function does not
+/// change the bodies calling the functions after it.
+pub fn diff_modules(reference: &Module, other: &Module) -> Result<ModuleDiff> {
+    let mut diff = ModuleDiff::default();
+
+    // Imports
+    let import_names = |module: &Module| {
+        module
+            .import_sections
+            .iter()
+            .map(|import| format!("{}::{}", import.module, import.name))
+            .collect::<Vec<_>>()
+    };
+    (diff.added_imports, diff.removed_imports) =
+        added_and_removed(import_names(reference), import_names(other));
+
+    // Exports
+    let export_names = |module: &Module| {
+        module
+            .export_sections
+            .iter()
+            .map(|export| format!("{} ({})", export.name, export_kind(export.kind)))
+            .collect::<Vec<_>>()
+    };
+    (diff.added_exports, diff.removed_exports) =
+        added_and_removed(export_names(reference), export_names(other));
+
+    // Memory
+    let memory = MemoryChange {
+        reference: memory_limits(reference),
+        other: memory_limits(other),
+    };
+    if memory.reference != memory.other {
+        diff.changed_memory = Some(memory);
+    }
+
+    // Functions
+    let reference_names = reference.function_names()?;
+    let other_names = other.function_names()?;
+    let index_of = |module: &Module, position: usize| {
+        (module.imported_functions_count() + position) as u32
+    };
+    let mut symbols = Symbols::default();
+    let reference_bodies = normalized_bodies(reference, &reference_names, &mut symbols)?;
+    let other_bodies = normalized_bodies(other, &other_names, &mut symbols)?;
+    let reference_name =
+        |position| reference_names.get(&index_of(reference, position)).copied();
+    let other_name = |position| other_names.get(&index_of(other, position)).copied();
+    let function_id = |module: &Module, position, name: Option<&str>| {
+        FunctionId {
+            index: index_of(module, position),
+            name: name.map(ToString::to_string),
+        }
+    };
+    let mut reference_unmatched: Vec<usize> =
+        (0..reference.function_bodies.len()).collect();
+    let mut other_unmatched: Vec<usize> = (0..other.function_bodies.len()).collect();
+    let mut matches = Vec::new();
+    // the named functions without a counterpart of the same name were added or removed
+    let mut reference_named = Vec::new();
+    let mut other_named = Vec::new();
+    if !reference_names.is_empty() && !other_names.is_empty() {
+        let by_name: HashMap<_, _> = other_unmatched
+            .iter()
+            .filter_map(|&position| other_name(position).map(|name| (name, position)))
+            .collect();
+        for &position in &reference_unmatched {
+            if let Some(&other_position) =
+                reference_name(position).and_then(|name| by_name.get(name))
+            {
+                matches.push((position, other_position));
+            }
+        }
+        match_remaining(&mut reference_unmatched, &mut other_unmatched, &matches);
+        (reference_named, reference_unmatched) = reference_unmatched
+            .into_iter()
+            .partition(|&position| reference_name(position).is_some());
+        (other_named, other_unmatched) = other_unmatched
+            .into_iter()
+            .partition(|&position| other_name(position).is_some());
+    }
+    let mut by_body = HashMap::<_, Vec<_>>::new();
+    for &position in other_unmatched.iter().rev() {
+        by_body
+            .entry(other_bodies[position].as_slice())
+            .or_default()
+            .push(position);
+    }
+    let identical: Vec<_> = reference_unmatched
+        .iter()
+        .filter_map(|&position| {
+            by_body
+                .get_mut(reference_bodies[position].as_slice())
+                .and_then(Vec::pop)
+                .map(|other_position| (position, other_position))
+        })
+        .collect();
+    match_remaining(&mut reference_unmatched, &mut other_unmatched, &identical);
+    matches.extend(identical);
+    // the functions matching neither by name nor body are paired by their position,
+    // as a changed function usually keeps its position
+    let paired = reference_unmatched.len().min(other_unmatched.len());
+    matches.extend(
+        reference_unmatched
+            .drain(..paired)
+            .zip(other_unmatched.drain(..paired)),
+    );
+    matches.sort();
+    for (position, other_position) in matches {
+        if reference_bodies[position] != other_bodies[other_position] {
+            let name = other_name(other_position).or_else(|| reference_name(position));
+            diff.changed_functions
+                .push(function_id(other, other_position, name));
+        }
+    }
+    reference_unmatched.extend(reference_named);
+    reference_unmatched.sort();
+    other_unmatched.extend(other_named);
+    other_unmatched.sort();
+    diff.removed_functions = reference_unmatched
+        .into_iter()
+        .map(|position| function_id(reference, position, reference_name(position)))
+        .collect();
+    diff.added_functions = other_unmatched
+        .into_iter()
+        .map(|position| function_id(other, position, other_name(position)))
+        .collect();
+
+    // Data segments
+    let data_len = reference.data_sections.len().max(other.data_sections.len());
+    for index in 0..data_len {
+        match (
+            reference.data_sections.get(index),
+            other.data_sections.get(index),
+        ) {
+            (Some(a), Some(b)) if !data_segments_eq(a, b)? => {
+                diff.changed_data_segments.push(index)
+            }
+            (Some(_), None) => diff.removed_data_segments.push(index),
+            (None, Some(_)) => diff.added_data_segments.push(index),
+            _ => {}
+        }
+    }
+
+    Ok(diff)
+}
+
+/// Assigns ids to the functions and globals referenced by the function bodies, shared by
+/// the compared modules.
+#[derive(Default)]
+struct Symbols {
+    ids: HashMap<String, u32>,
+}
+
+impl Symbols {
+    fn id(&mut self, symbol: String) -> u32 {
+        let next = self.ids.len() as u32;
+        *self.ids.entry(symbol).or_insert(next)
+    }
+}
+
+/// Returns the function bodies of the module, with the indices of called functions and
+/// accessed globals replaced by the ids of their `symbols`.
+///
+/// An imported function or global is identified by its name, without the import module
+/// whose changes are reported as changed imports. A defined function is identified by
+/// its name or otherwise by its type, and a defined global by its position in the
+/// global section.
+fn normalized_bodies(
+    module: &Module,
+    names: &HashMap<u32, &str>,
+    symbols: &mut Symbols,
+) -> Result<Vec<Vec<u8>>> {
+    let imports_of = |is_kind: fn(&TypeRef) -> bool| {
+        module
+            .import_sections
+            .iter()
+            .filter(|import| is_kind(&import.ty))
+            .map(|import| import.name)
+            .collect::<Vec<_>>()
+    };
+    let imported_functions = imports_of(|ty| matches!(ty, TypeRef::Func(_)));
+    let imported_globals = imports_of(|ty| matches!(ty, TypeRef::Global(_)));
+    let function = |index: u32| {
+        if let Some(import) = imported_functions.get(index as usize) {
+            return format!("imported function {import}")
+        }
+        if let Some(name) = names.get(&index) {
+            return format!("function {name}")
+        }
+        let ty = module
+            .function_sections
+            .get(index as usize - imported_functions.len())
+            .and_then(|&ty| module.type_sections.get(ty as usize));
+        match ty {
+            Some(ty) => format!("function {:?} -> {:?}", ty.params(), ty.results()),
+            None => format!("function #{index}"),
+        }
+    };
+    let global = |index: u32| {
+        match imported_globals.get(index as usize) {
+            Some(import) => format!("imported global {import}"),
+            None => format!("global {}", index as usize - imported_globals.len()),
+        }
+    };
+
+    let mut bodies = Vec::new();
+    for body in &module.function_bodies {
+        let mut reader = FunctionBody::new(0, body).get_operators_reader()?;
+        let mut normalized = body[..reader.original_position()].to_vec();
+        while !reader.eof() {
+            let (operator, offset) = reader.read_with_offset()?;
+            let symbol = match operator {
+                Operator::Call { function_index }
+                | Operator::ReturnCall { function_index }
+                | Operator::RefFunc { function_index } => Some(function(function_index)),
+                Operator::GlobalGet { global_index }
+                | Operator::GlobalSet { global_index } => Some(global(global_index)),
+                _ => None,
+            };
+            match symbol {
+                // the opcodes of these operators are a single byte
+                Some(symbol) => {
+                    normalized.push(body[offset]);
+                    normalized.extend(symbols.id(symbol).to_le_bytes());
+                }
+                None => {
+                    normalized
+                        .extend_from_slice(&body[offset..reader.original_position()])
+                }
+            }
+        }
+        bodies.push(normalized);
+    }
+    Ok(bodies)
+}
+
+/// Returns the items only present in `other`, and those only present in `reference`.
+fn added_and_removed(
+    reference: Vec<String>,
+    other: Vec<String>,
+) -> (Vec<String>, Vec<String>) {
+    let added = other
+        .iter()
+        .filter(|item| !reference.contains(item))
+        .cloned()
+        .collect();
+    let removed = reference
+        .into_iter()
+        .filter(|item| !other.contains(item))
+        .collect();
+    (added, removed)
+}
+
+/// Removes the matched positions from the unmatched positions of both modules.
+fn match_remaining(
+    reference_unmatched: &mut Vec<usize>,
+    other_unmatched: &mut Vec<usize>,
+    matches: &[(usize, usize)],
+) {
+    reference_unmatched.retain(|position| !matches.iter().any(|(p, _)| p == position));
+    other_unmatched.retain(|position| !matches.iter().any(|(_, p)| p == position));
+}
+
+/// Returns the constant offset of an active data segment.
+///
+/// Returns `None` for passive segments or offsets which are not a constant.
+pub fn data_segment_offset(data: &Data) -> Result<Option<i64>> {
+    let DataKind::Active { offset_expr, .. } = &data.kind else {
+        return Ok(None);
+    };
+    let offset = match offset_expr.get_operators_reader().read()? {
+        Operator::I32Const { value } => Some(value as i64),
+        Operator::I64Const { value } => Some(value),
+        _ => None,
+    };
+    Ok(offset)
+}
+
+/// Checks if two data segments have the same offset and content.
+fn data_segments_eq(a: &Data, b: &Data) -> Result<bool> {
+    Ok(a.data == b.data && data_segment_offset(a)? == data_segment_offset(b)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(reference: &str, other: &str) -> ModuleDiff {
+        let reference = wat::parse_str(reference).expect("Invalid wat.");
+        let other = wat::parse_str(other).expect("Invalid wat.");
+        let reference = Module::new(&reference).expect("Invalid module.");
+        let other = Module::new(&other).expect("Invalid module.");
+        diff_modules(&reference, &other).expect("Diff failed.")
+    }
+
+    #[test]
+    fn identical_modules_have_no_diff() {
+        let contract = r#"
+        (module
+            (import "seal0" "foo" (func (param i32)))
+            (import "env" "memory" (memory 2 16))
+            (func (i32.const 1) (call 0))
+            (data (i32.const 65536) "test")
+        )"#;
+        let diff = diff(contract, contract);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn detects_changed_imports_functions_and_data() {
+        let reference = r#"
+        (module
+            (import "seal0" "foo" (func (param i32)))
+            (import "env" "memory" (memory 2 16))
+            (func (i32.const 1) (call 0))
+            (func (i32.const 2) (call 0))
+            (data (i32.const 65536) "test")
+        )"#;
+        let other = r#"
+        (module
+            (import "seal1" "foo" (func (param i32)))
+            (import "env" "memory" (memory 2 16))
+            (func $changed (i32.const 3) (call 0))
+            (func (i32.const 2) (call 0))
+            (func $added (i32.const 4) (call 0))
+            (data (i32.const 65536) "tset")
+            (data (i32.const 65540) "new")
+        )"#;
+        let diff = diff(reference, other);
+        assert_eq!(diff.added_imports, vec!["seal1::foo".to_string()]);
+        assert_eq!(diff.removed_imports, vec!["seal0::foo".to_string()]);
+        assert_eq!(
+            diff.changed_functions,
+            vec![FunctionId {
+                index: 1,
+                name: Some("changed".to_string())
+            }]
+        );
+        assert_eq!(
+            diff.added_functions,
+            vec![FunctionId {
+                index: 3,
+                name: Some("added".to_string())
+            }]
+        );
+        assert!(diff.removed_functions.is_empty());
+        assert_eq!(diff.changed_data_segments, vec![0]);
+        assert_eq!(diff.added_data_segments, vec![1]);
+    }
+
+    #[test]
+    fn matches_functions_by_name_and_detects_changed_exports_and_memory() {
+        let reference = r#"
+        (module
+            (import "env" "memory" (memory 2 16))
+            (func $a (drop (i32.const 1)))
+            (func $b (drop (i32.const 2)))
+            (func $c (drop (i32.const 3)))
+            (export "call" (func $a))
+        )"#;
+        let other = r#"
+        (module
+            (import "env" "memory" (memory 2 32))
+            (func $b (drop (i32.const 2)))
+            (func $a (drop (i32.const 5)))
+            (func $d (drop (i32.const 4)))
+            (export "call" (func $a))
+            (export "deploy" (func $b))
+        )"#;
+        let diff = diff(reference, other);
+        let names = |functions: &[FunctionId]| {
+            functions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&diff.changed_functions), ["#1 (a)"]);
+        assert_eq!(names(&diff.removed_functions), ["#2 (c)"]);
+        assert_eq!(names(&diff.added_functions), ["#2 (d)"]);
+        assert_eq!(diff.added_exports, ["deploy (func)"]);
+        assert!(diff.removed_exports.is_empty());
+        assert_eq!(
+            diff.changed_memory.unwrap().to_string(),
+            "2 - 16 pages (imported) -> 2 - 32 pages (imported)"
+        );
+    }
+
+    #[test]
+    fn matches_unnamed_functions_by_body() {
+        let reference = r#"
+        (module
+            (func (drop (i32.const 1)))
+            (func (drop (i32.const 2)))
+        )"#;
+        let other = r#"
+        (module
+            (func (drop (i32.const 2)))
+            (func (drop (i32.const 1)))
+            (func (drop (i32.const 3)))
+        )"#;
+        let diff = diff(reference, other);
+        assert!(diff.changed_functions.is_empty());
+        assert!(diff.removed_functions.is_empty());
+        assert_eq!(
+            diff.added_functions,
+            vec![FunctionId {
+                index: 2,
+                name: None
+            }]
+        );
+    }
+
+    #[test]
+    fn inserted_function_does_not_change_the_callers() {
+        let reference = r#"
+        (module
+            (import "seal0" "foo" (func (param i32)))
+            (import "env" "memory" (memory 2 16))
+            (global (mut i32) (i32.const 65536))
+            (func (i32.const 1) (call 0) (call 2) (global.set 0 (i32.const 1)))
+            (func (i32.const 2) (call 0))
+        )"#;
+        let other = r#"
+        (module
+            (import "seal0" "foo" (func (param i32)))
+            (import "env" "memory" (memory 2 16))
+            (global (mut i32) (i32.const 65536))
+            (func (i32.const 3) (call 0))
+            (func (i32.const 1) (call 0) (call 3) (global.set 0 (i32.const 1)))
+            (func (i32.const 2) (call 0))
+        )"#;
+        let diff = diff(reference, other);
+        assert_eq!(
+            diff.added_functions,
+            vec![FunctionId {
+                index: 1,
+                name: None
+            }]
+        );
+        assert!(diff.changed_functions.is_empty());
+        assert!(diff.removed_functions.is_empty());
+    }
+}
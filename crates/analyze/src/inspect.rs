@@ -0,0 +1,325 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    analyze_entry_points,
+    determine_language,
+    determine_target,
+    EntryPoint,
+    KnownSelectors,
+    Module,
+    Selectors,
+    SourceTarget,
+};
+use anyhow::{
+    anyhow,
+    Result,
+};
+use polkavm_common::program::ProgramBlob;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+};
+use wasmparser::{
+    ExternalKind,
+    TypeRef,
+};
+
+/// The import module under which the host functions of a PolkaVM program are listed.
+///
+/// PolkaVM programs import host functions by their name only, without the module which
+/// denotes the API version in Wasm.
+pub const RISCV_IMPORT_MODULE: &str = "polkavm";
+
+/// A summary of contract code, to triage unknown contracts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Inspection {
+    /// The target the code was compiled for.
+    pub target: SourceTarget,
+    /// The detected source language, `None` if unrecognized.
+    pub language: Option<String>,
+    /// The size of the code in bytes.
+    pub code_size: usize,
+    /// The number of functions defined in the code, `None` for PolkaVM programs.
+    pub function_count: Option<usize>,
+    /// The imported host functions, grouped by their import module.
+    pub imports: BTreeMap<String, Vec<String>>,
+    /// The exported items.
+    pub exports: Vec<Export>,
+    /// The limits of the linear memory, `None` for PolkaVM programs and modules
+    /// without memory.
+    pub memory: Option<MemoryLimits>,
+    /// The sizes of the custom sections in bytes, by the name of the section.
+    pub custom_sections: BTreeMap<String, usize>,
+    /// The message and constructor selectors recovered from the dispatch, labeled
+    /// with the built-in [`KnownSelectors`]. `None` for PolkaVM programs and code
+    /// without the `call` and `deploy` exports.
+    pub selectors: Option<Selectors>,
+    /// The host functions reachable from the exported functions, empty for PolkaVM
+    /// programs.
+    pub entry_points: Vec<EntryPoint>,
+}
+
+/// An item exported by contract code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Export {
+    /// The name of the export.
+    pub name: String,
+    /// The kind of the exported item, e.g. `func` or `memory`.
+    pub kind: String,
+}
+
+/// The limits of the linear memory of a Wasm module, in pages of 64 KiB.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MemoryLimits {
+    /// The initial number of pages.
+    pub initial: u64,
+    /// The maximum number of pages, `None` if unlimited.
+    pub maximum: Option<u64>,
+    /// Whether the memory is imported from the host instead of defined by the module.
+    pub imported: bool,
+}
+
+impl Display for MemoryLimits {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        let maximum = self
+            .maximum
+            .map_or_else(|| "unlimited".to_string(), |max| max.to_string());
+        let origin = if self.imported { "imported" } else { "defined" };
+        write!(f, "{} - {maximum} pages ({origin})", self.initial)
+    }
+}
+
+/// Returns the name of the kind of an exported item, as in the text format.
+pub(crate) fn export_kind(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func => "func",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
+
+/// Returns the limits of the memory a Wasm module imports or defines.
+pub(crate) fn memory_limits(module: &Module) -> Option<MemoryLimits> {
+    let imported = module.import_sections.iter().find_map(|import| {
+        match import.ty {
+            TypeRef::Memory(ty) => Some((ty, true)),
+            _ => None,
+        }
+    });
+    let defined = module.memory_sections.first().map(|ty| (*ty, false));
+    defined.or(imported).map(|(ty, imported)| {
+        MemoryLimits {
+            initial: ty.initial,
+            maximum: ty.maximum,
+            imported,
+        }
+    })
+}
+
+impl Inspection {
+    /// Inspects a Wasm module or a PolkaVM program blob.
+    pub fn new(code: &[u8]) -> Result<Self> {
+        let target = determine_target(code)?;
+        let language = determine_language(code).ok().map(|lang| lang.to_string());
+        let mut inspection = match target {
+            SourceTarget::Wasm => Self::inspect_wasm(code)?,
+            SourceTarget::RiscV => Self::inspect_riscv(code)?,
+        };
+        inspection.language = language;
+        Ok(inspection)
+    }
+
+    fn inspect_wasm(code: &[u8]) -> Result<Self> {
+        let module = Module::new(code)?;
+        let mut imports = BTreeMap::<String, Vec<String>>::new();
+        for import in &module.import_sections {
+            if let TypeRef::Func(_) = import.ty {
+                imports
+                    .entry(import.module.to_string())
+                    .or_default()
+                    .push(import.name.to_string());
+            }
+        }
+        let exports = module
+            .export_sections
+            .iter()
+            .map(|export| {
+                Export {
+                    name: export.name.to_string(),
+                    kind: export_kind(export.kind).to_string(),
+                }
+            })
+            .collect();
+        let custom_sections = module
+            .custom_sections
+            .iter()
+            .map(|(name, data)| (name.to_string(), data.len()))
+            .collect();
+        let selectors = Selectors::recover(code).ok().map(|mut selectors| {
+            selectors.label(&KnownSelectors::builtin());
+            selectors
+        });
+        Ok(Self {
+            target: SourceTarget::Wasm,
+            language: None,
+            code_size: code.len(),
+            function_count: Some(module.function_sections.len()),
+            imports,
+            exports,
+            memory: memory_limits(&module),
+            custom_sections,
+            selectors,
+            entry_points: analyze_entry_points(code)?,
+        })
+    }
+
+    fn inspect_riscv(code: &[u8]) -> Result<Self> {
+        let parse_err = |err| anyhow!("Failed to parse the PolkaVM program: {err}");
+        let blob = ProgramBlob::parse(code).map_err(parse_err)?;
+        let mut functions = Vec::new();
+        for import in blob.imports() {
+            let import = import.map_err(parse_err)?;
+            functions.push(String::from_utf8_lossy(&import.symbol()[..]).into_owned());
+        }
+        let mut exports = Vec::new();
+        for export in blob.exports() {
+            let export = export.map_err(parse_err)?;
+            exports.push(Export {
+                name: String::from_utf8_lossy(&export.symbol()[..]).into_owned(),
+                kind: "func".to_string(),
+            });
+        }
+        let mut imports = BTreeMap::new();
+        if !functions.is_empty() {
+            imports.insert(RISCV_IMPORT_MODULE.to_string(), functions);
+        }
+        Ok(Self {
+            target: SourceTarget::RiscV,
+            language: None,
+            code_size: code.len(),
+            function_count: None,
+            imports,
+            exports,
+            memory: None,
+            custom_sections: BTreeMap::new(),
+            selectors: None,
+            entry_points: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inspects_wasm_module() {
+        let code = wat::parse_str(
+            r#"
+            (module
+                (type (;0;) (func))
+                (import "seal0" "input" (func (;0;) (type 0)))
+                (import "seal1" "get_storage" (func (;1;) (type 0)))
+                (import "seal0" "seal_return" (func (;2;) (type 0)))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func (;3;) (type 0))
+                (func (;4;) (type 0))
+                (export "call" (func 3))
+                (export "deploy" (func 4))
+                (@custom "producers" "rustc")
+            )"#,
+        )
+        .expect("Invalid wat.");
+
+        let inspection = Inspection::new(&code).unwrap();
+
+        assert_eq!(inspection.target, SourceTarget::Wasm);
+        assert_eq!(inspection.code_size, code.len());
+        assert_eq!(inspection.function_count, Some(2));
+        assert_eq!(
+            inspection.imports,
+            BTreeMap::from([
+                (
+                    "seal0".to_string(),
+                    vec!["input".to_string(), "seal_return".to_string()]
+                ),
+                ("seal1".to_string(), vec!["get_storage".to_string()]),
+            ])
+        );
+        assert_eq!(
+            inspection
+                .exports
+                .iter()
+                .map(|export| format!("{} ({})", export.name, export.kind))
+                .collect::<Vec<_>>(),
+            ["call (func)", "deploy (func)"]
+        );
+        assert_eq!(
+            inspection.memory,
+            Some(MemoryLimits {
+                initial: 2,
+                maximum: Some(16),
+                imported: true,
+            })
+        );
+        assert_eq!(
+            inspection.custom_sections,
+            BTreeMap::from([("producers".to_string(), 5)])
+        );
+        assert_eq!(inspection.entry_points.len(), 2);
+        assert!(inspection.entry_points[0].host_functions.is_empty());
+        // a `producers` section without a start function indicates Solidity
+        assert_eq!(inspection.language.as_deref(), Some("Solidity"));
+    }
+
+    #[test]
+    fn inspects_riscv_program() {
+        use polkavm_common::{
+            program::{
+                ProgramExport,
+                ProgramImport,
+            },
+            writer::ProgramBlobBuilder,
+        };
+        let mut builder = ProgramBlobBuilder::new();
+        builder.add_import(ProgramImport::new("input".into()));
+        builder.add_import(ProgramImport::new("value_transferred".into()));
+        builder.add_export(ProgramExport::new(0, "call".into()));
+        builder.add_export(ProgramExport::new(0, "deploy".into()));
+        let code = builder.into_vec();
+
+        let inspection = Inspection::new(&code).unwrap();
+
+        assert_eq!(inspection.target, SourceTarget::RiscV);
+        assert_eq!(inspection.language.as_deref(), Some("ink!"));
+        assert_eq!(inspection.function_count, None);
+        assert_eq!(
+            inspection.imports[RISCV_IMPORT_MODULE],
+            ["input", "value_transferred"]
+        );
+        assert_eq!(inspection.exports.len(), 2);
+        assert_eq!(inspection.memory, None);
+        assert!(Inspection::new(b"\x7fELF").is_err());
+    }
+}
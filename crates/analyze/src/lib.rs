@@ -15,21 +15,76 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 #![deny(unused_crate_dependencies)]
 
+mod call_graph;
+mod compiler;
+mod diff;
+mod inspect;
+mod selectors;
+mod symbols;
+
+pub use self::{
+    call_graph::{
+        analyze_entry_points,
+        CallGraph,
+        EntryPoint,
+        DANGEROUS_HOST_FUNCTIONS,
+    },
+    compiler::{
+        estimate_compiler,
+        CompilerEstimate,
+        Confidence,
+    },
+    diff::{
+        data_segment_offset,
+        diff_modules,
+        FunctionId,
+        MemoryChange,
+        ModuleDiff,
+    },
+    inspect::{
+        Export,
+        Inspection,
+        MemoryLimits,
+        RISCV_IMPORT_MODULE,
+    },
+    selectors::{
+        KnownSelectors,
+        RecoveredSelector,
+        Selectors,
+    },
+    symbols::{
+        DebugSymbols,
+        CODE_HASH_SECTION,
+    },
+};
 use anyhow::{
     anyhow,
     bail,
     Result,
 };
-pub use contract_metadata::Language;
+pub use contract_metadata::{
+    Language,
+    SourceTarget,
+};
+use polkavm_common::program::{
+    ProgramBlob,
+    BLOB_MAGIC,
+};
 use std::collections::HashMap;
 use wasmparser::{
+    Data,
+    Element,
+    Export as WasmExport,
     FuncType,
+    Global,
     Import,
+    MemoryType,
     Name,
     NameSectionReader,
     Operator,
     Parser,
     Payload,
+    Table,
     TypeRef,
     ValType,
 };
@@ -49,6 +104,20 @@ pub struct Module<'a> {
     pub import_sections: Vec<Import<'a>>,
     /// Code sections containing instructions only.
     pub code_sections: Vec<Vec<Operator<'a>>>,
+    /// Raw bytes of the function bodies in the code section, including locals.
+    pub function_bodies: Vec<&'a [u8]>,
+    /// Data segments of the data section.
+    pub data_sections: Vec<Data<'a>>,
+    /// Globals defined in the global section.
+    pub global_sections: Vec<Global<'a>>,
+    /// Tables defined in the table section.
+    pub table_sections: Vec<Table<'a>>,
+    /// Memories defined in the memory section.
+    pub memory_sections: Vec<MemoryType>,
+    /// Export section.
+    pub export_sections: Vec<WasmExport<'a>>,
+    /// Element segments of the element section, initializing the tables.
+    pub element_sections: Vec<Element<'a>>,
 }
 
 impl<'a> Module<'a> {
@@ -78,9 +147,11 @@ impl<'a> Module<'a> {
                     range,
                     size: _,
                 } => {
-                    let reader = wasmparser::CodeSectionReader::new(&code[range], 0)?;
+                    let section = &code[range];
+                    let reader = wasmparser::CodeSectionReader::new(section, 0)?;
                     for body in reader {
                         let body = body?;
+                        module.function_bodies.push(&section[body.range()]);
                         let reader = body.get_operators_reader();
                         let operators = reader?;
                         let ops = operators
@@ -105,6 +176,36 @@ impl<'a> Module<'a> {
                         module.function_sections.push(ty?);
                     }
                 }
+                Payload::DataSection(reader) => {
+                    for data in reader {
+                        module.data_sections.push(data?);
+                    }
+                }
+                Payload::GlobalSection(reader) => {
+                    for global in reader {
+                        module.global_sections.push(global?);
+                    }
+                }
+                Payload::TableSection(reader) => {
+                    for table in reader {
+                        module.table_sections.push(table?);
+                    }
+                }
+                Payload::MemorySection(reader) => {
+                    for memory in reader {
+                        module.memory_sections.push(memory?);
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        module.export_sections.push(export?);
+                    }
+                }
+                Payload::ElementSection(reader) => {
+                    for element in reader {
+                        module.element_sections.push(element?);
+                    }
+                }
                 _ => {}
             }
         }
@@ -130,7 +231,7 @@ impl<'a> Module<'a> {
                 for naming in name_reader {
                     let naming = naming?;
                     if naming.name.contains(name) {
-                        return Ok(true)
+                        return Ok(true);
                     }
                 }
             }
@@ -138,11 +239,43 @@ impl<'a> Module<'a> {
         Ok(false)
     }
 
+    /// Get the names of the functions from the 'name' custom section, keyed by the
+    /// function index.
+    ///
+    /// Returns an empty map if the module does not contain a name section.
+    pub fn function_names(&self) -> Result<HashMap<u32, &'a str>> {
+        let mut names = HashMap::new();
+        let Some(&name_section) = self.custom_sections.get("name") else {
+            return Ok(names);
+        };
+        let reader = NameSectionReader::new(name_section, 0);
+        for section in reader {
+            if let Name::Function(name_reader) = section? {
+                for naming in name_reader {
+                    let naming = naming?;
+                    names.insert(naming.index, naming.name);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Get the number of imported functions.
+    ///
+    /// Imported functions come first in the function index space, followed by the
+    /// functions defined in the code section.
+    pub fn imported_functions_count(&self) -> usize {
+        self.import_sections
+            .iter()
+            .filter(|entry| matches!(entry.ty, TypeRef::Func(_)))
+            .count()
+    }
+
     /// Get the function's type index from the type section.
     pub fn function_type_index(&self, function: &FuncType) -> Option<usize> {
         self.type_sections.iter().enumerate().find_map(|(i, ty)| {
             if ty == function {
-                return Some(i)
+                return Some(i);
             }
             None
         })
@@ -218,28 +351,74 @@ fn is_ink_function_present(module: &Module) -> bool {
     }
 }
 
+/// Returns the blake2 hash of the code, i.e. the code hash of a contract.
+fn code_hash(code: &[u8]) -> [u8; 32] {
+    use blake2::digest::{
+        consts::U32,
+        Digest as _,
+    };
+    let mut blake2 = blake2::Blake2b::<U32>::new();
+    blake2.update(code);
+    blake2.finalize().into()
+}
+
+/// The magic bytes at the start of a Wasm module.
+const WASM_MAGIC: [u8; 4] = [0x00, b'a', b's', b'm'];
+
+/// Detects the target a smart contract was compiled for from the magic bytes of its
+/// code.
+pub fn determine_target(code: &[u8]) -> Result<SourceTarget> {
+    if code.starts_with(&WASM_MAGIC) {
+        Ok(SourceTarget::Wasm)
+    } else if code.starts_with(&BLOB_MAGIC) {
+        Ok(SourceTarget::RiscV)
+    } else {
+        bail!("The contract code is neither a Wasm module nor a PolkaVM program.")
+    }
+}
+
 /// Detects the programming language of a smart contract from its WebAssembly (Wasm)
-/// binary code.
+/// binary code or its PolkaVM program blob.
 ///
 /// This function accepts a Wasm code as input and employs a set of heuristics to identify
 /// the contract's source language. It currently supports detection for Ink!, Solidity,
-/// and AssemblyScript languages.
+/// and AssemblyScript languages. For RISC-V contracts only ink! is detected.
 pub fn determine_language(code: &[u8]) -> Result<Language> {
+    if determine_target(code)? == SourceTarget::RiscV {
+        return determine_riscv_language(code)
+    }
     let module = Module::new(code)?;
     let start_section = module.start_section.is_some();
 
     if !start_section && module.custom_sections.keys().any(|e| e == &"producers") {
-        return Ok(Language::Solidity)
+        return Ok(Language::Solidity);
     } else if start_section
         && module
             .custom_sections
             .keys()
             .any(|e| e == &"sourceMappingURL")
     {
-        return Ok(Language::AssemblyScript)
+        return Ok(Language::AssemblyScript);
     } else if !start_section
         && (is_ink_function_present(&module)
             || matches!(module.has_function_name("ink_env"), Ok(true)))
+    {
+        return Ok(Language::Ink);
+    }
+
+    bail!("Language unsupported or unrecognized.")
+}
+
+/// Detects the programming language of a RISC-V smart contract from its PolkaVM program
+/// blob.
+///
+/// The program blob contains neither function names nor custom sections, so ink!
+/// contracts are recognized by importing the `value_transferred` host function, which
+/// the message dispatch of ink! calls to deny payments.
+fn determine_riscv_language(code: &[u8]) -> Result<Language> {
+    if riscv_imports(code)?
+        .iter()
+        .any(|import| import == "value_transferred")
     {
         return Ok(Language::Ink)
     }
@@ -247,6 +426,19 @@ pub fn determine_language(code: &[u8]) -> Result<Language> {
     bail!("Language unsupported or unrecognized.")
 }
 
+/// Returns the names of the host functions imported by a PolkaVM program blob.
+fn riscv_imports(code: &[u8]) -> Result<Vec<String>> {
+    let blob = ProgramBlob::parse(code)
+        .map_err(|err| anyhow!("Failed to parse the PolkaVM program: {err}"))?;
+    blob.imports()
+        .map(|import| {
+            let import = import
+                .map_err(|err| anyhow!("Failed to parse the PolkaVM program: {err}"))?;
+            Ok(String::from_utf8_lossy(&import.symbol()[..]).into_owned())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,4 +563,47 @@ mod tests {
             "Failed to detect AssemblyScript language."
         );
     }
+
+    /// Returns a PolkaVM program blob importing the given host functions.
+    pub(crate) fn riscv_program(imports: &[&'static str]) -> Vec<u8> {
+        use polkavm_common::{
+            program::{
+                ProgramExport,
+                ProgramImport,
+            },
+            writer::ProgramBlobBuilder,
+        };
+        let mut builder = ProgramBlobBuilder::new();
+        for import in imports {
+            builder.add_import(ProgramImport::new(import.as_bytes().into()));
+        }
+        builder.add_export(ProgramExport::new(0, "call".into()));
+        builder.add_export(ProgramExport::new(0, "deploy".into()));
+        builder.into_vec()
+    }
+
+    #[test]
+    fn determines_target() {
+        let wasm = wat::parse_str("(module)").expect("Invalid wat.");
+        let riscv = riscv_program(&[]);
+
+        assert_eq!(determine_target(&wasm).unwrap(), SourceTarget::Wasm);
+        assert_eq!(determine_target(&riscv).unwrap(), SourceTarget::RiscV);
+        assert!(determine_target(b"\x7fELF").is_err());
+    }
+
+    #[test]
+    fn determines_ink_language_of_riscv_contract() {
+        let ink = riscv_program(&["input", "value_transferred", "seal_return"]);
+        let unknown = riscv_program(&["input", "seal_return"]);
+
+        assert!(
+            matches!(determine_language(&ink), Ok(Language::Ink)),
+            "Failed to detect ink! language."
+        );
+        assert_eq!(
+            determine_language(&unknown).unwrap_err().to_string(),
+            "Language unsupported or unrecognized."
+        );
+    }
 }
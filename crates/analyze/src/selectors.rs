@@ -0,0 +1,724 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recovering the message and constructor selectors of contract code without metadata.
+//!
+//! The dispatch code of ink! loads the selector from the input as a little endian
+//! `u32` and compares it, or its bytes, against the selectors of the messages,
+//! respectively the constructors. The selectors are recovered from the constants
+//! compared in the functions reachable from the `call`, respectively the `deploy`
+//! export.
+
+use super::{
+    code_hash,
+    estimate_compiler,
+    CallGraph,
+    Language,
+    Module,
+};
+use anyhow::{
+    anyhow,
+    bail,
+    Context,
+    Result,
+};
+use contract_metadata::{
+    CodeHash,
+    Compiler,
+    Contract,
+    ContractMetadata,
+    Source,
+    SourceCompiler,
+    SourceLanguage,
+    SourceWasm,
+};
+use ink_metadata::{
+    layout::{
+        Layout,
+        LayoutKey,
+        LeafLayout,
+    },
+    ConstructorSpec,
+    ContractSpec,
+    EnvironmentSpec,
+    InkProject,
+    LangError,
+    MessageSpec,
+    ReturnTypeSpec,
+    TypeSpec,
+};
+use scale_info::{
+    form::PortableForm,
+    meta_type,
+    IntoPortable as _,
+    Registry,
+};
+use semver::Version;
+use serde::{
+    Serialize,
+    Serializer,
+};
+use std::collections::{
+    BTreeSet,
+    HashMap,
+};
+use wasmparser::{
+    ExternalKind,
+    Operator,
+};
+
+/// The labels of well-known messages and constructors, e.g. of the PSP22 and PSP34
+/// token standards.
+const BUILTIN_LABELS: &[&str] = &[
+    "new",
+    "default",
+    "flip",
+    "get",
+    "set_code",
+    "terminate",
+    "PSP22::total_supply",
+    "PSP22::balance_of",
+    "PSP22::allowance",
+    "PSP22::transfer",
+    "PSP22::transfer_from",
+    "PSP22::approve",
+    "PSP22::increase_allowance",
+    "PSP22::decrease_allowance",
+    "PSP22Metadata::token_name",
+    "PSP22Metadata::token_symbol",
+    "PSP22Metadata::token_decimals",
+    "PSP22Mintable::mint",
+    "PSP22Burnable::burn",
+    "PSP34::collection_id",
+    "PSP34::balance_of",
+    "PSP34::owner_of",
+    "PSP34::allowance",
+    "PSP34::approve",
+    "PSP34::transfer",
+    "PSP34::total_supply",
+    "Ownable::owner",
+    "Ownable::renounce_ownership",
+    "Ownable::transfer_ownership",
+];
+
+/// Constants with a smaller absolute value are lengths, offsets or error codes rather
+/// than selectors, which are hashes.
+const MIN_SELECTOR_MAGNITUDE: u32 = 1 << 16;
+
+/// A selector recovered from contract code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RecoveredSelector {
+    /// The selector.
+    #[serde(serialize_with = "serialize_selector")]
+    pub selector: [u8; 4],
+    /// The label of the message or constructor, if the selector is known.
+    pub label: Option<String>,
+}
+
+impl RecoveredSelector {
+    /// Returns the label, or the hex encoded selector if the label is unknown.
+    pub fn label_or_selector(&self) -> String {
+        self.label
+            .clone()
+            .unwrap_or_else(|| format!("0x{}", hex::encode(self.selector)))
+    }
+}
+
+/// The message and constructor selectors recovered from contract code.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Selectors {
+    /// The selectors of the constructors, compared in the `deploy` dispatch.
+    pub constructors: Vec<RecoveredSelector>,
+    /// The selectors of the messages, compared in the `call` dispatch.
+    pub messages: Vec<RecoveredSelector>,
+}
+
+impl Selectors {
+    /// Recovers the selectors from the dispatch functions of the Wasm `code`.
+    ///
+    /// Only functions exclusively reachable from one of the `call` and `deploy`
+    /// exports are searched, the helpers shared by both do not dispatch.
+    pub fn recover(code: &[u8]) -> Result<Self> {
+        let module = Module::new(code)?;
+        let export = |name: &str| {
+            module
+                .export_sections
+                .iter()
+                .find(|export| export.kind == ExternalKind::Func && export.name == name)
+                .map(|export| export.index)
+                .ok_or_else(|| anyhow!("The contract does not export `{name}`."))
+        };
+        let graph = CallGraph::new(&module)?;
+        let call = graph.reachable(export("call")?);
+        let deploy = graph.reachable(export("deploy")?);
+        let selectors = |functions: &BTreeSet<u32>, other: &BTreeSet<u32>| {
+            let mut selectors = BTreeSet::new();
+            for function in functions.difference(other) {
+                selectors.extend(compared_selectors(&module, *function));
+            }
+            selectors
+                .into_iter()
+                .map(|selector| {
+                    RecoveredSelector {
+                        selector,
+                        label: None,
+                    }
+                })
+                .collect()
+        };
+        Ok(Self {
+            constructors: selectors(&deploy, &call),
+            messages: selectors(&call, &deploy),
+        })
+    }
+
+    /// Labels the selectors found in `known`, the labels of other selectors are kept.
+    pub fn label(&mut self, known: &KnownSelectors) {
+        for recovered in self.constructors.iter_mut().chain(&mut self.messages) {
+            if let Some(label) = known.label(&recovered.selector) {
+                recovered.label = Some(label.to_string());
+            }
+        }
+    }
+
+    /// Returns skeleton metadata of the contract `name` with the recovered selectors.
+    ///
+    /// The messages and constructors take no arguments and return nothing, so they
+    /// can be used to call the contract with the raw selector. Messages with unknown
+    /// labels are named by their hex encoded selector, e.g. `0x633aa551`.
+    pub fn skeleton_metadata(&self, name: &str, code: &[u8]) -> Result<ContractMetadata> {
+        if self.constructors.is_empty() || self.messages.is_empty() {
+            bail!(
+                "Skeleton metadata requires at least one constructor and one message, \
+                {} constructors and {} messages were recovered.",
+                self.constructors.len(),
+                self.messages.len()
+            )
+        }
+        // the selector dispatch is the one of ink!, so unrecognized code is assumed
+        // to be compiled by the latest ink! version
+        let estimate = estimate_compiler(code).ok();
+        let (language, compiler, version) = match estimate.as_ref().map(|e| &e.language) {
+            Some(Language::Solidity) => {
+                (Language::Solidity, Compiler::Solang, Version::new(0, 0, 0))
+            }
+            Some(Language::AssemblyScript) => {
+                (
+                    Language::AssemblyScript,
+                    Compiler::RustC,
+                    Version::new(0, 0, 0),
+                )
+            }
+            _ => {
+                let versions = estimate.and_then(|estimate| estimate.versions);
+                let version = [5, 4, 3]
+                    .map(|major| Version::new(major, 0, 0))
+                    .into_iter()
+                    .find(|version| {
+                        versions.as_ref().map_or(true, |v| v.matches(version))
+                    })
+                    .unwrap_or(Version::new(5, 0, 0));
+                (Language::Ink, Compiler::RustC, version)
+            }
+        };
+        let source = Source::new(
+            Some(SourceWasm::new(code.to_vec())),
+            CodeHash(code_hash(code)),
+            SourceLanguage::new(language, version),
+            SourceCompiler::new(compiler, Version::new(0, 0, 0)),
+            None,
+        );
+        let contract = Contract::builder()
+            .name(name)
+            .version(Version::new(0, 0, 0))
+            .authors(["Unknown"])
+            .build()
+            .map_err(|err| anyhow!("Invalid contract section: {err}"))?;
+        let abi = serde_json::to_value(self.ink_project())?;
+        let abi = abi
+            .as_object()
+            .context("The ink! metadata is not a JSON object")?
+            .clone();
+        Ok(ContractMetadata::new(source, contract, None, None, abi))
+    }
+
+    /// Returns the ink! metadata of the recovered messages and constructors.
+    fn ink_project(&self) -> InkProject {
+        let mut registry = Registry::new();
+        // constructors and messages return `Result<(), LangError>`
+        let result = registry.register_type(&meta_type::<Result<(), LangError>>());
+        let mut type_spec = |ty| {
+            TypeSpec::<PortableForm>::new(registry.register_type(&ty), Default::default())
+        };
+        let lang_error = type_spec(meta_type::<LangError>());
+        let environment = EnvironmentSpec::new()
+            .account_id(type_spec(meta_type::<[u8; 32]>()))
+            .balance(type_spec(meta_type::<u128>()))
+            .hash(type_spec(meta_type::<[u8; 32]>()))
+            .timestamp(type_spec(meta_type::<u64>()))
+            .block_number(type_spec(meta_type::<u32>()))
+            .chain_extension(type_spec(meta_type::<()>()))
+            .max_event_topics(4)
+            .static_buffer_size(16 * 1024)
+            .done();
+        let constructors = self.constructors.iter().map(|recovered| {
+            ConstructorSpec::from_label(recovered.label_or_selector())
+                .selector(recovered.selector)
+                .payable(true)
+                .returns(ReturnTypeSpec::new(TypeSpec::new(
+                    result,
+                    Default::default(),
+                )))
+                .done()
+        });
+        let messages = self.messages.iter().map(|recovered| {
+            MessageSpec::from_label(recovered.label_or_selector())
+                .selector(recovered.selector)
+                .mutates(true)
+                .payable(true)
+                .returns(ReturnTypeSpec::new(TypeSpec::new(
+                    result,
+                    Default::default(),
+                )))
+                .done()
+        });
+        let spec = ContractSpec::new()
+            .constructors(constructors)
+            .messages(messages)
+            .lang_error(lang_error)
+            .environment(environment)
+            .done();
+        let layout = Layout::Leaf(LeafLayout::from_key::<()>(LayoutKey::new(0u32)))
+            .into_portable(&mut registry);
+        InkProject::new_portable(layout, spec, registry.into())
+    }
+}
+
+/// A database of known selectors and their labels.
+#[derive(Clone, Debug, Default)]
+pub struct KnownSelectors {
+    labels: HashMap<[u8; 4], String>,
+}
+
+impl KnownSelectors {
+    /// Returns the selectors of well-known messages and constructors, e.g. of the
+    /// PSP22 and PSP34 token standards.
+    pub fn builtin() -> Self {
+        let mut known = Self::default();
+        for label in BUILTIN_LABELS {
+            known.insert_label(label);
+        }
+        known
+    }
+
+    /// Adds a label, i.e. the name of an inherent message or constructor, or
+    /// `Trait::message` for a trait message, with the selector ink! derives from it.
+    pub fn insert_label(&mut self, label: &str) {
+        self.labels.insert(selector_of(label), label.to_string());
+    }
+
+    /// Adds a selector with its label, e.g. of a message with a custom selector.
+    pub fn insert(&mut self, selector: [u8; 4], label: &str) {
+        self.labels.insert(selector, label.to_string());
+    }
+
+    /// Adds the selectors of a database file.
+    ///
+    /// Each line contains a label, or a hex encoded selector followed by its label,
+    /// e.g. `0xcafebabe my_message`. Empty lines and lines starting with `#` are
+    /// skipped.
+    pub fn parse(&mut self, database: &str) -> Result<()> {
+        for (number, line) in database.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            match line.split_once(char::is_whitespace) {
+                Some((selector, label)) if selector.starts_with("0x") => {
+                    let selector = parse_selector(selector).with_context(|| {
+                        format!("Invalid selector in line {}", number + 1)
+                    })?;
+                    self.insert(selector, label.trim());
+                }
+                _ => self.insert_label(line),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the label of the selector, if known.
+    pub fn label(&self, selector: &[u8; 4]) -> Option<&str> {
+        self.labels.get(selector).map(String::as_str)
+    }
+}
+
+/// A value on the operand stack, as far as it is relevant for comparing the selector.
+#[derive(Clone, Debug)]
+enum Value {
+    Unknown,
+    Const(i32),
+    /// A 32 bit word, loaded by the operator at the index.
+    Word(usize),
+    /// A byte of a word, by the index of the byte in little endian order.
+    Byte(usize, usize),
+    /// Comparisons of words or bytes with constants.
+    Comparisons(Vec<Comparison>),
+}
+
+/// The comparison of a word, or of one of its bytes, with a constant.
+#[derive(Clone, Copy, Debug)]
+struct Comparison {
+    word: usize,
+    byte: Option<usize>,
+    value: i32,
+}
+
+/// Returns the selectors the function compares the input against.
+///
+/// The dispatch either compares the selector as a whole, or splits it into its bytes
+/// and compares them in a decision tree. The comparisons combined into a single
+/// branch condition form a selector, its bytes missing from the condition are
+/// compared by the enclosing branches of the tree.
+///
+/// Which message or constructor a selector dispatches to is not recovered, ink!
+/// decodes the selector into an enum and only later branches on its discriminant
+/// to the inlined message or constructor.
+pub(crate) fn compared_selectors(module: &Module, function: u32) -> Vec<[u8; 4]> {
+    let imported = module.imported_functions_count() as u32;
+    let Some(body) = function
+        .checked_sub(imported)
+        .and_then(|index| module.code_sections.get(index as usize))
+    else {
+        return Vec::new()
+    };
+
+    // the operators not relevant for the comparisons clear the stack, which loses
+    // the values below their operands but keeps the interpretation simple
+    let mut stack = Vec::new();
+    let mut locals = HashMap::new();
+    let mut conditions = Vec::new();
+    let pop = |stack: &mut Vec<Value>| stack.pop().unwrap_or(Value::Unknown);
+    for (index, operator) in body.iter().enumerate() {
+        match operator {
+            Operator::LocalGet { local_index } => {
+                let value = locals.get(local_index).cloned();
+                stack.push(value.unwrap_or(Value::Unknown));
+            }
+            Operator::LocalSet { local_index } => {
+                locals.insert(*local_index, pop(&mut stack));
+            }
+            Operator::LocalTee { local_index } => {
+                let value = stack.last().cloned().unwrap_or(Value::Unknown);
+                locals.insert(*local_index, value);
+            }
+            Operator::I32Const { value } => stack.push(Value::Const(*value)),
+            Operator::I32Load { .. } => {
+                pop(&mut stack);
+                stack.push(Value::Word(index));
+            }
+            Operator::I32ShrU
+            | Operator::I32And
+            | Operator::I32Or
+            | Operator::I32Eq
+            | Operator::I32Ne => {
+                let rhs = pop(&mut stack);
+                let lhs = pop(&mut stack);
+                stack.push(binary(operator, lhs, rhs));
+            }
+            Operator::If { .. } | Operator::BrIf { .. } => {
+                if let Value::Comparisons(comparisons) = pop(&mut stack) {
+                    conditions.push(comparisons);
+                }
+            }
+            _ => stack.clear(),
+        }
+    }
+
+    let mut selectors = Vec::new();
+    for (position, condition) in conditions.iter().enumerate() {
+        for comparison in condition.iter().filter(|c| c.byte.is_none()) {
+            selectors.push(comparison.value.to_le_bytes());
+        }
+        let Some(word) = condition.iter().find(|c| c.byte.is_some()).map(|c| c.word)
+        else {
+            continue
+        };
+        let byte_of = |condition: &[Comparison], byte| {
+            condition
+                .iter()
+                .find(|c| c.word == word && c.byte == Some(byte))
+                .map(|c| c.value as u8)
+        };
+        let mut bytes = [0, 1, 2, 3].map(|byte| byte_of(condition, byte));
+        // a condition on a single byte is a branch of the decision tree
+        if bytes.iter().flatten().count() < 2 {
+            continue
+        }
+        for (byte, value) in bytes.iter_mut().enumerate() {
+            if value.is_none() {
+                *value = conditions[..position]
+                    .iter()
+                    .rev()
+                    .filter(|branch| branch.len() == 1)
+                    .find_map(|branch| byte_of(branch, byte));
+            }
+        }
+        if let [Some(b0), Some(b1), Some(b2), Some(b3)] = bytes {
+            selectors.push([b0, b1, b2, b3]);
+        }
+    }
+    selectors
+}
+
+/// Applies the binary operator to the operands.
+fn binary(operator: &Operator, lhs: Value, rhs: Value) -> Value {
+    // the constant is the right operand of the commutative operators
+    let (lhs, rhs) = match (operator, lhs, rhs) {
+        (Operator::I32ShrU, lhs, rhs) => (lhs, rhs),
+        (_, Value::Const(value), rhs) => (rhs, Value::Const(value)),
+        (_, lhs, rhs) => (lhs, rhs),
+    };
+    match (operator, lhs, rhs) {
+        (Operator::I32ShrU, Value::Word(word), Value::Const(shift @ (8 | 16 | 24))) => {
+            Value::Byte(word, shift as usize / 8)
+        }
+        (Operator::I32And, Value::Word(word), Value::Const(0xff)) => Value::Byte(word, 0),
+        (Operator::I32And, Value::Byte(word, byte), Value::Const(0xff)) => {
+            Value::Byte(word, byte)
+        }
+        (
+            Operator::I32Eq | Operator::I32Ne,
+            Value::Byte(word, byte),
+            Value::Const(value @ 0..=0xff),
+        ) => {
+            Value::Comparisons(vec![Comparison {
+                word,
+                byte: Some(byte),
+                value,
+            }])
+        }
+        (Operator::I32Eq | Operator::I32Ne, Value::Word(word), Value::Const(value))
+            if value.unsigned_abs() >= MIN_SELECTOR_MAGNITUDE =>
+        {
+            Value::Comparisons(vec![Comparison {
+                word,
+                byte: None,
+                value,
+            }])
+        }
+        (
+            Operator::I32And | Operator::I32Or,
+            Value::Comparisons(mut lhs),
+            Value::Comparisons(rhs),
+        ) => {
+            lhs.extend(rhs);
+            Value::Comparisons(lhs)
+        }
+        _ => Value::Unknown,
+    }
+}
+
+/// Returns the selector ink! derives from the label, i.e. the first four bytes of its
+/// blake2 hash.
+fn selector_of(label: &str) -> [u8; 4] {
+    let hash = code_hash(label.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn parse_selector(input: &str) -> Result<[u8; 4]> {
+    let bytes = hex::decode(input.trim_start_matches("0x"))
+        .map_err(|_| anyhow!("Invalid hex in selector `{input}`"))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("A selector must be 4 bytes long, got `{input}`"))
+}
+
+fn serialize_selector<S>(selector: &[u8; 4], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format!("0x{}", hex::encode(selector)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dispatch of the selectors of `flip` and `get` in `call`, byte by byte as
+    /// compiled from ink!, and of `new` in `deploy`, as a whole.
+    const CODE: &str = r#"
+        (module
+            (type (;0;) (func))
+            (type (;1;) (func (param i32) (result i32)))
+            (import "seal0" "input" (func (;0;) (type 0)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (func (;1;) (type 0)
+                i32.const 0
+                call 3
+                drop)
+            (func (;2;) (type 0)
+                block
+                    i32.const 0
+                    i32.load
+                    i32.const 1587392155
+                    i32.ne
+                    br_if 0
+                end
+                call 4)
+            (func (;3;) (type 1) (param i32) (result i32) (local i32 i32 i32 i32)
+                call 4
+                block
+                    local.get 0
+                    i32.load
+                    local.tee 1
+                    i32.const 24
+                    i32.shr_u
+                    local.set 2
+                    local.get 1
+                    i32.const 16
+                    i32.shr_u
+                    local.set 3
+                    local.get 1
+                    i32.const 8
+                    i32.shr_u
+                    local.set 4
+                    local.get 1
+                    i32.const 255
+                    i32.and
+                    local.tee 1
+                    i32.const 99
+                    i32.ne
+                    if
+                        local.get 4
+                        i32.const 255
+                        i32.and
+                        i32.const 134
+                        i32.ne
+                        local.get 3
+                        i32.const 255
+                        i32.and
+                        i32.const 91
+                        i32.ne
+                        i32.or
+                        local.get 2
+                        i32.const 217
+                        i32.ne
+                        i32.or
+                        local.get 1
+                        i32.const 47
+                        i32.ne
+                        i32.or
+                        br_if 1
+                        i32.const 1
+                        return
+                    end
+                    local.get 4
+                    i32.const 255
+                    i32.and
+                    i32.const 58
+                    i32.ne
+                    local.get 3
+                    i32.const 255
+                    i32.and
+                    i32.const 165
+                    i32.ne
+                    i32.or
+                    local.get 2
+                    i32.const 81
+                    i32.ne
+                    i32.or
+                    br_if 0
+                    i32.const 2
+                    return
+                end
+                i32.const 0)
+            (func (;4;) (type 0)
+                block
+                    i32.const 0
+                    i32.load
+                    i32.const 305419896
+                    i32.eq
+                    br_if 0
+                end)
+            (export "call" (func 1))
+            (export "deploy" (func 2))
+        )"#;
+
+    #[test]
+    fn recovers_selectors_of_dispatch() {
+        let code = wat::parse_str(CODE).expect("Invalid wat.");
+
+        let mut selectors = Selectors::recover(&code).unwrap();
+        selectors.label(&KnownSelectors::builtin());
+
+        let labels = |selectors: &[RecoveredSelector]| {
+            selectors
+                .iter()
+                .map(RecoveredSelector::label_or_selector)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(&selectors.constructors), ["new"]);
+        assert_eq!(labels(&selectors.messages), ["get", "flip"]);
+        assert_eq!(selectors.messages[1].selector, [0x63, 0x3a, 0xa5, 0x51]);
+    }
+
+    #[test]
+    fn parses_selector_database() {
+        let mut known = KnownSelectors::default();
+        known
+            .parse("# custom\nPSP22::transfer\n0x633aa551 toggle\n")
+            .unwrap();
+
+        assert_eq!(known.label(&[0x63, 0x3a, 0xa5, 0x51]), Some("toggle"));
+        assert_eq!(
+            known.label(&selector_of("PSP22::transfer")),
+            Some("PSP22::transfer")
+        );
+        assert!(known.parse("0x1234 short").is_err());
+        assert!(known.parse("0x€€ab non_ascii").is_err());
+    }
+
+    #[test]
+    fn skeleton_metadata_encodes_raw_calls() {
+        let code = wat::parse_str(CODE).expect("Invalid wat.");
+        let mut selectors = Selectors::recover(&code).unwrap();
+        selectors.messages[0].label = Some("my_get".to_string());
+
+        let metadata = selectors.skeleton_metadata("unknown", &code).unwrap();
+        let project: InkProject =
+            serde_json::from_value(serde_json::Value::Object(metadata.abi)).unwrap();
+
+        let labels: Vec<_> = project
+            .spec()
+            .messages()
+            .iter()
+            .map(|message| {
+                (
+                    message.label().as_str(),
+                    message.selector().to_bytes().to_vec(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                ("my_get", vec![0x2f, 0x86, 0x5b, 0xd9]),
+                ("0x633aa551", vec![0x63, 0x3a, 0xa5, 0x51])
+            ]
+        );
+        assert_eq!(metadata.source.hash.0, code_hash(&code));
+    }
+}
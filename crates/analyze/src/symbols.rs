@@ -0,0 +1,160 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{
+    code_hash,
+    FunctionId,
+    Module,
+};
+use anyhow::{
+    anyhow,
+    bail,
+    Result,
+};
+use std::collections::HashMap;
+
+/// The custom section of a debug file which contains the code hash of the deployable
+/// code it belongs to.
+pub const CODE_HASH_SECTION: &str = "contract_code_hash";
+
+/// The debug symbols of deployed contract code, read from the `<name>.debug.wasm`
+/// companion file written by the build.
+///
+/// The deployed code and the debug file share the function index space, so a function
+/// index from e.g. a trap in the deployed code resolves to the name of the function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugSymbols {
+    /// The code hash of the deployable code.
+    pub code_hash: [u8; 32],
+    /// The function names, keyed by the function index.
+    pub function_names: HashMap<u32, String>,
+}
+
+impl DebugSymbols {
+    /// Reads the debug symbols from the debug file.
+    pub fn new(debug_code: &[u8]) -> Result<Self> {
+        let module = Module::new(debug_code)?;
+        let code_hash =
+            module
+                .custom_sections
+                .get(CODE_HASH_SECTION)
+                .ok_or_else(|| {
+                    anyhow!(
+                    "Custom section '{CODE_HASH_SECTION}' not found, not a debug file."
+                )
+                })?;
+        let code_hash = <[u8; 32]>::try_from(*code_hash)
+            .map_err(|_| anyhow!("Invalid code hash in '{CODE_HASH_SECTION}'."))?;
+        let function_names = module
+            .function_names()?
+            .into_iter()
+            .map(|(index, name)| (index, name.to_string()))
+            .collect();
+        Ok(Self {
+            code_hash,
+            function_names,
+        })
+    }
+
+    /// Reads the debug symbols from the debug file, ensuring that they belong to the
+    /// deployed `code`.
+    pub fn for_code(code: &[u8], debug_code: &[u8]) -> Result<Self> {
+        let symbols = Self::new(debug_code)?;
+        let code_hash = code_hash(code);
+        if symbols.code_hash != code_hash {
+            bail!(
+                "The debug file belongs to the code with hash 0x{}, not to the code with \
+                hash 0x{}.",
+                hex::encode(symbols.code_hash),
+                hex::encode(code_hash)
+            );
+        }
+        Ok(symbols)
+    }
+
+    /// Returns the name of the function with the given index, if known.
+    pub fn function_name(&self, index: u32) -> Option<&str> {
+        self.function_names.get(&index).map(String::as_str)
+    }
+
+    /// Returns the function with the given index, named if the name is known.
+    pub fn function_id(&self, index: u32) -> FunctionId {
+        FunctionId {
+            index,
+            name: self.function_name(index).map(ToString::to_string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = r#"
+        (module
+            (type (;0;) (func))
+            (import "seal0" "foo" (func (;0;) (type 0)))
+            (import "env" "memory" (memory (;0;) 2 16))
+            (func (;1;) (type 0) call 2)
+            (func (;2;) (type 0) unreachable)
+            (export "call" (func 1))
+            (export "deploy" (func 1))
+        )"#;
+
+    /// Returns the debug file of `CODE` with function names, linked to `code_hash`.
+    fn debug_file(code_hash: [u8; 32]) -> Vec<u8> {
+        let code_hash: String = code_hash.iter().map(|b| format!("\\{b:02x}")).collect();
+        let debug_code = format!(
+            r#"
+            (module
+                (type (;0;) (func))
+                (import "seal0" "foo" (func (;0;) (type 0)))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func $call (type 0) call $panic)
+                (func $panic (type 0) unreachable)
+                (export "call" (func $call))
+                (export "deploy" (func $call))
+                (@custom "{CODE_HASH_SECTION}" "{code_hash}")
+            )"#
+        );
+        wat::parse_str(debug_code).expect("Invalid wat.")
+    }
+
+    #[test]
+    fn resolves_function_names_of_deployed_code() {
+        let code = wat::parse_str(CODE).expect("Invalid wat.");
+        let debug_code = debug_file(code_hash(&code));
+
+        let symbols = DebugSymbols::for_code(&code, &debug_code).unwrap();
+
+        assert_eq!(symbols.function_name(1), Some("call"));
+        assert_eq!(symbols.function_id(2).to_string(), "#2 (panic)");
+        assert_eq!(symbols.function_name(3), None);
+    }
+
+    #[test]
+    fn rejects_debug_file_of_other_code() {
+        let code = wat::parse_str(CODE).expect("Invalid wat.");
+        let debug_code = debug_file([0; 32]);
+
+        let err = DebugSymbols::for_code(&code, &debug_code).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .starts_with("The debug file belongs to the code"));
+        assert!(DebugSymbols::new(&code).is_err());
+    }
+}
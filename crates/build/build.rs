@@ -57,22 +57,36 @@ fn main() {
     }
 }
 
-/// Creates a zip archive `template.zip` of the `new` project template in `out_dir`.
+/// Creates a zip archive `templates/<name>.zip` in `out_dir` for each of the `new`
+/// project templates.
 fn zip_template(manifest_dir: &Path, out_dir: &Path) -> Result<()> {
-    let template_dir = manifest_dir.join("templates").join("new");
-    let template_dst_file = out_dir.join("template.zip");
-    println!(
-        "Creating template zip: template_dir '{}', destination archive '{}'",
-        template_dir.display(),
-        template_dst_file.display()
-    );
-    zip_dir(&template_dir, &template_dst_file, CompressionMethod::Stored).map(|_| {
+    let templates_dir = manifest_dir.join("templates").join("new");
+    let templates_dst_dir = out_dir.join("templates");
+    std::fs::create_dir_all(&templates_dst_dir)?;
+
+    for entry in std::fs::read_dir(&templates_dir)? {
+        let template_dir = entry?.path();
+        if !template_dir.is_dir() {
+            continue
+        }
+        let template_name = template_dir
+            .file_name()
+            .expect("template directories have a name")
+            .to_string_lossy();
+        let template_dst_file = templates_dst_dir.join(format!("{template_name}.zip"));
+        println!(
+            "Creating template zip: template_dir '{}', destination archive '{}'",
+            template_dir.display(),
+            template_dst_file.display()
+        );
+        zip_dir(&template_dir, &template_dst_file, CompressionMethod::Stored)?;
         println!(
             "Done: {} written to {}",
             template_dir.display(),
             template_dst_file.display()
         );
-    })
+    }
+    Ok(())
 }
 
 /// Creates a zip archive at `dst_file` with the content of the `src_dir`.
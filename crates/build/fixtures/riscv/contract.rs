@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+#[polkavm_derive::polkavm_import]
+extern "C" {
+    fn input(buf_ptr: *mut u8, buf_len_ptr: *mut u32);
+    fn seal_return(flags: u32, data_ptr: *const u8, data_len: u32);
+}
+
+#[polkavm_derive::polkavm_export]
+pub extern "C" fn deploy() {}
+
+#[polkavm_derive::polkavm_export]
+pub extern "C" fn call() {
+    let mut buffer = [0u8; 32];
+    let mut len = buffer.len() as u32;
+    unsafe {
+        input(buffer.as_mut_ptr(), &mut len);
+        seal_return(0, buffer.as_ptr(), len);
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    unsafe { core::arch::asm!("unimp", options(noreturn)) }
+}
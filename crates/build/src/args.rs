@@ -16,6 +16,7 @@
 
 use anyhow::Result;
 use clap::Args;
+use contract_metadata::SourceTarget;
 use std::{
     convert::TryFrom,
     fmt,
@@ -129,6 +130,37 @@ impl BuildArtifacts {
     }
 }
 
+/// The format of the software bill of materials of a contract build.
+#[derive(
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    Debug,
+    clap::ValueEnum,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SbomFormat {
+    /// CycloneDX JSON
+    #[clap(name = "cyclonedx")]
+    CycloneDx,
+    /// SPDX JSON
+    #[clap(name = "spdx")]
+    Spdx,
+}
+
+impl SbomFormat {
+    /// The extension of the SBOM file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::CycloneDx => "cdx.json",
+            Self::Spdx => "spdx.json",
+        }
+    }
+}
+
 /// The list of targets that ink! supports.
 #[derive(
     Eq,
@@ -186,6 +218,24 @@ impl Target {
     }
 }
 
+impl From<Target> for SourceTarget {
+    fn from(target: Target) -> Self {
+        match target {
+            Target::Wasm => SourceTarget::Wasm,
+            Target::RiscV => SourceTarget::RiscV,
+        }
+    }
+}
+
+impl From<SourceTarget> for Target {
+    fn from(target: SourceTarget) -> Self {
+        match target {
+            SourceTarget::Wasm => Target::Wasm,
+            SourceTarget::RiscV => Target::RiscV,
+        }
+    }
+}
+
 /// The mode to build the contract in.
 #[derive(
     Eq, PartialEq, Copy, Clone, Debug, Default, serde::Serialize, serde::Deserialize,
@@ -265,6 +315,11 @@ impl Features {
         self.features.push(feature.to_owned())
     }
 
+    /// Returns an iterator over the features.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.features.iter().map(String::as_str)
+    }
+
     /// Appends the raw features args to pass through to the `cargo` invocation.
     pub fn append_to_args(&self, args: &mut Vec<String>) {
         if !self.features.is_empty() {
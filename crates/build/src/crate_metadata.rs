@@ -163,6 +163,13 @@ impl CrateMetadata {
         let fname_bundle = format!("{}.contract", self.contract_artifact_name);
         target_directory.join(fname_bundle)
     }
+
+    /// Get the path of the debug companion of the contract Wasm, containing the debug
+    /// symbols stripped from the deployable code.
+    pub fn debug_code_path(&self) -> PathBuf {
+        let debug_file = format!("{}.debug.wasm", self.contract_artifact_name);
+        self.target_directory.join(debug_file)
+    }
 }
 
 /// Get the result of `cargo metadata`, together with the root package id.
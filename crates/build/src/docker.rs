@@ -32,6 +32,10 @@
 //! 5. After the build, the docker container produces metadata with
 //! paths relative to its internal storage structure, we parse the file
 //! and overwrite those paths relative to the host machine.
+//!
+//! If a git revision is given, the revision is checked out into a temporary
+//! directory which is mounted instead of the working directory. The resulting
+//! artifacts are copied into the target directory of the host project afterwards.
 
 use std::{
     cmp::Ordering,
@@ -48,7 +52,10 @@ use std::{
         Write,
     },
     marker::Unpin,
-    path::Path,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 
 use anyhow::{
@@ -61,6 +68,7 @@ use bollard::{
         AttachContainerResults,
         Config,
         CreateContainerOptions,
+        InspectContainerOptions,
         ListContainersOptions,
         LogOutput,
         RemoveContainerOptions,
@@ -69,6 +77,7 @@ use bollard::{
     image::{
         CreateImageOptions,
         ListImagesOptions,
+        RemoveImageOptions,
     },
     models::CreateImageInfo,
     service::{
@@ -79,6 +88,7 @@ use bollard::{
     },
     Docker,
 };
+use chrono::DateTime;
 use contract_metadata::ContractMetadata;
 use tokio_stream::{
     Stream,
@@ -86,10 +96,13 @@ use tokio_stream::{
 };
 
 use crate::{
+    git,
     verbose_eprintln,
+    BuildInfo,
     BuildResult,
     CrateMetadata,
     ExecuteArgs,
+    GitInfo,
     Verbosity,
 };
 
@@ -100,6 +113,8 @@ const IMAGE: &str = "useink/contracts-verifiable";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 /// The default directory to be mounted in the container.
 const MOUNT_DIR: &str = "/contract";
+/// The prefix of the names of the containers created for the build.
+const CONTAINER_PREFIX: &str = "ink-verified-";
 
 /// The image to be used.
 #[derive(Clone, Debug, Default)]
@@ -121,6 +136,16 @@ impl From<Option<String>> for ImageVariant {
     }
 }
 
+impl ImageVariant {
+    /// The full name of the image, including its tag.
+    pub fn image_name(&self) -> String {
+        match self {
+            ImageVariant::Custom(image) => image.clone(),
+            ImageVariant::Default => format!("{}:{}", IMAGE, VERSION),
+        }
+    }
+}
+
 /// Launches the docker container to execute verifiable build.
 pub fn docker_build(args: ExecuteArgs) -> Result<BuildResult> {
     let ExecuteArgs {
@@ -129,6 +154,7 @@ pub fn docker_build(args: ExecuteArgs) -> Result<BuildResult> {
         output_type,
         target,
         image,
+        from_git,
         ..
     } = args;
     tokio::runtime::Builder::new_multi_thread()
@@ -136,41 +162,91 @@ pub fn docker_build(args: ExecuteArgs) -> Result<BuildResult> {
         .build()?
         .block_on(async {
             let crate_metadata = CrateMetadata::collect(&manifest_path, target)?;
-            let host_folder = std::env::current_dir()?;
+            let host_folder = std::env::current_dir()?.canonicalize()?;
             let args = compose_build_args()?;
 
-            let client = Docker::connect_with_socket_defaults().map_err(|e| {
-                anyhow::anyhow!("{}\nDo you have the docker engine installed in path?", e)
-            })?;
-            let _ = client.ping().await.map_err(|e| {
-                anyhow::anyhow!("{}\nIs your docker engine up and running?", e)
-            })?;
-
-            let image = match image {
-                ImageVariant::Custom(i) => i.clone(),
-                ImageVariant::Default => {
-                    format!("{}:{}", IMAGE, VERSION)
+            let git_root = git::repository_root(&host_folder).ok();
+            let (checkout, git_info) = match (&from_git, &git_root) {
+                (Some(rev), Some(root)) => {
+                    let git_info = git::git_info(root, Some(rev))?;
+                    verbose_eprintln!(
+                        verbosity,
+                        " {} {}",
+                        "[==]".bold(),
+                        format!("Checking out the git commit {}", git_info.commit)
+                            .bright_cyan()
+                            .bold()
+                    );
+                    let checkout = tempfile::Builder::new()
+                        .prefix("cargo-contract_git_")
+                        .tempdir()?;
+                    git::checkout(root, &git_info.commit, &checkout.path().join("repo"))?;
+                    (Some(checkout), Some(git_info))
+                }
+                (Some(_), None) => {
+                    anyhow::bail!(
+                        "Building from a git revision requires the project to be inside \
+                        a git repository."
+                    )
+                }
+                (None, Some(root)) => (None, git::git_info(root, None).ok()),
+                (None, None) => (None, None),
+            };
+            // the folder mounted into the container, this is either the working
+            // directory or the same directory inside the clean checkout
+            let build_folder = match (&checkout, &git_root) {
+                (Some(checkout), Some(root)) => {
+                    checkout
+                        .path()
+                        .join("repo")
+                        .join(host_folder.strip_prefix(root)?)
                 }
+                _ => host_folder.clone(),
             };
 
+            let client = connect().await?;
+
+            let image = image.image_name();
+
             let container = create_container(
                 &client,
                 args.clone(),
                 &image,
                 &crate_metadata.contract_artifact_name,
-                &host_folder,
+                &build_folder,
+                checkout.is_some(),
                 &verbosity,
             )
             .await?;
 
             let build_result = async {
                 let mut build_result = run_build(&client, &container, &verbosity).await?;
-                update_build_result(&host_folder, &mut build_result)?;
-                update_metadata(&build_result, &verbosity, &image, &client).await?;
+                update_build_result(&build_folder, &mut build_result)?;
+                if checkout.is_some() {
+                    copy_artifacts(&crate_metadata.target_directory, &mut build_result)?;
+                }
+                update_metadata(
+                    &build_result,
+                    &verbosity,
+                    &image,
+                    git_info.as_ref(),
+                    &client,
+                )
+                .await?;
                 Ok::<BuildResult, anyhow::Error>(build_result)
             }
             .await;
 
+            // the container of a build from a clean checkout mounts a temporary
+            // directory, so it can not be reused
+            if checkout.is_some() {
+                let options = Some(RemoveContainerOptions {
+                    force: true,
+                    ..Default::default()
+                });
+                let _ = client.remove_container(&container, options).await;
+            }
+
             let build_result = match build_result {
                 Ok(build_result) => build_result,
                 Err(e) => {
@@ -219,6 +295,14 @@ fn update_build_result(host_folder: &Path, build_result: &mut BuildResult) -> Re
     });
     build_result.dest_wasm = new_path;
 
+    build_result.dest_debug_wasm = build_result.dest_debug_wasm.as_ref().map(|p| {
+        host_folder.join(
+            p.as_path()
+                .strip_prefix(MOUNT_DIR)
+                .expect("cannot strip prefix"),
+        )
+    });
+
     build_result.metadata_result.as_mut().map(|m| {
         m.dest_bundle = host_folder.join(
             m.dest_bundle
@@ -234,14 +318,58 @@ fn update_build_result(host_folder: &Path, build_result: &mut BuildResult) -> Re
         );
         m
     });
+
+    build_result.sbom = build_result.sbom.as_ref().map(|p| {
+        host_folder.join(
+            p.as_path()
+                .strip_prefix(MOUNT_DIR)
+                .expect("cannot strip prefix"),
+        )
+    });
+    Ok(())
+}
+
+/// Copies the artefacts into the `target_directory` of the host project and updates
+/// the `build_result` paths accordingly.
+fn copy_artifacts(target_directory: &Path, build_result: &mut BuildResult) -> Result<()> {
+    std::fs::create_dir_all(target_directory)?;
+    let copy = |path: &mut PathBuf| -> Result<()> {
+        let dest = target_directory.join(
+            path.file_name()
+                .context("Artefact path does not contain a file name")?,
+        );
+        std::fs::copy(&path, &dest)
+            .context(format!("Failed to copy artefact {}", path.display()))?;
+        *path = dest;
+        Ok(())
+    };
+
+    if let Some(dest_wasm) = build_result.dest_wasm.as_mut() {
+        copy(dest_wasm)?;
+    }
+    if let Some(dest_debug_wasm) = build_result.dest_debug_wasm.as_mut() {
+        copy(dest_debug_wasm)?;
+    }
+    if let Some(m) = build_result.metadata_result.as_mut() {
+        copy(&mut m.dest_bundle)?;
+        copy(&mut m.dest_metadata)?;
+    }
+    if let Some(sbom) = build_result.sbom.as_mut() {
+        copy(sbom)?;
+    }
+    build_result.target_directory = target_directory.to_path_buf();
     Ok(())
 }
 
 /// Overwrites `build_result` and `image` fields in the metadata.
+///
+/// If the project is inside a git repository, the `git` information is added to the
+/// `build_info` as well.
 async fn update_metadata(
     build_result: &BuildResult,
     verbosity: &Verbosity,
     build_image: &str,
+    git: Option<&GitInfo>,
     client: &Docker,
 ) -> Result<()> {
     if let Some(metadata_artifacts) = &build_result.metadata_result {
@@ -262,6 +390,17 @@ async fn update_metadata(
 
         metadata.image = Some(image_tag);
 
+        if let Some(git) = git {
+            let build_info = metadata
+                .source
+                .build_info
+                .take()
+                .context("The metadata does not contain any build information")?;
+            let mut build_info: BuildInfo = serde_json::from_value(build_info.into())?;
+            build_info.git = Some(git.clone());
+            metadata.source.build_info = Some(build_info.try_into()?);
+        }
+
         crate::metadata::write_metadata(metadata_artifacts, metadata, verbosity, true)?;
     }
     Ok(())
@@ -286,12 +425,15 @@ async fn find_local_image(
 /// Creates the container, returning the container id if successful.
 ///
 /// If the image is not available locally, it will be pulled from the registry.
+/// The container of a clean `checkout` is named after the mounted folder, so it is
+/// not reused for another checkout.
 async fn create_container(
     client: &Docker,
     mut build_args: Vec<String>,
     build_image: &str,
     contract_name: &str,
     host_folder: &Path,
+    checkout: bool,
     verbosity: &Verbosity,
 ) -> Result<String> {
     let entrypoint = vec!["cargo".to_string(), "contract".to_string()];
@@ -304,9 +446,17 @@ async fn create_container(
 
     cmd.append(&mut build_args);
 
-    let digest_code = container_digest(cmd.clone(), build_image.to_string());
-    let container_name =
-        format!("ink-verified-{}-{}", contract_name, digest_code.clone());
+    let digest_code = container_digest(
+        cmd.clone(),
+        build_image.to_string(),
+        checkout.then_some(host_folder),
+    );
+    let container_name = format!(
+        "{}{}-{}",
+        CONTAINER_PREFIX,
+        contract_name,
+        digest_code.clone()
+    );
 
     let mut filters = HashMap::new();
     filters.insert("name".to_string(), vec![container_name.clone()]);
@@ -468,34 +618,53 @@ async fn run_build(
     }
 }
 
+/// Options of the host command which are not passed on to the build inside the
+/// container, together with their value.
+const HOST_OPTIONS: &[&str] = &[
+    "--image",
+    "--from-git",
+    "--contract",
+    "--wasm",
+    "--address",
+    "--url",
+    "--config",
+    "--chain",
+];
+
+/// Flags of the host command which are not passed on to the build inside the
+/// container.
+const HOST_FLAGS: &[&str] = &["--verifiable", "--output-json"];
+
 /// Takes CLI args from the host and appends them to the build command inside the docker.
 fn compose_build_args() -> Result<Vec<String>> {
-    use regex::Regex;
-    let mut args: Vec<String> = Vec::new();
-    // match `--image` or `verify` with arg with 1 or more white spaces surrounded
-    let rex = Regex::new(r#"(--image|verify)[ ]*[^ ]*[ ]*"#)?;
-    // we join the args together, so we can remove `--image <arg>`
-    let args_string: String = std::env::args().collect::<Vec<String>>().join(" ");
-    let args_string = rex.replace_all(&args_string, "").to_string();
-
-    // and then we turn it back to the vec, filtering out commands and arguments
-    // that should not be passed to the docker build command
-    let mut os_args: Vec<String> = args_string
-        .split_ascii_whitespace()
-        .filter(|a| {
-            a != &"--verifiable"
-                && !a.contains("cargo-contract")
-                && a != &"cargo"
-                && a != &"contract"
-                && a != &"build"
-                && a != &"--output-json"
-        })
-        .map(|s| s.to_string())
-        .collect();
+    Ok(filter_build_args(std::env::args().skip(1)))
+}
 
-    args.append(&mut os_args);
+/// Removes the `contract` and `build` or `verify` subcommands as well as the host
+/// options from the command line arguments, without the binary name.
+fn filter_build_args<I>(args: I) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+    args.next_if(|arg| arg == "contract");
+    args.next_if(|arg| arg == "build" || arg == "verify");
 
-    Ok(args)
+    let mut build_args = Vec::new();
+    while let Some(arg) = args.next() {
+        let option = arg
+            .split_once('=')
+            .map_or(arg.as_str(), |(option, _)| option);
+        if HOST_OPTIONS.contains(&option) {
+            if !arg.contains('=') {
+                // skip the value
+                args.next();
+            }
+        } else if !HOST_FLAGS.contains(&arg.as_str()) {
+            build_args.push(arg)
+        }
+    }
+    build_args
 }
 
 /// Pulls the docker image from the registry.
@@ -594,16 +763,276 @@ async fn show_pull_progress(
 }
 
 /// Calculates the unique container's code.
-fn container_digest(entrypoint: Vec<String>, image_digest: String) -> String {
+fn container_digest(
+    entrypoint: Vec<String>,
+    image_digest: String,
+    checkout_folder: Option<&Path>,
+) -> String {
     // in order to optimise the container usage
     // we are hashing the inputted command
     // in order to reuse the container for the same permutation of arguments
     let mut s = DefaultHasher::new();
-    // the data is set of commands and args and the image digest
-    let data = (entrypoint, image_digest);
-    data.hash(&mut s);
+    // the data is set of commands and args and the image digest, as well as the
+    // mounted folder of a clean checkout. The digest of other builds is unchanged, so
+    // their existing containers and cargo caches are reused.
+    match checkout_folder {
+        None => (entrypoint, image_digest).hash(&mut s),
+        Some(folder) => (entrypoint, image_digest, folder).hash(&mut s),
+    }
     let digest = s.finish();
     // taking the first 5 digits to be a unique identifier
     let digest_code: String = digest.to_string().chars().take(5).collect();
     digest_code
 }
+
+/// Connects to the local docker engine.
+async fn connect() -> Result<Docker> {
+    let client = Docker::connect_with_socket_defaults().map_err(|e| {
+        anyhow::anyhow!("{}\nDo you have the docker engine installed in path?", e)
+    })?;
+    let _ = client
+        .ping()
+        .await
+        .map_err(|e| anyhow::anyhow!("{}\nIs your docker engine up and running?", e))?;
+    Ok(client)
+}
+
+/// Runs `f` with a client connected to the local docker engine.
+fn with_client<F, Fut, T>(f: F) -> Result<T>
+where
+    F: FnOnce(Docker) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(async { f(connect().await?).await })
+}
+
+/// Checks that the local docker engine is reachable.
+pub(crate) fn check_connection() -> Result<()> {
+    with_client(|_| async { Ok(()) })
+}
+
+/// A container created by a verifiable build.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BuildContainer {
+    /// The name of the container.
+    pub name: String,
+    /// The name of the contract artifact the container builds.
+    pub contract: String,
+    /// The digest of the build command and image, identifying the container.
+    pub digest: String,
+    /// The host directory mounted into the container.
+    pub project_path: Option<String>,
+    /// The image the container was created from.
+    pub image: String,
+    /// The human readable status of the container, as reported by docker.
+    pub status: String,
+    /// Whether the container is running, i.e. a build is in progress.
+    pub running: bool,
+    /// The unix timestamp in seconds of the end of the last build in the container,
+    /// `None` if no build has finished yet.
+    pub last_used: Option<u64>,
+}
+
+/// An image used by verifiable builds.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct BuildImage {
+    /// The id of the image.
+    pub id: String,
+    /// The repository tags of the image.
+    pub tags: Vec<String>,
+    /// The size of the image in bytes.
+    pub size: i64,
+    /// The unix timestamp in seconds of the creation of the image.
+    pub created: i64,
+    /// The names of the build containers using the image.
+    pub containers: Vec<String>,
+}
+
+/// Lists all containers created by verifiable builds.
+pub fn list_build_containers() -> Result<Vec<BuildContainer>> {
+    with_client(|client| async move { build_containers(&client).await })
+}
+
+/// Lists the default verifiable build images and all images used by build containers.
+pub fn list_build_images() -> Result<Vec<BuildImage>> {
+    with_client(|client| {
+        async move {
+            let containers = build_containers(&client).await?;
+            build_images(&client, &containers).await
+        }
+    })
+}
+
+/// Removes the build containers with the given names.
+///
+/// Running containers are only stopped and removed with `force`, otherwise docker
+/// refuses to remove them.
+pub fn remove_build_containers(names: &[String], force: bool) -> Result<()> {
+    with_client(|client| {
+        async move {
+            for name in names {
+                let options = Some(RemoveContainerOptions {
+                    force,
+                    ..Default::default()
+                });
+                client
+                    .remove_container(name, options)
+                    .await
+                    .context(format!("Failed to remove container {name}"))?;
+            }
+            Ok(())
+        }
+    })
+}
+
+/// Removes the images with the given ids or tags.
+pub fn remove_build_images(images: &[String]) -> Result<()> {
+    with_client(|client| {
+        async move {
+            for image in images {
+                let options = Some(RemoveImageOptions {
+                    force: true,
+                    ..Default::default()
+                });
+                client
+                    .remove_image(image, options, None)
+                    .await
+                    .context(format!("Failed to remove image {image}"))?;
+            }
+            Ok(())
+        }
+    })
+}
+
+/// Collects the containers whose names start with [`CONTAINER_PREFIX`].
+async fn build_containers(client: &Docker) -> Result<Vec<BuildContainer>> {
+    let summaries = client
+        .list_containers(Some(ListContainersOptions::<String> {
+            all: true,
+            ..Default::default()
+        }))
+        .await?;
+
+    let mut containers = Vec::new();
+    for summary in summaries {
+        // docker prefixes the container names with a `/`
+        let Some(name) = summary
+            .names
+            .iter()
+            .flatten()
+            .map(|n| n.trim_start_matches('/'))
+            .find(|n| n.starts_with(CONTAINER_PREFIX))
+        else {
+            continue
+        };
+        let (contract, digest) = name
+            .trim_start_matches(CONTAINER_PREFIX)
+            .rsplit_once('-')
+            .unwrap_or_default();
+        let project_path = summary
+            .mounts
+            .iter()
+            .flatten()
+            .find(|m| m.destination.as_deref() == Some(MOUNT_DIR))
+            .and_then(|m| m.source.clone());
+        let state = client
+            .inspect_container(name, None::<InspectContainerOptions>)
+            .await?
+            .state;
+        let running = state
+            .as_ref()
+            .and_then(|state| state.running)
+            .unwrap_or_default();
+        let last_used = state
+            .and_then(|state| state.finished_at)
+            .and_then(|finished_at| DateTime::parse_from_rfc3339(&finished_at).ok())
+            .and_then(|finished_at| u64::try_from(finished_at.timestamp()).ok());
+
+        containers.push(BuildContainer {
+            name: name.to_string(),
+            contract: contract.to_string(),
+            digest: digest.to_string(),
+            project_path,
+            image: summary.image.unwrap_or_default(),
+            status: summary.status.unwrap_or_default(),
+            running,
+            last_used,
+        })
+    }
+    Ok(containers)
+}
+
+/// Collects the images of the default repository and those used by `containers`.
+async fn build_images(
+    client: &Docker,
+    containers: &[BuildContainer],
+) -> Result<Vec<BuildImage>> {
+    let summaries = client
+        .list_images(Some(ListImagesOptions::<String> {
+            all: true,
+            ..Default::default()
+        }))
+        .await?;
+
+    let images = summaries
+        .into_iter()
+        .filter_map(|summary| {
+            let used_by = containers
+                .iter()
+                .filter(|c| c.image == summary.id || summary.repo_tags.contains(&c.image))
+                .map(|c| c.name.clone())
+                .collect::<Vec<_>>();
+            let is_default = summary
+                .repo_tags
+                .iter()
+                .any(|t| t.starts_with(&format!("{IMAGE}:")));
+            (is_default || !used_by.is_empty()).then_some(BuildImage {
+                id: summary.id,
+                tags: summary.repo_tags,
+                size: summary.size,
+                created: summary.created,
+                containers: used_by,
+            })
+        })
+        .collect();
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(args: &str) -> Vec<String> {
+        filter_build_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn filter_build_args_removes_subcommand_and_host_options() {
+        assert_eq!(
+            filter("contract build --verifiable --release --image=custom:1.0"),
+            ["--release"]
+        );
+        assert_eq!(
+            filter(
+                "contract verify --address 5Grw --url=ws://localhost:9944 --chain \
+                 rococo --output-json --features foo"
+            ),
+            ["--features", "foo"]
+        );
+    }
+
+    #[test]
+    fn filter_build_args_keeps_paths_containing_subcommands() {
+        assert_eq!(
+            filter("contract verify --manifest-path /x/verify_proj/Cargo.toml"),
+            ["--manifest-path", "/x/verify_proj/Cargo.toml"]
+        );
+        assert_eq!(
+            filter("contract build --manifest-path=build/contract/Cargo.toml"),
+            ["--manifest-path=build/contract/Cargo.toml"]
+        );
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Diagnostics of the environment required to build contracts.
+//!
+//! Every check is executed regardless of the outcome of the previous ones, so that all
+//! problems are reported at once.
+
+use crate::{
+    assert_debug_mode_supported,
+    check_dylint_requirements,
+    docker,
+    lint_manifest,
+    util,
+    CrateMetadata,
+    ManifestPath,
+    Target,
+};
+use anyhow::Result;
+use contract_metadata::compatibility::check_contract_ink_compatibility;
+use std::process::Command;
+
+/// The severity of a [`Diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticLevel {
+    /// The check passed.
+    Ok,
+    /// The check failed, but only some functionality is affected.
+    Warning,
+    /// The check failed and contracts can not be built.
+    Error,
+}
+
+/// The outcome of a single environment check.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Diagnostic {
+    /// The name of the check.
+    pub check: &'static str,
+    /// The severity of the outcome.
+    pub level: DiagnosticLevel,
+    /// Describes the outcome of the check.
+    pub message: String,
+    /// The suggested fix, if the check failed.
+    pub fix: Option<String>,
+}
+
+impl Diagnostic {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            check,
+            level: DiagnosticLevel::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn failed(
+        check: &'static str,
+        level: DiagnosticLevel,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            check,
+            level,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    /// Creates a failed diagnostic from an error, whose first line describes the
+    /// problem and the remaining lines describe the fix.
+    fn from_error(
+        check: &'static str,
+        level: DiagnosticLevel,
+        err: &anyhow::Error,
+    ) -> Self {
+        let err = strip_ansi_colors(&err.to_string());
+        let (message, fix) = err.split_once('\n').unwrap_or((&err, ""));
+        let fix = fix.trim();
+        Self {
+            check,
+            level,
+            message: message.trim().to_string(),
+            fix: (!fix.is_empty()).then(|| fix.to_string()),
+        }
+    }
+}
+
+/// Runs all environment checks for building contracts for `target`.
+///
+/// If a `manifest_path` is given, the contract at this path is checked as well.
+pub fn diagnose(manifest_path: Option<&ManifestPath>, target: Target) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![
+        check_rust_toolchain(),
+        check_rust_src(),
+        check_target(target),
+        match check_dylint_requirements(None) {
+            Ok(()) => {
+                Diagnostic::ok("linting", "cargo-dylint and dylint-link are installed")
+            }
+            Err(err) => Diagnostic::from_error("linting", DiagnosticLevel::Warning, &err),
+        },
+        check_docker(),
+    ];
+    if let Some(manifest_path) = manifest_path {
+        diagnostics.extend(check_contract(manifest_path, target));
+    }
+    diagnostics
+}
+
+fn check_rust_toolchain() -> Diagnostic {
+    match util::rust_toolchain() {
+        Ok(toolchain) => Diagnostic::ok("rust toolchain", toolchain),
+        Err(err) => {
+            Diagnostic::failed(
+                "rust toolchain",
+                DiagnosticLevel::Error,
+                format!("Failed to determine the Rust toolchain: {err}"),
+                "Install `rustup` according to https://rustup.rs/",
+            )
+        }
+    }
+}
+
+fn check_rust_src() -> Diagnostic {
+    match rustup(&["component", "list", "--installed"]) {
+        Ok(components) if components.lines().any(|c| c.starts_with("rust-src")) => {
+            Diagnostic::ok("rust-src", "The `rust-src` component is installed")
+        }
+        Ok(_) => {
+            Diagnostic::failed(
+                "rust-src",
+                DiagnosticLevel::Error,
+                "The `rust-src` component is required to build the standard library for \
+                the contract target",
+                "rustup component add rust-src",
+            )
+        }
+        Err(err) => Diagnostic::from_error("rust-src", DiagnosticLevel::Warning, &err),
+    }
+}
+
+fn check_target(target: Target) -> Diagnostic {
+    let llvm_target = target.llvm_target();
+    match rustup(&["target", "list", "--installed"]) {
+        Ok(targets) if targets.lines().any(|t| t.trim() == llvm_target) => {
+            Diagnostic::ok("target", format!("`{llvm_target}` is installed"))
+        }
+        Ok(_) => {
+            Diagnostic::failed(
+                "target",
+                DiagnosticLevel::Warning,
+                format!("The `{llvm_target}` target is not installed"),
+                format!("rustup target add {llvm_target}"),
+            )
+        }
+        Err(err) => Diagnostic::from_error("target", DiagnosticLevel::Warning, &err),
+    }
+}
+
+fn check_docker() -> Diagnostic {
+    match docker::check_connection() {
+        Ok(()) => Diagnostic::ok("docker", "The docker engine is reachable"),
+        Err(err) => Diagnostic::failed(
+            "docker",
+            DiagnosticLevel::Warning,
+            format!(
+                "The docker engine, required for verifiable builds, is not reachable: \
+                    {}",
+                err.to_string().replace('\n', " ")
+            ),
+            "Make sure the docker engine is running and that your user has access to \
+                its socket, e.g. by adding it to the `docker` group.",
+        ),
+    }
+}
+
+fn check_contract(manifest_path: &ManifestPath, target: Target) -> Vec<Diagnostic> {
+    let crate_metadata = match CrateMetadata::collect(manifest_path, target) {
+        Ok(crate_metadata) => crate_metadata,
+        Err(err) => {
+            return vec![Diagnostic::from_error(
+                "contract",
+                DiagnosticLevel::Error,
+                &err,
+            )]
+        }
+    };
+    let ink_version = &crate_metadata.ink_version;
+
+    let compatibility = match check_contract_ink_compatibility(ink_version, None) {
+        Ok(()) => {
+            Diagnostic::ok(
+                "ink! compatibility",
+                format!("ink! `{ink_version}` is supported"),
+            )
+        }
+        Err(err) => {
+            Diagnostic::from_error("ink! compatibility", DiagnosticLevel::Warning, &err)
+        }
+    };
+    let debug_mode = match assert_debug_mode_supported(ink_version) {
+        Ok(()) => Diagnostic::ok("debug mode", "Debug builds are supported"),
+        Err(err) => {
+            Diagnostic::failed(
+                "debug mode",
+                DiagnosticLevel::Warning,
+                err.to_string(),
+                "Build the contract with `--release` or update ink!",
+            )
+        }
+    };
+    let mut diagnostics = vec![compatibility, debug_mode];
+    match lint_manifest(&crate_metadata, &Default::default()) {
+        Ok(lints) if lints.is_empty() => {
+            diagnostics.push(Diagnostic::ok(
+                "manifest",
+                "No misconfigurations found in the `Cargo.toml`",
+            ))
+        }
+        Ok(lints) => {
+            diagnostics.extend(lints.into_iter().map(|lint| {
+                Diagnostic::failed(
+                    "manifest",
+                    DiagnosticLevel::Warning,
+                    lint.message,
+                    lint.help,
+                )
+            }))
+        }
+        Err(err) => {
+            diagnostics.push(Diagnostic::from_error(
+                "manifest",
+                DiagnosticLevel::Error,
+                &err,
+            ))
+        }
+    }
+    diagnostics
+}
+
+/// Removes the terminal color codes some of the checks add to their errors.
+fn strip_ansi_colors(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // skip the escape sequence up to and including the terminating `m`
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
+
+/// Invokes `rustup` with `args`, returning its stdout.
+fn rustup(args: &[&str]) -> Result<String> {
+    let output = Command::new("rustup").args(args).output().map_err(|_| {
+        anyhow::anyhow!(
+            "`rustup` was not found!\n\
+            Install `rustup` according to https://rustup.rs/"
+        )
+    })?;
+    anyhow::ensure!(
+        output.status.success(),
+        "`rustup {}` failed\n{}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostic_from_error_splits_message_and_fix() {
+        let err =
+            anyhow::anyhow!("Something is missing!\n\nInstall it by executing:\n  x");
+        let diagnostic = Diagnostic::from_error("test", DiagnosticLevel::Error, &err);
+        assert_eq!(diagnostic.message, "Something is missing!");
+        assert_eq!(
+            diagnostic.fix.as_deref(),
+            Some("Install it by executing:\n  x")
+        );
+
+        let err = anyhow::anyhow!("\x1b[93mSingle line\x1b[0m");
+        let diagnostic = Diagnostic::from_error("test", DiagnosticLevel::Error, &err);
+        assert_eq!(diagnostic.message, "Single line");
+        assert_eq!(diagnostic.fix, None);
+    }
+}
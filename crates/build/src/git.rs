@@ -0,0 +1,175 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers to build a contract from a clean checkout of a git revision.
+
+use crate::GitInfo;
+use anyhow::{
+    Context,
+    Result,
+};
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    process::Command,
+};
+
+/// Returns the root directory of the git repository containing `dir`.
+pub(crate) fn repository_root(dir: &Path) -> Result<PathBuf> {
+    let root = git(dir, &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(root))
+}
+
+/// Collects the [`GitInfo`] of the repository at `root`.
+///
+/// If `rev` is `None` the information describes the current working tree, otherwise
+/// the given revision, which is considered clean since it is checked out separately.
+pub(crate) fn git_info(root: &Path, rev: Option<&str>) -> Result<GitInfo> {
+    let commit = git(
+        root,
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("{}^{{commit}}", rev.unwrap_or("HEAD")),
+        ],
+    )
+    .context(format!(
+        "Failed to resolve the git revision `{}`",
+        rev.unwrap_or("HEAD")
+    ))?;
+    let repository = git(root, &["config", "--get", "remote.origin.url"]).ok();
+    let dirty = match rev {
+        Some(_) => false,
+        None => !git(root, &["status", "--porcelain"])?.is_empty(),
+    };
+    Ok(GitInfo {
+        repository,
+        commit,
+        dirty,
+    })
+}
+
+/// Checks out `commit` of the repository at `root` into the empty directory `dest`.
+///
+/// Only committed files end up in `dest`, untracked and modified files of the working
+/// tree are left behind.
+pub(crate) fn checkout(root: &Path, commit: &str, dest: &Path) -> Result<()> {
+    let dest = dest.to_str().context("Cannot convert path to string.")?;
+    // `--shared` makes all objects of the source repository available, so that also
+    // commits which are not reachable from any branch can be checked out.
+    git(
+        root,
+        &["clone", "--quiet", "--shared", "--no-checkout", ".", dest],
+    )?;
+    git(
+        Path::new(dest),
+        &["checkout", "--quiet", "--detach", commit],
+    )?;
+    Ok(())
+}
+
+/// Clones the latest commit of the default branch of the repository at `url` into
+/// the not yet existing directory `dest`.
+pub(crate) fn shallow_clone(url: &str, dest: &Path) -> Result<()> {
+    let parent = dest.parent().context("Clone destination has no parent")?;
+    let dest = dest.to_str().context("Cannot convert path to string.")?;
+    git(parent, &["clone", "--quiet", "--depth", "1", url, dest])
+        .context(format!("Failed to clone the repository `{url}`"))?;
+    Ok(())
+}
+
+/// Invokes `git` with `args` in `dir`, returning the trimmed stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to execute `git`, is it installed?")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn init_repo(dir: &Path) {
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .status()
+                .expect("git failed");
+            assert!(status.success());
+        };
+        git(&["init", "--quiet"]);
+        fs::write(dir.join("committed.rs"), "committed").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "init"]);
+    }
+
+    #[test]
+    fn git_info_detects_dirty_working_tree() {
+        let tmp = tempfile::tempdir().unwrap();
+        init_repo(tmp.path());
+        let root = repository_root(tmp.path()).unwrap();
+
+        let clean = git_info(&root, None).unwrap();
+        assert!(!clean.dirty);
+        assert_eq!(clean.repository, None);
+
+        fs::write(tmp.path().join("untracked.rs"), "untracked").unwrap();
+        let dirty = git_info(&root, None).unwrap();
+        assert!(dirty.dirty);
+        assert_eq!(dirty.commit, clean.commit);
+
+        let rev = git_info(&root, Some("HEAD")).unwrap();
+        assert!(!rev.dirty);
+        assert_eq!(rev.commit, clean.commit);
+    }
+
+    #[test]
+    fn checkout_contains_only_committed_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        init_repo(tmp.path());
+        fs::write(tmp.path().join("untracked.rs"), "untracked").unwrap();
+        fs::write(tmp.path().join("committed.rs"), "modified").unwrap();
+        let root = repository_root(tmp.path()).unwrap();
+        let info = git_info(&root, Some("HEAD")).unwrap();
+
+        let dest = tempfile::tempdir().unwrap();
+        let dest = dest.path().join("checkout");
+        checkout(&root, &info.commit, &dest).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dest.join("committed.rs")).unwrap(),
+            "committed"
+        );
+        assert!(!dest.join("untracked.rs").exists());
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Policies of the host functions a target chain provides to contracts.
+//!
+//! A policy file lists the allowed functions by their import module, i.e. the version of
+//! the `pallet-contracts` API, e.g.
+//!
+//! ```toml
+//! chain = "my-chain"
+//!
+//! [imports]
+//! seal0 = ["*"]
+//! seal1 = ["get_storage", "set_storage"]
+//! ```
+//!
+//! where `*` allows all functions of a module.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+};
+use wasmparser::{
+    Parser,
+    Payload,
+    TypeRef,
+};
+
+/// Allows all functions of an import module.
+const WILDCARD: &str = "*";
+
+/// The host functions a chain provides to contracts.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportPolicy {
+    /// The name of the chain.
+    pub chain: String,
+    /// The allowed functions by their import module.
+    pub imports: BTreeMap<String, Vec<String>>,
+}
+
+impl ImportPolicy {
+    /// Loads the policy file at the `path`, which is relative to `base_dir`.
+    pub fn load(path: &str, base_dir: &Path) -> Result<Self> {
+        let path = base_dir.join(path);
+        if !path.is_file() {
+            anyhow::bail!("The policy file {} does not exist", path.display())
+        }
+        let policy = fs::read_to_string(&path)
+            .context(format!("Reading the policy file {} failed", path.display()))?;
+        toml::from_str(&policy)
+            .context(format!("Parsing the policy file {} failed", path.display()))
+    }
+
+    /// Returns `true` if the chain provides the host function.
+    pub fn allows(&self, module: &str, function: &str) -> bool {
+        self.imports.get(module).is_some_and(|functions| {
+            functions.iter().any(|f| f == WILDCARD || f == function)
+        })
+    }
+
+    /// Checks that the chain provides all host functions imported by the Wasm `code`.
+    pub fn validate(&self, code: &[u8]) -> Result<()> {
+        let mut unsupported = Vec::new();
+        for payload in Parser::new(0).parse_all(code) {
+            if let Payload::ImportSection(reader) = payload? {
+                for import in reader {
+                    let import = import?;
+                    if matches!(import.ty, TypeRef::Func(_))
+                        && !self.allows(import.module, import.name)
+                    {
+                        unsupported.push(format!("`{}::{}`", import.module, import.name));
+                    }
+                }
+            }
+        }
+        if !unsupported.is_empty() {
+            anyhow::bail!(
+                "The contract imports host functions which `{}` does not provide: {}\n\
+                Use the host functions of the supported API versions ({}), or build for \
+                another chain with `--chain <PATH>`",
+                self.chain,
+                unsupported.join(", "),
+                self.imports.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = r#"
+        (module
+            (type (;0;) (func (param i32 i32)))
+            (import "seal0" "value_transferred" (func (;0;) (type 0)))
+            (import "seal1" "get_storage" (func (;1;) (type 0)))
+            (import "__unstable__" "call_runtime" (func (;2;) (type 0)))
+            (import "env" "memory" (memory (;0;) 2 16))
+        )"#;
+
+    #[test]
+    fn rejects_host_functions_not_provided_by_the_chain() {
+        let code = wabt::wat2wasm(CODE).expect("Invalid wabt");
+
+        let unstable: ImportPolicy = toml::from_str(
+            r#"
+                chain = "unstable"
+
+                [imports]
+                seal0 = ["*"]
+                seal1 = ["*"]
+                __unstable__ = ["*"]
+            "#,
+        )
+        .unwrap();
+        assert!(unstable.validate(&code).is_ok());
+
+        let stable: ImportPolicy = toml::from_str(
+            r#"
+                chain = "stable"
+
+                [imports]
+                seal0 = ["*"]
+                seal1 = ["*"]
+            "#,
+        )
+        .unwrap();
+        let err = stable.validate(&code).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "The contract imports host functions which `stable` does not provide: \
+            `__unstable__::call_runtime`\n"
+        ));
+    }
+
+    #[test]
+    fn loads_custom_policy_files() {
+        crate::util::tests::with_tmp_dir(|path| {
+            let policy_path = path.join("my-chain.toml");
+            fs::write(
+                &policy_path,
+                r#"
+                    chain = "my-chain"
+
+                    [imports]
+                    seal0 = ["*"]
+                    seal1 = ["set_storage"]
+                "#,
+            )?;
+            let policy = ImportPolicy::load("my-chain.toml", path)?;
+            assert!(policy.allows("seal0", "value_transferred"));
+            assert!(policy.allows("seal1", "set_storage"));
+            assert!(!policy.allows("seal1", "get_storage"));
+            assert!(!policy.allows("seal2", "call"));
+
+            let code = wabt::wat2wasm(CODE).expect("Invalid wabt");
+            let err = policy.validate(&code).unwrap_err().to_string();
+            assert!(err.contains("`seal1::get_storage`, `__unstable__::call_runtime`"));
+
+            assert!(ImportPolicy::load("unknown-chain.toml", path).is_err());
+            Ok(())
+        })
+    }
+}
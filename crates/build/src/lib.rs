@@ -26,14 +26,23 @@ use which as _;
 mod args;
 mod crate_metadata;
 mod docker;
+mod doctor;
+mod git;
+mod import_policy;
+mod lint_manifest;
 pub mod metadata;
+mod migrate;
 mod new;
+mod post_process_riscv;
 mod post_process_wasm;
+mod sbom;
+mod std_dependencies;
 #[cfg(test)]
 mod tests;
 pub mod util;
 mod validate_wasm;
 mod wasm_opt;
+mod watch;
 mod workspace;
 
 #[deprecated(since = "2.0.2", note = "Use MetadataArtifacts instead")]
@@ -46,6 +55,7 @@ pub use self::{
         Features,
         Network,
         OutputType,
+        SbomFormat,
         Target,
         UnstableFlags,
         UnstableOptions,
@@ -53,21 +63,45 @@ pub use self::{
         VerbosityFlags,
     },
     crate_metadata::CrateMetadata,
+    import_policy::ImportPolicy,
+    lint_manifest::{
+        lint_manifest,
+        ManifestLint,
+    },
     metadata::{
         BuildInfo,
+        GitInfo,
         MetadataArtifacts,
+        SbomInfo,
         WasmOptSettings,
     },
-    new::new_contract_project,
+    migrate::{
+        migrate,
+        ManualChange,
+        MigrationReport,
+    },
+    new::{
+        new_contract_project,
+        new_contract_project_with_template,
+        templates,
+        Template,
+    },
+    post_process_riscv::post_process_riscv,
     post_process_wasm::{
         load_module,
         post_process_wasm,
+        post_process_wasm_with_debug_file,
+    },
+    std_dependencies::{
+        find_std_dependencies,
+        StdDependency,
     },
     util::DEFAULT_KEY_COL_WIDTH,
     wasm_opt::{
         OptimizationPasses,
         OptimizationResult,
     },
+    watch::watch,
     workspace::{
         Lto,
         Manifest,
@@ -82,8 +116,19 @@ pub use self::{
 use crate::wasm_opt::WasmOptHandler;
 pub use docker::{
     docker_build,
+    list_build_containers,
+    list_build_images,
+    remove_build_containers,
+    remove_build_images,
+    BuildContainer,
+    BuildImage,
     ImageVariant,
 };
+pub use doctor::{
+    diagnose,
+    Diagnostic,
+    DiagnosticLevel,
+};
 
 use anyhow::{
     Context,
@@ -138,8 +183,22 @@ pub struct ExecuteArgs {
     pub output_type: OutputType,
     pub skip_wasm_validation: bool,
     pub target: Target,
+    /// Further targets to build the contract for, bundled together with the code of the
+    /// `target`.
+    pub additional_targets: Vec<Target>,
     pub max_memory_pages: u64,
     pub image: ImageVariant,
+    /// Build the given git revision from a clean checkout instead of the working tree.
+    ///
+    /// Only supported for verifiable builds.
+    pub from_git: Option<String>,
+    /// Abort the build if the manifest lints report any warnings.
+    pub deny_manifest_warnings: bool,
+    /// Write a software bill of materials in the given format alongside the contract.
+    pub sbom: Option<SbomFormat>,
+    /// The path to the policy file of the chain whose host functions the contract may
+    /// import.
+    pub chain: Option<String>,
 }
 
 impl Default for ExecuteArgs {
@@ -158,8 +217,13 @@ impl Default for ExecuteArgs {
             output_type: Default::default(),
             skip_wasm_validation: Default::default(),
             target: Default::default(),
+            additional_targets: Default::default(),
             max_memory_pages: DEFAULT_MAX_MEMORY_PAGES,
             image: Default::default(),
+            from_git: Default::default(),
+            deny_manifest_warnings: Default::default(),
+            sbom: Default::default(),
+            chain: Default::default(),
         }
     }
 }
@@ -169,6 +233,12 @@ impl Default for ExecuteArgs {
 pub struct BuildResult {
     /// Path to the resulting Wasm file.
     pub dest_wasm: Option<PathBuf>,
+    /// Path to the debug companion of the Wasm file, containing its debug symbols.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest_debug_wasm: Option<PathBuf>,
+    /// Paths to the code of the additional targets the contract was built for.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dest_additional_code: Vec<PathBuf>,
     /// Result of the metadata generation.
     pub metadata_result: Option<MetadataArtifacts>,
     /// Path to the directory where output files are written to.
@@ -183,6 +253,9 @@ pub struct BuildResult {
     pub verbosity: Verbosity,
     /// Image used for the verifiable build
     pub image: Option<String>,
+    /// Path to the software bill of materials, if one was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom: Option<PathBuf>,
     /// The type of formatting to use for the build output.
     #[serde(skip_serializing, skip_deserializing)]
     pub output_type: OutputType,
@@ -211,7 +284,7 @@ impl BuildResult {
         );
 
         if self.build_artifact == BuildArtifacts::CodeOnly {
-            let out = format!(
+            let mut out = format!(
                 "{}{}Your contract's code is ready. You can find it here:\n{}",
                 opt_size_diff,
                 build_mode,
@@ -222,6 +295,9 @@ impl BuildResult {
                     .to_string()
                     .bold()
             );
+            for dest_code in &self.dest_additional_code {
+                out.push_str(&format!("\n{}", dest_code.display().to_string().bold()));
+            }
             return out
         };
 
@@ -245,6 +321,20 @@ impl BuildResult {
             );
             out.push_str(&wasm);
         }
+        for dest_code in &self.dest_additional_code {
+            let code = format!(
+                "  - {} (the contract's code)\n",
+                util::base_name(dest_code).bold()
+            );
+            out.push_str(&code);
+        }
+        if let Some(dest_debug_wasm) = self.dest_debug_wasm.as_ref() {
+            let debug_wasm = format!(
+                "  - {} (the contract's debug symbols)\n",
+                util::base_name(dest_debug_wasm).bold()
+            );
+            out.push_str(&debug_wasm);
+        }
         if let Some(metadata_result) = self.metadata_result.as_ref() {
             let metadata = format!(
                 "  - {} (the contract's metadata)",
@@ -252,6 +342,13 @@ impl BuildResult {
             );
             out.push_str(&metadata);
         }
+        if let Some(sbom) = self.sbom.as_ref() {
+            let sbom = format!(
+                "\n  - {} (the software bill of materials)",
+                util::base_name(sbom).bold()
+            );
+            out.push_str(&sbom);
+        }
         out
     }
 
@@ -370,6 +467,7 @@ fn exec_cargo_for_onchain_target(
             .with_root_package_manifest(|manifest| {
                 manifest
                     .with_replaced_lib_to_bin()?
+                    .with_merged_workspace_profile(crate_metadata)?
                     .with_profile_release_defaults(Profile::default_contract_release())?
                     .with_merged_workspace_dependencies(crate_metadata)?
                     .with_empty_workspace();
@@ -713,12 +811,58 @@ pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
         extra_lints,
         output_type,
         target,
+        additional_targets,
+        deny_manifest_warnings,
+        sbom,
+        skip_wasm_validation,
+        chain,
         ..
     } = &args;
 
+    // the code of all targets is bundled with the code of the first one
+    let mut additional_targets = additional_targets.clone();
+    additional_targets.retain(|t| t != target);
+    additional_targets.dedup();
+    let targets: Vec<Target> = std::iter::once(*target)
+        .chain(additional_targets.iter().copied())
+        .collect();
+
+    // The CLI flag `chain` overwrites the chain which is potentially defined in the
+    // `Cargo.toml`, a policy file defined there is relative to the `Cargo.toml`.
+    let import_policy = if *skip_wasm_validation || !targets.contains(&Target::Wasm) {
+        None
+    } else if let Some(chain) = chain {
+        Some(ImportPolicy::load(chain, Path::new(""))?)
+    } else {
+        let manifest = Manifest::new(manifest_path.clone())?;
+        let manifest_dir = manifest_path.absolute_directory()?;
+        manifest
+            .profile_chain()
+            .map(|chain| ImportPolicy::load(chain, &manifest_dir))
+            .transpose()?
+    };
+    let validate_imports = |target: Target, dest_code: &Path| -> Result<()> {
+        match &import_policy {
+            Some(policy) if target == Target::Wasm => {
+                policy.validate(&fs::read(dest_code)?)
+            }
+            _ => Ok(()),
+        }
+    };
+
     // if image exists, then --verifiable was called and we need to build inside docker.
     if build_mode == &BuildMode::Verifiable {
-        return docker_build(args)
+        if !additional_targets.is_empty() {
+            anyhow::bail!("Verifiable builds are only supported for a single target")
+        }
+        // the `--chain` is not passed into the container, where a policy file might not
+        // exist, so the imports are validated on the host
+        let target = *target;
+        let build_result = docker_build(args)?;
+        if let Some(dest_wasm) = &build_result.dest_wasm {
+            validate_imports(target, dest_wasm)?;
+        }
+        return Ok(build_result)
     }
 
     // The CLI flag `optimization-passes` overwrites optimization passes which are
@@ -747,64 +891,202 @@ pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
         eprintln!("{} {}", "warning:".yellow().bold(), e.to_string().bold());
     }
 
+    let manifest_lints = lint_manifest(&crate_metadata, unstable_flags)?;
+    for lint in &manifest_lints {
+        eprintln!(
+            "{} {}\n  {} {}",
+            "warning:".yellow().bold(),
+            lint.message.bold(),
+            "help:".cyan().bold(),
+            lint.help
+        );
+    }
+    if *deny_manifest_warnings && !manifest_lints.is_empty() {
+        anyhow::bail!(
+            "Aborting the build, {} warning(s) found in the `Cargo.toml`",
+            manifest_lints.len()
+        );
+    }
+
+    // the check invokes `cargo tree`, which is not worth it if nothing is built for the
+    // on-chain target
+    if *build_artifact != BuildArtifacts::CheckOnly {
+        match find_std_dependencies(&crate_metadata.manifest_path, features, target) {
+            Ok(std_dependencies) => {
+                for dependency in std_dependencies {
+                    eprintln!(
+                        "{} {}\n  {} {}",
+                        "warning:".yellow().bold(),
+                        format!(
+                            "The dependency `{}` is built with `std` for the on-chain \
+                            target, {}",
+                            dependency.package, dependency.reason
+                        )
+                        .bold(),
+                        "path:".cyan().bold(),
+                        dependency.path.join(" -> ")
+                    );
+                }
+            }
+            Err(err) => {
+                verbose_eprintln!(
+                    verbosity,
+                    "{} {}",
+                    "warning:".yellow().bold(),
+                    format!("Skipped the check for `std` dependencies: {err:#}").bold()
+                );
+            }
+        }
+    }
+
     let clean_metadata = || {
         fs::remove_file(crate_metadata.metadata_path()).ok();
         fs::remove_file(crate_metadata.contract_bundle_path()).ok();
     };
 
-    let (opt_result, metadata_result, dest_wasm) = match build_artifact {
-        BuildArtifacts::CheckOnly => {
-            // Check basically means only running our linter without building.
-            lint(*extra_lints, &crate_metadata, target, verbosity)?;
-            (None, None, None)
+    // remove the code of targets which are not built, so nothing stale is lingering
+    // around
+    let clean_other_targets = || {
+        for t in Target::iter().filter(|t| !targets.contains(t)) {
+            fs::remove_file(crate_metadata.dest_code.with_extension(t.dest_extension()))
+                .ok();
         }
-        BuildArtifacts::CodeOnly => {
-            // when building only the code metadata will become stale
-            clean_metadata();
-            let (opt_result, _, dest_wasm) =
-                local_build(&crate_metadata, &optimization_passes, &args)?;
-            (opt_result, None, Some(dest_wasm))
+        if !targets.contains(&Target::Wasm) {
+            fs::remove_file(crate_metadata.debug_code_path()).ok();
         }
-        BuildArtifacts::All => {
-            let (opt_result, build_info, dest_wasm) =
-                local_build(&crate_metadata, &optimization_passes, &args).map_err(
-                    |e| {
-                        // build error -> bundle is stale
-                        clean_metadata();
-                        e
-                    },
-                )?;
-
-            let metadata_result = MetadataArtifacts {
-                dest_metadata: crate_metadata.metadata_path(),
-                dest_bundle: crate_metadata.contract_bundle_path(),
-            };
-
-            // skip metadata generation if contract unchanged and all metadata artifacts
-            // exist.
-            if opt_result.is_some()
-                || !metadata_result.dest_metadata.exists()
-                || !metadata_result.dest_bundle.exists()
-            {
-                // if metadata build fails after a code build it might become stale
+    };
+
+    // builds the code of the additional targets, in the same way as the code of `target`
+    let build_additional_targets =
+        || -> Result<Vec<(Target, PathBuf, Option<OptimizationResult>)>> {
+            additional_targets
+                .iter()
+                .map(|t| {
+                    let crate_metadata = CrateMetadata::collect(manifest_path, *t)?;
+                    let args = ExecuteArgs {
+                        target: *t,
+                        ..args.clone()
+                    };
+                    let (opt_result, _, dest_code) =
+                        local_build(&crate_metadata, &optimization_passes, &args)?;
+                    validate_imports(*t, &dest_code)?;
+                    Ok((*t, dest_code, opt_result))
+                })
+                .collect()
+        };
+
+    let write_sbom = |dest_wasm: &Path| -> Result<Option<PathBuf>> {
+        sbom.map(|format| {
+            sbom::write_sbom(&crate_metadata, format, &fs::read(dest_wasm)?)
+        })
+        .transpose()
+    };
+
+    let (opt_result, metadata_result, dest_wasm, additional_code, sbom_path) =
+        match build_artifact {
+            BuildArtifacts::CheckOnly => {
+                // Check basically means only running our linter without building.
+                lint(*extra_lints, &crate_metadata, target, verbosity)?;
+                (None, None, None, Vec::new(), None)
+            }
+            BuildArtifacts::CodeOnly => {
+                // when building only the code metadata will become stale
                 clean_metadata();
-                metadata::execute(
-                    &crate_metadata,
-                    dest_wasm.as_path(),
-                    &metadata_result,
-                    features,
-                    *network,
-                    *verbosity,
-                    unstable_flags,
-                    build_info,
-                )?;
+                clean_other_targets();
+                let (opt_result, _, dest_wasm) =
+                    local_build(&crate_metadata, &optimization_passes, &args)?;
+                validate_imports(*target, &dest_wasm)?;
+                let additional_code = build_additional_targets()?;
+                let sbom_path = write_sbom(&dest_wasm)?;
+                (
+                    opt_result,
+                    None,
+                    Some(dest_wasm),
+                    additional_code,
+                    sbom_path,
+                )
             }
-            (opt_result, Some(metadata_result), Some(dest_wasm))
-        }
-    };
+            BuildArtifacts::All => {
+                clean_other_targets();
+                let (opt_result, mut build_info, dest_wasm) =
+                    local_build(&crate_metadata, &optimization_passes, &args).map_err(
+                        |e| {
+                            // build error -> bundle is stale
+                            clean_metadata();
+                            e
+                        },
+                    )?;
+                // invalid imports -> bundle is stale
+                validate_imports(*target, &dest_wasm)
+                    .inspect_err(|_| clean_metadata())?;
+                let additional_code =
+                    build_additional_targets().inspect_err(|_| clean_metadata())?;
+
+                // link the SBOM from the build info of the bundle
+                let sbom_path = write_sbom(&dest_wasm)?;
+                build_info.sbom = sbom.zip(sbom_path.as_ref()).map(|(format, path)| {
+                    SbomInfo {
+                        format,
+                        file: util::base_name(path).to_string(),
+                    }
+                });
+
+                let metadata_result = MetadataArtifacts {
+                    dest_metadata: crate_metadata.metadata_path(),
+                    dest_bundle: crate_metadata.contract_bundle_path(),
+                };
+
+                // skip metadata generation if contract unchanged and all metadata
+                // artifacts exist.
+                if opt_result.is_some()
+                    || additional_code
+                        .iter()
+                        .any(|(_, _, opt_result)| opt_result.is_some())
+                    || sbom.is_some()
+                    || !metadata_result.dest_metadata.exists()
+                    || !metadata_result.dest_bundle.exists()
+                {
+                    // if metadata build fails after a code build it might become stale
+                    clean_metadata();
+                    metadata::execute(
+                        &crate_metadata,
+                        dest_wasm.as_path(),
+                        *target,
+                        &additional_code
+                            .iter()
+                            .map(|(t, dest_code, _)| (*t, dest_code.clone()))
+                            .collect::<Vec<_>>(),
+                        &metadata_result,
+                        features,
+                        *network,
+                        *verbosity,
+                        unstable_flags,
+                        build_info,
+                    )?;
+                }
+                (
+                    opt_result,
+                    Some(metadata_result),
+                    Some(dest_wasm),
+                    additional_code,
+                    sbom_path,
+                )
+            }
+        };
+
+    // the debug file is only written for Wasm, but might be left from a previous build
+    let dest_debug_wasm = dest_wasm
+        .as_ref()
+        .map(|_| crate_metadata.debug_code_path())
+        .filter(|path| path.exists());
 
     Ok(BuildResult {
         dest_wasm,
+        dest_debug_wasm,
+        dest_additional_code: additional_code
+            .into_iter()
+            .map(|(_, dest_code, _)| dest_code)
+            .collect(),
         metadata_result,
         target_directory: crate_metadata.target_directory,
         optimization_result: opt_result,
@@ -813,6 +1095,7 @@ pub fn execute(args: ExecuteArgs) -> Result<BuildResult> {
         verbosity: *verbosity,
         image: None,
         output_type: output_type.clone(),
+        sbom: sbom_path,
     })
 }
 
@@ -880,6 +1163,8 @@ fn local_build(
             optimization_passes: *optimization_passes,
             keep_debug_symbols: *keep_debug_symbols,
         },
+        git: None,
+        sbom: None,
     };
 
     let post_fingerprint = Fingerprint::new(crate_metadata)?.ok_or_else(|| {
@@ -915,35 +1200,38 @@ fn local_build(
     );
 
     // remove build artifacts so we don't have anything stale lingering around
-    for t in Target::iter() {
-        fs::remove_file(crate_metadata.dest_code.with_extension(t.dest_extension())).ok();
+    fs::remove_file(&crate_metadata.dest_code).ok();
+    if *target == Target::Wasm {
+        fs::remove_file(crate_metadata.debug_code_path()).ok();
     }
 
-    let original_size =
-        fs::metadata(&crate_metadata.original_code)?.len() as f64 / 1000.0;
-
     match target {
         Target::Wasm => {
-            let handler = WasmOptHandler::new(*optimization_passes, *keep_debug_symbols)?;
-            handler.optimize(&crate_metadata.original_code, &crate_metadata.dest_code)?;
-            post_process_wasm(
+            let debug_code_path = crate_metadata.debug_code_path();
+            // the code is optimized once with the debug symbols, the deployable code
+            // is stripped from it
+            WasmOptHandler::new(*optimization_passes, true)?
+                .optimize(&crate_metadata.original_code, &debug_code_path)?;
+            post_process_wasm_with_debug_file(
+                &debug_code_path,
                 &crate_metadata.dest_code,
+                *keep_debug_symbols,
                 *skip_wasm_validation,
                 verbosity,
                 *max_memory_pages,
             )?;
         }
         Target::RiscV => {
-            fs::copy(&crate_metadata.original_code, &crate_metadata.dest_code)?;
+            post_process_riscv(
+                &crate_metadata.original_code,
+                &crate_metadata.dest_code,
+                *skip_wasm_validation,
+            )?;
         }
     }
 
-    let optimized_size = fs::metadata(&dest_code_path)?.len() as f64 / 1000.0;
-
-    let optimization_result = OptimizationResult {
-        original_size,
-        optimized_size,
-    };
+    let optimization_result =
+        OptimizationResult::from_files(&crate_metadata.original_code, &dest_code_path)?;
 
     Ok((
         Some(optimization_result),
@@ -987,6 +1275,8 @@ impl Fingerprint {
 }
 
 /// Returns the blake2 hash of the code slice.
+///
+/// `pallet-contracts` identifies the code of both targets by this hash.
 pub fn code_hash(code: &[u8]) -> [u8; 32] {
     blake2_hash(code)
 }
@@ -1059,6 +1349,8 @@ mod unit_tests {
 
         let build_result = BuildResult {
             dest_wasm: Some(PathBuf::from("/path/to/contract.wasm")),
+            dest_debug_wasm: None,
+            dest_additional_code: Vec::new(),
             metadata_result: Some(MetadataArtifacts {
                 dest_metadata: PathBuf::from("/path/to/contract.json"),
                 dest_bundle: PathBuf::from("/path/to/contract.contract"),
@@ -1073,6 +1365,7 @@ mod unit_tests {
             image: None,
             verbosity: Verbosity::Quiet,
             output_type: OutputType::Json,
+            sbom: None,
         };
 
         // when
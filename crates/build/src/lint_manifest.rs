@@ -0,0 +1,301 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checks of the contract `Cargo.toml` for configurations which are known to break the
+//! build of a contract, or to only be reported after a full compilation.
+
+use crate::{
+    CrateMetadata,
+    Manifest,
+    ManifestPath,
+    UnstableFlags,
+};
+use anyhow::Result;
+
+/// Dependencies known to enable `std` with their default features.
+const STD_BY_DEFAULT: &[&str] = &["parity-scale-codec", "scale-info"];
+
+/// A misconfiguration found in the `Cargo.toml` of a contract.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct ManifestLint {
+    /// The name of the lint.
+    pub name: &'static str,
+    /// Describes the misconfiguration.
+    pub message: String,
+    /// Describes how to fix the misconfiguration.
+    pub help: String,
+}
+
+/// Checks the `Cargo.toml` of the contract, and the one of its workspace, if any.
+pub fn lint_manifest(
+    crate_metadata: &CrateMetadata,
+    unstable_flags: &UnstableFlags,
+) -> Result<Vec<ManifestLint>> {
+    let manifest = Manifest::new(crate_metadata.manifest_path.clone())?;
+    let workspace_manifest_path =
+        crate_metadata.cargo_meta.workspace_root.join("Cargo.toml");
+    let workspace_manifest =
+        if workspace_manifest_path != crate_metadata.root_package.manifest_path {
+            ManifestPath::new(workspace_manifest_path)
+                .and_then(Manifest::new)
+                .ok()
+        } else {
+            None
+        };
+    Ok(lint(
+        &manifest,
+        workspace_manifest.as_ref(),
+        unstable_flags.original_manifest,
+    ))
+}
+
+/// Checks the `manifest`, using the `[workspace]` of `workspace_manifest` for inherited
+/// settings.
+///
+/// The `[lib] crate-type` is only checked if the contract is built with the
+/// `original_manifest`, otherwise the build replaces the `[lib]` target.
+fn lint(
+    manifest: &Manifest,
+    workspace_manifest: Option<&Manifest>,
+    original_manifest: bool,
+) -> Vec<ManifestLint> {
+    let mut lints = Vec::new();
+
+    if original_manifest && !manifest.crate_types().contains(&"cdylib") {
+        lints.push(ManifestLint {
+            name: "missing-cdylib",
+            message: "The `[lib]` target has no `cdylib` crate type, no Wasm binary \
+                will be produced"
+                .to_string(),
+            help: "Add `crate-type = [\"cdylib\"]` to the `[lib]` section".to_string(),
+        })
+    }
+
+    let profiles = [
+        ("contract", Some(manifest)),
+        ("workspace", workspace_manifest),
+    ];
+    for (owner, manifest) in profiles {
+        let Some(manifest) = manifest else { continue };
+        let panic = manifest
+            .profile_release_setting("panic")
+            .and_then(|panic| panic.as_str());
+        if panic == Some("unwind") {
+            lints.push(ManifestLint {
+                name: "panic-unwind",
+                message: format!(
+                    "`panic = \"unwind\"` is set in the `[profile.release]` of the \
+                    {owner}, contracts can not unwind and the Wasm will import panic \
+                    functions"
+                ),
+                help: "Remove the setting or use `panic = \"abort\"`".to_string(),
+            })
+        }
+    }
+
+    let std_feature = manifest.feature("std");
+    let has_std_feature = std_feature.is_some();
+    if !has_std_feature {
+        lints.push(ManifestLint {
+            name: "missing-std-feature",
+            message: "The contract has no `std` feature, which is required to generate \
+                the metadata"
+                .to_string(),
+            help: "Add a `std` feature which enables the `std` features of the \
+                dependencies, e.g. `std = [\"ink/std\"]`"
+                .to_string(),
+        })
+    }
+    let std_feature = std_feature.unwrap_or_default();
+
+    let workspace_dependencies =
+        workspace_manifest.and_then(Manifest::workspace_dependencies);
+    for (name, dependency) in manifest.dependencies().into_iter().flatten() {
+        // settings of `workspace = true` dependencies are inherited from the workspace
+        let dependency = match dependency.get("workspace").and_then(|w| w.as_bool()) {
+            Some(true) => {
+                match workspace_dependencies.and_then(|deps| deps.get(name)) {
+                    Some(dependency) => dependency,
+                    None => continue,
+                }
+            }
+            _ => dependency,
+        };
+        let package = dependency
+            .get("package")
+            .and_then(|package| package.as_str())
+            .unwrap_or(name);
+        let std_by_default = is_ink_crate(package) || STD_BY_DEFAULT.contains(&package);
+        let default_features = dependency
+            .get("default-features")
+            .and_then(|default_features| default_features.as_bool())
+            .unwrap_or(true);
+        let std_forwarded = std_feature
+            .iter()
+            .any(|&f| f == format!("{name}/std") || f == format!("{name}?/std"));
+
+        if default_features && (std_by_default || std_forwarded) {
+            lints.push(ManifestLint {
+                name: "default-features",
+                message: format!(
+                    "The dependency `{name}` is used with its default features, which \
+                    enable `std` in the on-chain build"
+                ),
+                help: format!(
+                    "Set `default-features = false` for `{name}` and add `\"{name}/std\"` \
+                    to the `std` feature"
+                ),
+            })
+        } else if has_std_feature && !default_features && std_by_default && !std_forwarded
+        {
+            lints.push(ManifestLint {
+                name: "std-not-forwarded",
+                message: format!(
+                    "The `std` feature does not enable the `std` feature of `{name}`"
+                ),
+                help: format!("Add `\"{name}/std\"` to the `std` feature"),
+            })
+        }
+    }
+
+    lints
+}
+
+/// Returns `true` for the crates of ink! which are used as contract dependencies.
+fn is_ink_crate(package: &str) -> bool {
+    package == "ink" || (package.starts_with("ink_") && package != "ink_e2e")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests::with_tmp_dir;
+    use std::fs;
+
+    fn lint_names(
+        manifest: &str,
+        workspace_manifest: Option<&str>,
+        original_manifest: bool,
+    ) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        with_tmp_dir(|path| {
+            let manifest_path = ManifestPath::new(path.join("Cargo.toml"))?;
+            fs::write(&manifest_path, manifest)?;
+            let workspace_manifest = match workspace_manifest {
+                Some(workspace_manifest) => {
+                    let workspace_path = path.join("workspace");
+                    fs::create_dir(&workspace_path)?;
+                    let manifest_path =
+                        ManifestPath::new(workspace_path.join("Cargo.toml"))?;
+                    fs::write(&manifest_path, workspace_manifest)?;
+                    Some(Manifest::new(manifest_path)?)
+                }
+                None => None,
+            };
+            let manifest = Manifest::new(manifest_path)?;
+            names = lint(&manifest, workspace_manifest.as_ref(), original_manifest)
+                .into_iter()
+                .map(|lint| lint.name)
+                .collect();
+            Ok(())
+        });
+        names
+    }
+
+    #[test]
+    fn well_configured_manifest_has_no_lints() {
+        let manifest = r#"
+            [package]
+            name = "flipper"
+            version = "0.1.0"
+
+            [dependencies]
+            ink = { version = "5.0.0", default-features = false }
+            scale = { package = "parity-scale-codec", version = "3", default-features = false }
+            hex = { version = "0.4", default-features = false }
+
+            [lib]
+            crate-type = ["cdylib"]
+
+            [features]
+            default = ["std"]
+            std = ["ink/std", "scale/std"]
+        "#;
+        assert!(lint_names(manifest, None, true).is_empty());
+    }
+
+    #[test]
+    fn reports_misconfigurations() {
+        let manifest = r#"
+            [package]
+            name = "flipper"
+            version = "0.1.0"
+
+            [dependencies]
+            ink = { version = "5.0.0", default-features = false }
+            scale = { package = "parity-scale-codec", version = "3" }
+            scale-info = { workspace = true }
+            hex = { version = "0.4" }
+
+            [lib]
+            crate-type = ["rlib"]
+
+            [features]
+            default = ["std"]
+            std = ["hex/std"]
+
+            [profile.release]
+            panic = "unwind"
+        "#;
+        let workspace_manifest = r#"
+            [workspace]
+            members = ["flipper"]
+
+            [workspace.dependencies]
+            scale-info = { version = "2.6", default-features = false }
+
+            [profile.release]
+            panic = "unwind"
+        "#;
+        assert_eq!(
+            lint_names(manifest, Some(workspace_manifest), true),
+            [
+                "missing-cdylib",
+                "panic-unwind",
+                "panic-unwind",
+                "std-not-forwarded",
+                "default-features",
+                "std-not-forwarded",
+                "default-features",
+            ]
+        );
+        // the `[lib]` target is replaced unless the original manifest is used
+        assert!(!lint_names(manifest, None, false).contains(&"missing-cdylib"));
+    }
+
+    #[test]
+    fn reports_missing_std_feature() {
+        let manifest = r#"
+            [package]
+            name = "flipper"
+            version = "0.1.0"
+
+            [dependencies]
+            ink = { version = "5.0.0", default-features = false }
+        "#;
+        assert_eq!(lint_names(manifest, None, false), ["missing-std-feature"]);
+    }
+}
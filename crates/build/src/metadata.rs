@@ -15,7 +15,6 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-    code_hash,
     crate_metadata::CrateMetadata,
     util,
     verbose_eprintln,
@@ -29,6 +28,8 @@ use crate::{
     Network,
     OptimizationPasses,
     Profile,
+    SbomFormat,
+    Target,
     UnstableFlags,
     Verbosity,
 };
@@ -43,6 +44,7 @@ use contract_metadata::{
     Source,
     SourceCompiler,
     SourceLanguage,
+    SourceRiscV,
     SourceWasm,
     User,
 };
@@ -86,6 +88,12 @@ pub struct BuildInfo {
     pub build_mode: BuildMode,
     /// Information about the `wasm-opt` optimization settings.
     pub wasm_opt_settings: WasmOptSettings,
+    /// The git revision the contract was built from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitInfo>,
+    /// The software bill of materials written alongside the contract, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sbom: Option<SbomInfo>,
 }
 
 impl TryFrom<BuildInfo> for serde_json::Map<String, serde_json::Value> {
@@ -106,13 +114,35 @@ pub struct WasmOptSettings {
     pub keep_debug_symbols: bool,
 }
 
+/// The software bill of materials of a contract build.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct SbomInfo {
+    /// The format of the SBOM.
+    pub format: SbomFormat,
+    /// The file name of the SBOM, relative to the contract bundle.
+    pub file: String,
+}
+
+/// The git revision a contract was built from.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct GitInfo {
+    /// The URL of the `origin` remote of the repository, if configured.
+    pub repository: Option<String>,
+    /// The hash of the commit which was built.
+    pub commit: String,
+    /// Whether the working tree contained uncommitted changes during the build.
+    pub dirty: bool,
+}
+
 /// Generates a file with metadata describing the ABI of the smart contract.
 ///
 /// It does so by generating and invoking a temporary workspace member.
 #[allow(clippy::too_many_arguments)]
 pub fn execute(
     crate_metadata: &CrateMetadata,
-    final_contract_wasm: &Path,
+    final_contract_code: &Path,
+    target: Target,
+    additional_code: &[(Target, PathBuf)],
     metadata_artifacts: &MetadataArtifacts,
     features: &Features,
     network: Network,
@@ -125,7 +155,13 @@ pub fn execute(
         source,
         contract,
         user,
-    } = extended_metadata(crate_metadata, final_contract_wasm, build_info)?;
+    } = extended_metadata(
+        crate_metadata,
+        final_contract_code,
+        target,
+        additional_code,
+        build_info,
+    )?;
 
     let generate_metadata = |manifest_path: &ManifestPath| -> Result<()> {
         verbose_eprintln!(
@@ -235,7 +271,9 @@ pub fn write_metadata(
 /// Generate the extended contract project metadata
 fn extended_metadata(
     crate_metadata: &CrateMetadata,
-    final_contract_wasm: &Path,
+    final_contract_code: &Path,
+    target: Target,
+    additional_code: &[(Target, PathBuf)],
     build_info: BuildInfo,
 ) -> Result<ExtendedMetadataResult> {
     let contract_package = &crate_metadata.root_package;
@@ -257,15 +295,35 @@ fn extended_metadata(
     let source = {
         let lang = SourceLanguage::new(Language::Ink, ink_version.clone());
         let compiler = SourceCompiler::new(Compiler::RustC, rust_version);
-        let wasm = fs::read(final_contract_wasm)?;
-        let hash = code_hash(wasm.as_slice());
-        Source::new(
-            Some(SourceWasm::new(wasm)),
-            hash.into(),
-            lang,
-            compiler,
-            Some(build_info.try_into()?),
-        )
+        let code = fs::read(final_contract_code)?;
+        let hash = crate::code_hash(code.as_slice());
+        let build_info = Some(build_info.try_into()?);
+        let mut source = match target {
+            Target::Wasm => {
+                Source::new(
+                    Some(SourceWasm::new(code)),
+                    hash.into(),
+                    lang,
+                    compiler,
+                    build_info,
+                )
+            }
+            Target::RiscV => {
+                Source::new_riscv(
+                    Some(SourceRiscV::new(code)),
+                    hash.into(),
+                    lang,
+                    compiler,
+                    build_info,
+                )
+            }
+        };
+        for (target, dest_code) in additional_code {
+            let code = fs::read(dest_code)?;
+            let hash = crate::code_hash(code.as_slice());
+            source.add_target((*target).into(), code, hash.into());
+        }
+        source
     };
 
     // Required contract fields
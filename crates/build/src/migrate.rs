@@ -0,0 +1,396 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migration of contracts to a newer major version of ink!.
+//!
+//! The `Cargo.toml` is migrated automatically, whereas the source code is only scanned
+//! for constructs which have to be migrated manually.
+
+use crate::{
+    Manifest,
+    ManifestPath,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use contract_metadata::compatibility::check_contract_ink_compatibility;
+use regex::Regex;
+use semver::{
+    Version,
+    VersionReq,
+};
+use std::{
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// The ink! version contracts are migrated to by `--to 5`.
+const INK_5_VERSION: &str = "5.0.0";
+
+/// The features of ink! 4 contracts which were renamed, with their ink! 5 names.
+const INK_5_FEATURE_RENAMES: &[(&str, &str)] = &[
+    ("ink_as_dependency", "ink-as-dependency"),
+    ("e2e_tests", "e2e-tests"),
+];
+
+/// A source code construct which has to be migrated manually.
+struct SourceRule {
+    /// Matches a line containing the construct.
+    pattern: &'static str,
+    /// Describes how to migrate the construct.
+    message: &'static str,
+}
+
+/// The source code changes required when migrating from ink! 4 to ink! 5.
+const INK_5_SOURCE_RULES: &[SourceRule] = &[
+    SourceRule {
+        pattern: r#"cfg_attr\(\s*not\(\s*feature\s*=\s*"std"\s*\)\s*,\s*no_std\s*\)"#,
+        message: "Add `no_main` to the crate attributes: \
+            `#![cfg_attr(not(feature = \"std\"), no_std, no_main)]`",
+    },
+    SourceRule {
+        pattern: r#"feature\s*=\s*"(ink_as_dependency|e2e_tests)""#,
+        message: "The feature was renamed in the `Cargo.toml`, use the new name: \
+            `ink-as-dependency` or `e2e-tests`",
+    },
+    SourceRule {
+        pattern: r"#\[ink\(event\)\]",
+        message: "The topics of events are calculated differently in ink! 5. Off-chain \
+            tooling relying on the topics of this event has to be updated, or the \
+            previous signature topic set with `#[ink(signature_topic = \"..\")]`",
+    },
+    SourceRule {
+        pattern: r"\bMapping<",
+        message: "`Mapping` panics if an encoded key or value exceeds the static buffer \
+            in ink! 5. Use `try_insert`, `try_get` and `try_take` to handle large values",
+    },
+    SourceRule {
+        pattern: r"derive\([^)]*\b(scale|scale_info)::",
+        message: "Replace the derives of `scale` and `scale_info` with \
+            `#[ink::scale_derive(Encode, Decode, TypeInfo)]`, the `scale` and \
+            `scale-info` dependencies can be removed afterwards if unused",
+    },
+    SourceRule {
+        pattern: r"#\[ink::chain_extension\]",
+        message: "Chain extensions require an id in ink! 5: \
+            `#[ink::chain_extension(extension = <id>)]`, function ids are `u16`",
+    },
+    SourceRule {
+        pattern: r"\bbuild_message\b",
+        message: "`ink_e2e::build_message` was removed, create calls with \
+            `contract.call_builder::<Contract>()` and submit them with \
+            `client.call(&caller, &call).submit()`",
+    },
+    SourceRule {
+        pattern: r"ink_e2e::Client<",
+        message: "E2E tests receive the client as `mut client: Client` in ink! 5",
+    },
+    SourceRule {
+        pattern: r"additional_contracts",
+        message: "`additional_contracts` was removed from `#[ink_e2e::test]`, the \
+            contracts are now built from the dev-dependencies",
+    },
+];
+
+/// The outcome of a migration.
+#[derive(Debug, serde::Serialize)]
+pub struct MigrationReport {
+    /// The ink! version requirement of the contract before the migration.
+    pub from: String,
+    /// The ink! version requirement of the contract after the migration.
+    pub to: String,
+    /// The changes applied to the `Cargo.toml`.
+    pub applied: Vec<String>,
+    /// The changes which have to be applied manually.
+    pub manual: Vec<ManualChange>,
+    /// Set if the migrated contract is not supported by this version of
+    /// `cargo-contract`.
+    pub compatibility: Option<String>,
+}
+
+/// A change which has to be applied manually.
+#[derive(Debug, serde::Serialize)]
+pub struct ManualChange {
+    /// The file which has to be changed.
+    pub file: PathBuf,
+    /// The line of the file which has to be changed, if known.
+    pub line: Option<usize>,
+    /// Describes the required change.
+    pub message: String,
+}
+
+/// Migrates the contract at `manifest_path` to the major version `to` of ink!.
+///
+/// The `Cargo.toml` is only modified if `dry_run` is `false`, in which case its
+/// formatting and comments are kept.
+pub fn migrate(
+    manifest_path: &ManifestPath,
+    to: u64,
+    dry_run: bool,
+) -> Result<MigrationReport> {
+    if to != 5 {
+        anyhow::bail!(
+            "Migrating to ink! {to} is not supported, contracts can be migrated to \
+            ink! 5"
+        );
+    }
+    let mut manifest = Manifest::new(manifest_path.clone())?;
+    if manifest.is_workspace_dependency("ink") {
+        anyhow::bail!(
+            "The `ink` dependency is inherited from the workspace, migrate the \
+            `[workspace.dependencies]` of the workspace instead"
+        );
+    }
+    let from = manifest
+        .dependency_version("ink")
+        .context("The contract has no `ink` dependency with a version")?
+        .to_string();
+    let from_major = VersionReq::parse(&from)
+        .ok()
+        .and_then(|req| req.comparators.first().map(|c| c.major))
+        .context(format!("Invalid `ink` version requirement `{from}`"))?;
+    if from_major >= to {
+        anyhow::bail!("The contract already uses ink! {from}");
+    }
+    if from_major != 4 {
+        anyhow::bail!(
+            "Only ink! 4 contracts can be migrated to ink! 5, migrate the contract to \
+            ink! 4 first"
+        );
+    }
+
+    let manifest_file = PathBuf::from(manifest_path.as_ref());
+    let mut applied = Vec::new();
+    let mut manual = Vec::new();
+
+    let ink_crates = manifest
+        .dependency_names()
+        .into_iter()
+        .filter(|name| *name == "ink" || name.starts_with("ink_"))
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    for ink_crate in ink_crates {
+        match manifest.dependency_version(&ink_crate) {
+            Some(version) => {
+                applied.push(format!(
+                    "Updated `{ink_crate}` from `{version}` to `{INK_5_VERSION}`"
+                ));
+                manifest.with_dependency_version(&ink_crate, INK_5_VERSION)?;
+            }
+            None => {
+                manual.push(ManualChange {
+                    file: manifest_file.clone(),
+                    line: None,
+                    message: format!(
+                        "Update `{ink_crate}` to ink! {INK_5_VERSION}, it is not fetched \
+                        from crates.io"
+                    ),
+                })
+            }
+        }
+    }
+
+    for (from, to) in INK_5_FEATURE_RENAMES {
+        if !manifest.has_feature(to) && manifest.rename_feature(from, to)? {
+            applied.push(format!("Renamed the `{from}` feature to `{to}`"));
+        }
+    }
+    // used when the contract is a dependency of another contract
+    if !manifest.has_feature("ink-as-dependency") {
+        manifest.with_feature("ink-as-dependency", &[])?;
+        applied.push("Added the `ink-as-dependency` feature".to_string());
+    }
+    // enables the E2E tests, which are only compiled if the feature is set
+    if manifest.dependency_names().contains(&"ink_e2e")
+        && !manifest.has_feature("e2e-tests")
+    {
+        manifest.with_feature("e2e-tests", &[])?;
+        applied.push("Added the `e2e-tests` feature".to_string());
+    }
+
+    let project_dir = manifest_path
+        .absolute_directory()
+        .context("Failed to determine the project directory")?;
+    manual.extend(scan_sources(&project_dir, INK_5_SOURCE_RULES)?);
+
+    let target_version = Version::parse(INK_5_VERSION)?;
+    let compatibility = check_contract_ink_compatibility(&target_version, None)
+        .err()
+        .map(|err| err.to_string());
+
+    if !dry_run {
+        manifest.save()?;
+    }
+
+    Ok(MigrationReport {
+        from,
+        to: INK_5_VERSION.to_string(),
+        applied,
+        manual,
+        compatibility,
+    })
+}
+
+/// Scans the Rust source files in `dir` for constructs matching the `rules`.
+///
+/// The `target` directory and hidden directories are skipped.
+fn scan_sources(dir: &Path, rules: &[SourceRule]) -> Result<Vec<ManualChange>> {
+    let rules = rules
+        .iter()
+        .map(|rule| Ok((Regex::new(rule.pattern)?, rule.message)))
+        .collect::<Result<Vec<_>>>()?;
+    let mut changes = Vec::new();
+    scan_dir(dir, &rules, &mut changes)?;
+    Ok(changes)
+}
+
+fn scan_dir(
+    dir: &Path,
+    rules: &[(Regex, &'static str)],
+    changes: &mut Vec<ManualChange>,
+) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for path in entries {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if path.is_dir() {
+            if file_name != "target" && !file_name.starts_with('.') {
+                scan_dir(&path, rules, changes)?;
+            }
+            continue
+        }
+        if path.extension() != Some("rs".as_ref()) {
+            continue
+        }
+        let source = fs::read_to_string(&path)
+            .context(format!("Failed to read {}", path.display()))?;
+        for (line, text) in source.lines().enumerate() {
+            for (pattern, message) in rules {
+                if pattern.is_match(text) {
+                    changes.push(ManualChange {
+                        file: path.clone(),
+                        line: Some(line + 1),
+                        message: message.to_string(),
+                    })
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests::with_tmp_dir;
+
+    const INK_4_MANIFEST: &str = r#"
+[package]
+name = "flipper"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+ink = { version = "4.3.0", default-features = false }
+scale = { package = "parity-scale-codec", version = "3", default-features = false }
+
+[dev-dependencies]
+ink_e2e = "4.3.0"
+
+[lib]
+path = "lib.rs"
+
+[features]
+# build for tests by default
+default = ["std"]
+std = ["ink/std", "scale/std"]
+ink_as_dependency = []
+"#;
+
+    const INK_4_SOURCE: &str = r#"#![cfg_attr(not(feature = "std"), no_std)]
+
+#[ink::contract]
+mod flipper {
+    #[derive(scale::Encode, scale::Decode)]
+    pub enum Error {}
+}
+"#;
+
+    #[test]
+    fn migrates_ink_4_manifest() {
+        with_tmp_dir(|path| {
+            let manifest_path = ManifestPath::new(path.join("Cargo.toml"))?;
+            fs::write(&manifest_path, INK_4_MANIFEST)?;
+            fs::write(path.join("lib.rs"), INK_4_SOURCE)?;
+
+            let report = migrate(&manifest_path, 5, false)?;
+
+            assert_eq!(report.from, "4.3.0");
+            assert_eq!(
+                report.applied,
+                [
+                    "Updated `ink` from `4.3.0` to `5.0.0`",
+                    "Updated `ink_e2e` from `4.3.0` to `5.0.0`",
+                    "Renamed the `ink_as_dependency` feature to `ink-as-dependency`",
+                    "Added the `e2e-tests` feature",
+                ]
+            );
+            let lines = report
+                .manual
+                .iter()
+                .map(|change| change.line)
+                .collect::<Vec<_>>();
+            assert_eq!(lines, [Some(1), Some(5)]);
+
+            let manifest = Manifest::new(manifest_path.clone())?;
+            assert_eq!(manifest.dependency_version("ink"), Some("5.0.0"));
+            assert_eq!(manifest.dependency_version("ink_e2e"), Some("5.0.0"));
+            assert_eq!(manifest.dependency_version("scale"), Some("3"));
+            assert!(manifest.has_feature("e2e-tests"));
+            assert!(!manifest.has_feature("ink_as_dependency"));
+            let migrated = fs::read_to_string(&manifest_path)?;
+            assert!(migrated
+                .contains("ink = { version = \"5.0.0\", default-features = false }"));
+            assert!(migrated.contains("# build for tests by default\n"));
+
+            let result = migrate(&manifest_path, 5, false);
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "The contract already uses ink! 5.0.0"
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn dry_run_does_not_modify_manifest() {
+        with_tmp_dir(|path| {
+            let manifest_path = ManifestPath::new(path.join("Cargo.toml"))?;
+            fs::write(&manifest_path, INK_4_MANIFEST)?;
+
+            migrate(&manifest_path, 5, true)?;
+
+            assert_eq!(fs::read_to_string(&manifest_path)?, INK_4_MANIFEST);
+            Ok(())
+        })
+    }
+}
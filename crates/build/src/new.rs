@@ -14,7 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Result;
+use crate::{
+    git,
+    Manifest,
+    ManifestPath,
+};
+use anyhow::{
+    Context,
+    Result,
+};
 use heck::ToUpperCamelCase as _;
 use std::{
     env,
@@ -31,8 +39,82 @@ use std::{
     },
 };
 
-/// Creates a new contract project from the template.
+/// The template used if none is specified.
+pub const DEFAULT_TEMPLATE: &str = "flipper";
+
+/// A project template which is built into `cargo contract new`.
+pub struct Template {
+    /// The name by which the template is selected.
+    pub name: &'static str,
+    /// A short description of the contract created from the template.
+    pub description: &'static str,
+    /// The zip archive of the template files.
+    archive: &'static [u8],
+}
+
+macro_rules! template {
+    ($name:literal, $description:literal) => {
+        Template {
+            name: $name,
+            description: $description,
+            archive: include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/templates/",
+                $name,
+                ".zip"
+            )),
+        }
+    };
+}
+
+const TEMPLATES: &[Template] = &[
+    template!("flipper", "A minimal contract storing a single boolean"),
+    template!("psp22", "A fungible token implementing the PSP22 standard"),
+    template!(
+        "psp34",
+        "A non-fungible token implementing the PSP34 standard"
+    ),
+    template!(
+        "upgradeable",
+        "A contract which replaces its own code using `set_code_hash`"
+    ),
+    template!(
+        "cross_contract",
+        "A contract instantiating and calling another contract of the project"
+    ),
+    template!(
+        "chain_extension",
+        "A contract using a chain extension through a custom environment"
+    ),
+];
+
+/// Returns the project templates built into `cargo contract new`.
+pub fn templates() -> &'static [Template] {
+    TEMPLATES
+}
+
+/// Creates a new contract project from the default template.
 pub fn new_contract_project<P>(name: &str, dir: Option<P>) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    new_contract_project_with_template(name, dir, None).map(|_| ())
+}
+
+/// Creates a new contract project from the given `template`.
+///
+/// The `template` is either the name of one of the built-in [`templates`], the URL of
+/// a git repository or the path to a local directory. The placeholders `{{name}}` and
+/// `{{camel_name}}` in the files of the template are replaced with the name of the
+/// contract, and files named `_Cargo.toml` are renamed to `Cargo.toml`.
+///
+/// If the project is created inside of a Cargo workspace, it is added to the workspace
+/// and the path to the workspace manifest is returned.
+pub fn new_contract_project_with_template<P>(
+    name: &str,
+    dir: Option<P>,
+    template: Option<&str>,
+) -> Result<Option<ManifestPath>>
 where
     P: AsRef<Path>,
 {
@@ -57,14 +139,195 @@ where
     if out_dir.join("Cargo.toml").exists() {
         anyhow::bail!("A Cargo package already exists in {}", name);
     }
-    if !out_dir.exists() {
-        fs::create_dir(&out_dir)?;
+
+    // a failed project is removed, so that `new` can be run again
+    let created = !out_dir.exists();
+    let result = write_template(name, &out_dir, template.unwrap_or(DEFAULT_TEMPLATE))
+        .and_then(|()| add_to_workspace(&out_dir));
+    if result.is_err() && created && out_dir.exists() {
+        fs::remove_dir_all(&out_dir)?;
     }
+    result
+}
+
+/// Writes the files of the `template` for the contract `name` to `out_dir`.
+fn write_template(name: &str, out_dir: &Path, template: &str) -> Result<()> {
+    if let Some(builtin) = TEMPLATES.iter().find(|t| t.name == template) {
+        if !out_dir.exists() {
+            fs::create_dir(out_dir)?;
+        }
+        unzip(builtin.archive, out_dir.to_path_buf(), Some(name))?;
+    } else if is_git_url(template) {
+        let tmp_dir = tempfile::tempdir()?;
+        let clone_dir = tmp_dir.path().join("template");
+        git::shallow_clone(template, &clone_dir)?;
+        copy_template_dir(&clone_dir, out_dir, name)?;
+    } else if Path::new(template).is_dir() {
+        copy_template_dir(Path::new(template), out_dir, name)?;
+    } else {
+        anyhow::bail!(
+            "Unknown template `{template}`. It is neither a built-in template, a git \
+            URL nor a directory.\n\
+            Use `cargo contract new --list-templates` to list the built-in templates."
+        );
+    }
+    Ok(())
+}
+
+/// Adds the project at `project_dir` to the enclosing Cargo workspace, if any.
+///
+/// The dependencies declared in `[workspace.dependencies]` are inherited by the
+/// project and its profiles are removed in favour of the ones of the workspace.
+fn add_to_workspace(project_dir: &Path) -> Result<Option<ManifestPath>> {
+    let project_dir = project_dir.canonicalize()?;
+    let Some((workspace_manifest_path, mut workspace)) = project_dir
+        .ancestors()
+        .skip(1)
+        .filter_map(|dir| ManifestPath::new(dir.join("Cargo.toml")).ok())
+        .filter(|path| path.as_ref().exists())
+        .find_map(|path| {
+            let manifest = Manifest::new(path.clone()).ok()?;
+            manifest.is_workspace_root().then_some((path, manifest))
+        })
+    else {
+        return Ok(None)
+    };
 
-    let template = include_bytes!(concat!(env!("OUT_DIR"), "/template.zip"));
+    let workspace_dir = workspace_manifest_path
+        .directory()
+        .context("workspace manifest has a parent directory")?;
+    let member = project_dir
+        .strip_prefix(workspace_dir)?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if workspace.is_excluded_workspace_member(&member) {
+        return Ok(None)
+    }
+    // nested packages, like the callee of the `cross_contract` template, are members
+    // as well since they are path dependencies inside of the workspace
+    let mut manifests = Vec::new();
+    for manifest_path in package_manifests(&project_dir)? {
+        let mut manifest = Manifest::new(manifest_path.clone())?;
+        if let Some(workspace_dependencies) = workspace.workspace_dependencies() {
+            manifest.with_workspace_dependencies(workspace_dependencies)?;
+        }
+        manifests.push((manifest_path, manifest));
+    }
+
+    if !workspace.is_workspace_member(&member) {
+        workspace.with_workspace_member(&member)?.save()?;
+    }
+    for (manifest_path, mut manifest) in manifests {
+        if manifest.remove_profiles() {
+            use colored::Colorize;
+            eprintln!(
+                "{} {}",
+                "warning:".yellow().bold(),
+                format!(
+                    "removed the profiles of {}, the profiles of the workspace are \
+                    used instead",
+                    manifest_path.as_ref().display()
+                )
+                .bold()
+            );
+        }
+        manifest.save()?;
+    }
+
+    Ok(Some(workspace_manifest_path))
+}
+
+/// Returns the paths of all `Cargo.toml` files in `dir` and its subdirectories.
+fn package_manifests(dir: &Path) -> Result<Vec<ManifestPath>> {
+    let mut manifests = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && !path.ends_with("target") {
+            manifests.extend(package_manifests(&path)?);
+        } else if path.file_name() == Some("Cargo.toml".as_ref()) {
+            manifests.push(ManifestPath::new(path)?);
+        }
+    }
+    Ok(manifests)
+}
+
+/// Returns `true` if the `template` refers to a git repository.
+fn is_git_url(template: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "git@"]
+        .iter()
+        .any(|prefix| template.starts_with(prefix))
+        || template.ends_with(".git")
+}
+
+/// Copies the template directory `src` to `out_dir`, replacing the placeholders for
+/// the contract `name`.
+///
+/// The `.git` and `target` directories of the template are skipped.
+fn copy_template_dir(src: &Path, out_dir: &Path, name: &str) -> Result<()> {
+    if !src.join("_Cargo.toml").exists() && !src.join("Cargo.toml").exists() {
+        anyhow::bail!(
+            "The template directory {} does not contain a `Cargo.toml` or `_Cargo.toml`",
+            src.display()
+        );
+    }
+    copy_template_files(src, src, out_dir, name)
+}
+
+fn copy_template_files(
+    template_root: &Path,
+    src: &Path,
+    out_dir: &Path,
+    name: &str,
+) -> Result<()> {
+    fs::create_dir_all(out_dir)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        if entry.file_type()?.is_dir() {
+            if file_name == ".git" || file_name == "target" {
+                continue
+            }
+            copy_template_files(template_root, &path, &out_dir.join(&file_name), name)?;
+            continue
+        }
+
+        let outpath = if file_name == "_Cargo.toml" {
+            out_dir.join("Cargo.toml")
+        } else {
+            out_dir.join(&file_name)
+        };
+        let relative_path = path.strip_prefix(template_root).unwrap_or(&path);
+        let mut outfile = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&outpath)
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::AlreadyExists {
+                    anyhow::anyhow!("File {} already exists", relative_path.display())
+                } else {
+                    anyhow::anyhow!(e)
+                }
+            })?;
 
-    unzip(template, out_dir, Some(name))?;
+        let contents = fs::read(&path)
+            .context(format!("Failed to read template file {}", path.display()))?;
+        match String::from_utf8(contents) {
+            Ok(contents) => {
+                let contents = contents
+                    .replace("{{name}}", name)
+                    .replace("{{camel_name}}", &name.to_upper_camel_case());
+                outfile.write_all(contents.as_bytes())?;
+            }
+            // binary files are copied as they are
+            Err(err) => outfile.write_all(err.as_bytes())?,
+        }
 
+        #[cfg(unix)]
+        fs::set_permissions(&outpath, entry.metadata()?.permissions())?;
+    }
     Ok(())
 }
 
@@ -176,6 +439,132 @@ mod tests {
         })
     }
 
+    #[test]
+    fn builtin_templates_are_unpacked() {
+        with_tmp_dir(|path| {
+            for template in templates() {
+                let name = format!("new_{}", template.name);
+                new_contract_project_with_template(
+                    &name,
+                    Some(path),
+                    Some(template.name),
+                )?;
+                let manifest = fs::read_to_string(path.join(&name).join("Cargo.toml"))?;
+                assert!(manifest.contains(&format!("name = \"{name}\"")));
+                let lib = fs::read_to_string(path.join(&name).join("lib.rs"))?;
+                assert!(!lib.contains("{{"), "unreplaced placeholder in {name}");
+            }
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn directory_template_is_copied_with_replacements() {
+        with_tmp_dir(|path| {
+            let template = path.join("my_template");
+            fs::create_dir_all(template.join("src"))?;
+            fs::create_dir_all(template.join("target"))?;
+            fs::write(template.join("_Cargo.toml"), "name = \"{{name}}\"")?;
+            fs::write(
+                template.join("src").join("lib.rs"),
+                "struct {{camel_name}};",
+            )?;
+            fs::write(template.join("target").join("artifact"), "")?;
+            fs::write(template.join("logo.bin"), [0xff, 0xfe])?;
+
+            new_contract_project_with_template(
+                "my_contract",
+                Some(path),
+                Some(template.to_str().unwrap()),
+            )?;
+
+            let project = path.join("my_contract");
+            assert_eq!(
+                fs::read_to_string(project.join("Cargo.toml"))?,
+                "name = \"my_contract\""
+            );
+            assert_eq!(
+                fs::read_to_string(project.join("src").join("lib.rs"))?,
+                "struct MyContract;"
+            );
+            assert_eq!(fs::read(project.join("logo.bin"))?, [0xff, 0xfe]);
+            assert!(!project.join("target").exists());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn project_is_added_to_enclosing_workspace() {
+        with_tmp_dir(|path| {
+            fs::write(
+                path.join("Cargo.toml"),
+                "# the contracts of the project\n[workspace]\nmembers = []\n\n\
+                [workspace.dependencies]\n\
+                ink = { version = \"5.0.0\", default-features = false } # pinned\n",
+            )?;
+            fs::create_dir(path.join("contracts"))?;
+
+            let workspace_manifest = new_contract_project_with_template(
+                "flipper",
+                Some(path.join("contracts")),
+                None,
+            )?;
+            assert_eq!(
+                workspace_manifest.map(PathBuf::from),
+                Some(path.canonicalize()?.join("Cargo.toml"))
+            );
+
+            let workspace = fs::read_to_string(path.join("Cargo.toml"))?;
+            assert!(workspace.starts_with("# the contracts of the project\n"));
+            assert!(workspace.contains("default-features = false } # pinned"));
+            let workspace: toml::Table = toml::from_str(&workspace)?;
+            assert_eq!(
+                workspace["workspace"]["members"],
+                vec![toml::Value::from("contracts/flipper")].into()
+            );
+            let manifest: toml::Table = toml::from_str(&fs::read_to_string(
+                path.join("contracts").join("flipper").join("Cargo.toml"),
+            )?)?;
+            assert_eq!(
+                manifest["dependencies"]["ink"],
+                toml::toml! { workspace = true }.into()
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn workspace_with_incompatible_dependency_is_not_changed() {
+        with_tmp_dir(|path| {
+            let workspace_toml = "[workspace]\nmembers = []\n\n\
+                [workspace.dependencies]\nink = \"4.3.0\"\n";
+            fs::write(path.join("Cargo.toml"), workspace_toml)?;
+
+            let result = new_contract_project("flipper", Some(path));
+
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("`ink` is declared with version `4.3.0` in the workspace"));
+            assert_eq!(fs::read_to_string(path.join("Cargo.toml"))?, workspace_toml);
+            assert!(!path.join("flipper").exists());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_unknown_template() {
+        with_tmp_dir(|path| {
+            let result =
+                new_contract_project_with_template("xxx", Some(path), Some("unknown"));
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .starts_with("Unknown template `unknown`"));
+            Ok(())
+        })
+    }
+
     #[test]
     fn contract_cargo_project_already_exists() {
         with_tmp_dir(|path| {
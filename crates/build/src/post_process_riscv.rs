@@ -0,0 +1,231 @@
+// Copyright 2018-2024 Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    fs,
+    path::Path,
+};
+
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use colored::Colorize;
+use polkavm_linker::{
+    Config,
+    ProgramBlob,
+};
+
+/// The functions a contract has to export, i.e. the entry points of the contract.
+const EXPORTS: [&str; 2] = ["call", "deploy"];
+
+/// The host functions a RISC-V contract is allowed to import.
+const HOST_FUNCTIONS: &[&str] = &[
+    "address",
+    "balance",
+    "block_number",
+    "call",
+    "call_chain_extension",
+    "call_runtime",
+    "caller",
+    "caller_is_origin",
+    "clear_storage",
+    "code_hash",
+    "contains_storage",
+    "debug_message",
+    "delegate_call",
+    "deposit_event",
+    "ecdsa_recover",
+    "ecdsa_to_eth_address",
+    "gas_left",
+    "get_storage",
+    "hash_blake2_128",
+    "hash_blake2_256",
+    "hash_keccak_256",
+    "hash_sha2_256",
+    "input",
+    "instantiate",
+    "is_contract",
+    "minimum_balance",
+    "now",
+    "own_code_hash",
+    "seal_return",
+    "set_code_hash",
+    "set_storage",
+    "sr25519_verify",
+    "take_storage",
+    "terminate",
+    "transfer",
+    "value_transferred",
+    "weight_to_fee",
+    "xcm_execute",
+    "xcm_send",
+];
+
+/// Links the RISC-V ELF at `original_code` into a program blob for the chain and
+/// writes it to `dest_code`.
+///
+/// The linker strips the debug symbols and optimizes the program. Unless
+/// `skip_validation` is set, the program must only import the supported host
+/// functions and only export the entry points `call` and `deploy`.
+pub fn post_process_riscv(
+    original_code: &Path,
+    dest_code: &Path,
+    skip_validation: bool,
+) -> Result<()> {
+    let elf = fs::read(original_code).context(format!(
+        "Loading of the RISC-V ELF at '{}' failed",
+        original_code.display(),
+    ))?;
+    let mut config = Config::default();
+    config.set_strip(true);
+    config.set_optimize(true);
+    let blob = polkavm_linker::program_from_elf(config, &elf)
+        .map_err(|err| anyhow!("Failed to link polkavm program: {err}"))?;
+
+    if !skip_validation {
+        validate(&blob)?;
+    }
+
+    fs::write(dest_code, blob.as_bytes())?;
+    Ok(())
+}
+
+/// Validates the imports and exports of the linked program.
+fn validate(blob: &ProgramBlob) -> Result<()> {
+    let parse_err = |err| anyhow!("Failed to parse the polkavm program: {err}");
+    let mut imports = Vec::new();
+    for import in blob.imports() {
+        let import = import.map_err(parse_err)?;
+        imports.push(String::from_utf8_lossy(&import.symbol()[..]).into_owned());
+    }
+    let mut exports = Vec::new();
+    for export in blob.exports() {
+        let export = export.map_err(parse_err)?;
+        exports.push(String::from_utf8_lossy(&export.symbol()[..]).into_owned());
+    }
+    validate_symbols(&imports, &exports)
+}
+
+/// Returns `Ok` if only supported host functions are imported and only the entry
+/// points are exported.
+fn validate_symbols(imports: &[String], exports: &[String]) -> Result<()> {
+    let mut errs = Vec::new();
+    for import in imports {
+        if !HOST_FUNCTIONS.contains(&import.as_str()) {
+            errs.push(format!(
+                "An unexpected import function was found in the contract program: \
+                `{import}`.\n\
+                Only the host functions of `pallet-contracts` can be imported, this \
+                points to a dependency using an unsupported feature of the target."
+            ));
+        }
+    }
+    for export in exports {
+        if !EXPORTS.contains(&export.as_str()) {
+            errs.push(format!(
+                "An unexpected export was found in the contract program: `{export}`.\n\
+                A contract must only export the `call` and `deploy` entry points."
+            ));
+        }
+    }
+    for entry_point in EXPORTS {
+        if !exports.iter().any(|export| export == entry_point) {
+            errs.push(format!(
+                "Missing the required export `{entry_point}` in the contract program."
+            ));
+        }
+    }
+
+    if !errs.is_empty() {
+        anyhow::bail!(format!(
+            "Validation of the RISC-V program failed.\n\n\n{}\n\nIgnore with \
+            `--skip-validation`",
+            errs.into_iter()
+                .map(|err| format!("{} {}", "ERROR:".to_string().bold(), err))
+                .collect::<Vec<String>>()
+                .join("\n\n\n")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptimizationResult;
+
+    fn symbols(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn accepts_host_functions_and_entry_points() {
+        let imports = symbols(&["input", "seal_return", "get_storage"]);
+        let exports = symbols(&["call", "deploy"]);
+
+        assert!(validate_symbols(&imports, &exports).is_ok());
+    }
+
+    #[test]
+    fn links_elf_into_program_blob() {
+        crate::util::tests::with_tmp_dir(|path| {
+            // `fixtures/riscv/contract.rs` with `polkavm-derive = "=0.9.1"`, built for
+            // `riscv32em-unknown-none-elf` with `-Zbuild-std=core`, `opt-level = "z"`
+            // and `-Cembed-bitcode=no -Clink-arg=--emit-relocs -Clink-arg=--relocatable`
+            let original_code = path.join("contract.elf");
+            let dest_code = path.join("contract.riscv");
+            fs::write(
+                &original_code,
+                include_bytes!("../fixtures/riscv/contract.elf"),
+            )?;
+
+            post_process_riscv(&original_code, &dest_code, false)?;
+
+            let blob = ProgramBlob::parse(fs::read(&dest_code)?)
+                .map_err(|err| anyhow!("{err}"))?;
+            let mut exports = Vec::new();
+            for export in blob.exports() {
+                let export = export.map_err(|err| anyhow!("{err}"))?;
+                exports.push(String::from_utf8_lossy(&export.symbol()[..]).into_owned());
+            }
+            exports.sort();
+            assert_eq!(exports, ["call", "deploy"]);
+
+            let result = OptimizationResult::from_files(&original_code, &dest_code)?;
+            assert!(result.optimized_size > 0.0);
+            assert!(result.optimized_size < result.original_size);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_unknown_imports_and_exports() {
+        let imports = symbols(&["input", "fd_write"]);
+        let exports = symbols(&["call", "main"]);
+
+        let err = validate_symbols(&imports, &exports)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.starts_with("Validation of the RISC-V program failed."));
+        assert!(err.contains("`fd_write`"));
+        assert!(err.contains("`main`"));
+        assert!(err.contains("Missing the required export `deploy`"));
+        assert!(!err.contains("`input`"));
+    }
+}
@@ -24,6 +24,7 @@ use std::{
 
 use colored::Colorize;
 use wasm_encoder::{
+    CustomSection,
     EntityType,
     ExportSection,
     ImportSection,
@@ -44,7 +45,10 @@ use anyhow::{
     Result,
 };
 
+use contract_analyze::CODE_HASH_SECTION;
+
 use crate::{
+    code_hash,
     validate_wasm,
     verbose_eprintln,
     Verbosity,
@@ -96,7 +100,7 @@ fn ensure_maximum_memory_pages(
 /// Strips all custom sections.
 ///
 /// Presently all custom sections are not required so they can be stripped safely.
-/// The name section is already stripped by `wasm-opt`.
+/// The name section is only kept if the debug symbols are kept.
 fn strip_custom_sections(name: &str) -> bool {
     !(name.starts_with("reloc.") || name == "name")
 }
@@ -147,12 +151,62 @@ pub fn post_process_wasm(
     Ok(())
 }
 
+/// Performs required post-processing steps on the Wasm optimized with debug symbols,
+/// and splits it into the deployable code and a companion debug file.
+///
+/// The deployable code is written to `code`, it only keeps the name section if
+/// `keep_debug_symbols` is set. The debug file keeps all custom sections, e.g. the names
+/// and DWARF, and the code hash of the deployable code in the [`CODE_HASH_SECTION`].
+/// Since both are stripped from the same module, the function indices of the deployable
+/// code refer to the same functions in the debug file.
+pub fn post_process_wasm_with_debug_file(
+    optimized_debug_code: &Path,
+    code: &Path,
+    keep_debug_symbols: bool,
+    skip_wasm_validation: bool,
+    verbosity: &Verbosity,
+    max_memory_pages: u64,
+) -> Result<()> {
+    let debug_module = load_module(optimized_debug_code)
+        .context("Loading of optimized wasm with debug symbols failed")?;
+    let output = process_module(&debug_module, max_memory_pages, |name| {
+        keep_debug_symbols && !strip_custom_sections(name)
+    })?;
+    let mut debug_output = process_module(&debug_module, max_memory_pages, |_| true)?;
+    // the debug output is validated for determinism to report the function names
+    validate(&output, &debug_output, skip_wasm_validation, verbosity)?;
+
+    CustomSection {
+        name: CODE_HASH_SECTION.into(),
+        data: code_hash(&output).as_slice().into(),
+    }
+    .append_to(&mut debug_output);
+
+    fs::write(code, output)?;
+    fs::write(optimized_debug_code, debug_output)?;
+    Ok(())
+}
+
 /// Performs required post-processing steps on the Wasm in the buffer.
 pub fn post_process_module(
     module: &[u8],
     skip_wasm_validation: bool,
     verbosity: &Verbosity,
     max_memory_pages: u64,
+) -> Result<Vec<u8>> {
+    let output = process_module(module, max_memory_pages, |name| {
+        !strip_custom_sections(name)
+    })?;
+    validate(&output, &output, skip_wasm_validation, verbosity)?;
+    Ok(output)
+}
+
+/// Strips the exports and adjusts the memory import of the module, only custom sections
+/// for which `keep_custom_section` returns `true` are kept.
+fn process_module(
+    module: &[u8],
+    max_memory_pages: u64,
+    keep_custom_section: impl Fn(&str) -> bool,
 ) -> Result<Vec<u8>> {
     let mut output = Vec::new();
     for payload in Parser::new(0).parse_all(module) {
@@ -169,7 +223,7 @@ pub fn post_process_module(
             }
             Payload::End(_) => break,
             Payload::CustomSection(ref c) => {
-                if strip_custom_sections(c.name()) {
+                if !keep_custom_section(c.name()) {
                     // Strip custom section
                     continue
                 }
@@ -200,9 +254,25 @@ pub fn post_process_module(
         !output.is_empty(),
         "resulting wasm size of post processing must be > 0"
     );
+    Ok(output)
+}
 
+/// Validates the imports and the determinism of the post-processed code, unless
+/// skipped.
+///
+/// The determinism is validated on the `named_code`, which only differs from the `code`
+/// by its custom sections.
+fn validate(
+    code: &[u8],
+    named_code: &[u8],
+    skip_wasm_validation: bool,
+    verbosity: &Verbosity,
+) -> Result<()> {
     if !skip_wasm_validation {
-        validate_wasm::validate_import_section(&output)?;
+        validate_wasm::validate_import_section(code)?;
+        for warning in validate_wasm::validate_determinism(named_code)? {
+            eprintln!("{} {}", "warning:".yellow().bold(), warning.bold());
+        }
     } else {
         verbose_eprintln!(
             verbosity,
@@ -212,8 +282,7 @@ pub fn post_process_module(
                 .bold()
         );
     }
-
-    Ok(output)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -359,4 +428,63 @@ mod unit_tests {
         // then
         assert_eq!(module, output);
     }
+
+    #[test]
+    fn post_process_wasm_writes_debug_file() {
+        crate::util::tests::with_tmp_dir(|path| {
+            // given
+            let contract = r#"
+                (module
+                    (type (;0;) (func))
+                    (import "env" "memory" (memory (;0;) 2 16))
+                    (func $call (type 0))
+                    (func $deploy (type 0))
+                    (export "call" (func $call))
+                    (export "deploy" (func $deploy))
+                )"#;
+            let debug_module = wabt::wat2wasm(contract).expect("Invalid wabt");
+            let code_path = path.join("contract.wasm");
+            let debug_path = path.join("contract.debug.wasm");
+            let custom_sections = |code: &[u8]| {
+                Parser::new(0)
+                    .parse_all(code)
+                    .filter_map(|payload| {
+                        match payload.unwrap() {
+                            Payload::CustomSection(c) => {
+                                Some((c.name().to_string(), c.data().to_vec()))
+                            }
+                            _ => None,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            for keep_debug_symbols in [false, true] {
+                fs::write(&debug_path, &debug_module)?;
+
+                // when
+                post_process_wasm_with_debug_file(
+                    &debug_path,
+                    &code_path,
+                    keep_debug_symbols,
+                    false,
+                    &Verbosity::Verbose,
+                    16,
+                )?;
+
+                // then
+                let code = fs::read(&code_path)?;
+                let debug_code = fs::read(&debug_path)?;
+                let code_sections = custom_sections(&code);
+                assert_eq!(code_sections.len(), usize::from(keep_debug_symbols));
+                let debug_sections = custom_sections(&debug_code);
+                assert_eq!(debug_sections[0].0, "name");
+                assert_eq!(
+                    debug_sections[1],
+                    (CODE_HASH_SECTION.to_string(), code_hash(&code).to_vec())
+                );
+            }
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,469 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Software bill of materials (SBOM) of a contract build.
+//!
+//! The SBOM lists the packages of the `cargo metadata` dependency graph of the contract,
+//! with the checksums recorded in the `Cargo.lock`, and identifies the contract by its
+//! code hash.
+
+use crate::{
+    code_hash,
+    CrateMetadata,
+    SbomFormat,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use cargo_metadata::{
+    DependencyKind,
+    Metadata as CargoMetadata,
+    Package,
+    PackageId,
+};
+use chrono::{
+    DateTime,
+    SecondsFormat,
+};
+use serde_json::{
+    json,
+    Value,
+};
+use std::{
+    collections::{
+        HashMap,
+        HashSet,
+        VecDeque,
+    },
+    fs,
+    path::PathBuf,
+    time::SystemTime,
+};
+
+const CRATES_IO_SOURCE: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+/// The checksums of the `Cargo.lock` by the name, version and source of the packages.
+type Checksums = HashMap<(String, String, String), String>;
+
+/// A package of the dependency graph of the contract.
+struct SbomPackage<'a> {
+    package: &'a Package,
+    /// The SHA-256 checksum of the package from the `Cargo.lock`.
+    checksum: Option<&'a str>,
+    dependencies: Vec<&'a PackageId>,
+}
+
+impl SbomPackage<'_> {
+    fn purl(&self) -> Option<String> {
+        let source = self.package.source.as_ref()?;
+        (source.repr == CRATES_IO_SOURCE)
+            .then(|| format!("pkg:cargo/{}@{}", self.package.name, self.package.version))
+    }
+}
+
+/// Writes the SBOM of the contract in `format` next to the contract `code`.
+///
+/// Returns the path of the written file.
+pub fn write_sbom(
+    crate_metadata: &CrateMetadata,
+    format: SbomFormat,
+    code: &[u8],
+) -> Result<PathBuf> {
+    let metadata = &crate_metadata.cargo_meta;
+    let lockfile = metadata.workspace_root.join("Cargo.lock");
+    let lockfile = if lockfile.exists() {
+        fs::read_to_string(&lockfile)
+            .with_context(|| format!("Failed to read {lockfile}"))?
+    } else {
+        String::new()
+    };
+    let checksums = lockfile_checksums(&lockfile)?;
+    let packages =
+        collect_packages(metadata, &crate_metadata.root_package.id, &checksums)?;
+    let code_hash = hex::encode(code_hash(code));
+    let document = match format {
+        SbomFormat::CycloneDx => cyclonedx(&packages, &code_hash, &timestamp()),
+        SbomFormat::Spdx => spdx(&packages, &code_hash, &timestamp()),
+    };
+
+    let path = crate_metadata.target_directory.join(format!(
+        "{}.{}",
+        crate_metadata.contract_artifact_name,
+        format.extension()
+    ));
+    fs::write(&path, serde_json::to_string_pretty(&document)?)?;
+    Ok(path)
+}
+
+/// Returns the checksums of the `Cargo.lock`.
+fn lockfile_checksums(lockfile: &str) -> Result<Checksums> {
+    #[derive(serde::Deserialize)]
+    struct Lockfile {
+        #[serde(default)]
+        package: Vec<LockedPackage>,
+    }
+    #[derive(serde::Deserialize)]
+    struct LockedPackage {
+        name: String,
+        version: String,
+        source: Option<String>,
+        checksum: Option<String>,
+    }
+
+    let lockfile: Lockfile = toml::from_str(lockfile).context("Invalid Cargo.lock")?;
+    Ok(lockfile
+        .package
+        .into_iter()
+        .filter_map(|package| {
+            Some((
+                (package.name, package.version, package.source?),
+                package.checksum?,
+            ))
+        })
+        .collect())
+}
+
+/// Collects the packages the contract `root` depends on, excluding dev-dependencies.
+fn collect_packages<'a>(
+    metadata: &'a CargoMetadata,
+    root: &'a PackageId,
+    checksums: &'a Checksums,
+) -> Result<Vec<SbomPackage<'a>>> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .context("Missing the dependency graph in the `cargo metadata`")?;
+    let nodes = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, node))
+        .collect::<HashMap<_, _>>();
+
+    let mut packages = Vec::new();
+    let mut visited = HashSet::from([root]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        let package = metadata
+            .packages
+            .iter()
+            .find(|package| package.id == *id)
+            .context(format!("Package {id} not found in the `cargo metadata`"))?;
+        let node = nodes
+            .get(id)
+            .context(format!("Package {id} not found in the dependency graph"))?;
+        let dependencies = node
+            .deps
+            .iter()
+            .filter(|dep| {
+                dep.dep_kinds
+                    .iter()
+                    .any(|kind| kind.kind != DependencyKind::Development)
+            })
+            .map(|dep| &dep.pkg)
+            .collect::<Vec<_>>();
+        for dependency in &dependencies {
+            if visited.insert(*dependency) {
+                queue.push_back(dependency);
+            }
+        }
+        let checksum = package.source.as_ref().and_then(|source| {
+            let key = (
+                package.name.clone(),
+                package.version.to_string(),
+                source.repr.clone(),
+            );
+            checksums.get(&key).map(String::as_str)
+        });
+        packages.push(SbomPackage {
+            package,
+            checksum,
+            dependencies,
+        })
+    }
+    // the contract stays first
+    packages[1..].sort_by(|a, b| {
+        (&a.package.name, &a.package.version).cmp(&(&b.package.name, &b.package.version))
+    });
+    Ok(packages)
+}
+
+/// Creates a CycloneDX 1.5 document, the first package is the contract.
+fn cyclonedx(packages: &[SbomPackage], code_hash: &str, timestamp: &str) -> Value {
+    let component = |package: &SbomPackage, kind: &str| {
+        let mut component = json!({
+            "type": kind,
+            "bom-ref": package.package.id.repr,
+            "name": package.package.name,
+            "version": package.package.version.to_string(),
+        });
+        if let Some(license) = &package.package.license {
+            component["licenses"] = json!([{ "expression": license }]);
+        }
+        if let Some(purl) = package.purl() {
+            component["purl"] = json!(purl);
+        }
+        if let Some(checksum) = package.checksum {
+            component["hashes"] = json!([{ "alg": "SHA-256", "content": checksum }]);
+        }
+        component
+    };
+
+    let contract = &packages[0];
+    let mut contract_component = component(contract, "application");
+    contract_component["hashes"] =
+        json!([{ "alg": "BLAKE2b-256", "content": code_hash }]);
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp,
+            "tools": [{
+                "vendor": "Use Ink",
+                "name": "cargo-contract",
+                "version": env!("CARGO_PKG_VERSION"),
+            }],
+            "component": contract_component,
+        },
+        "components": packages[1..]
+            .iter()
+            .map(|package| component(package, "library"))
+            .collect::<Vec<_>>(),
+        "dependencies": packages
+            .iter()
+            .map(|package| {
+                json!({
+                    "ref": package.package.id.repr,
+                    "dependsOn": package
+                        .dependencies
+                        .iter()
+                        .map(|dependency| &dependency.repr)
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Creates an SPDX 2.3 document, the first package is the contract.
+fn spdx(packages: &[SbomPackage], code_hash: &str, timestamp: &str) -> Value {
+    let spdx_ids = packages
+        .iter()
+        .enumerate()
+        .map(|(index, package)| {
+            // SPDX identifiers only consist of letters, numbers, `.` and `-`
+            let name = package
+                .package
+                .name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect::<String>();
+            (
+                &package.package.id,
+                format!("SPDXRef-Package-{index}-{name}"),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    let contract = &packages[0];
+    let spdx_packages = packages
+        .iter()
+        .map(|package| {
+            let mut spdx_package = json!({
+                "name": package.package.name,
+                "SPDXID": spdx_ids[&package.package.id],
+                "versionInfo": package.package.version.to_string(),
+                "downloadLocation": "NOASSERTION",
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": package
+                    .package
+                    .license
+                    .as_deref()
+                    .unwrap_or("NOASSERTION"),
+                "copyrightText": "NOASSERTION",
+            });
+            if let Some(purl) = package.purl() {
+                spdx_package["downloadLocation"] = json!(format!(
+                    "https://crates.io/api/v1/crates/{}/{}/download",
+                    package.package.name, package.package.version
+                ));
+                spdx_package["externalRefs"] = json!([{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": purl,
+                }]);
+            }
+            if let Some(checksum) = package.checksum {
+                spdx_package["checksums"] =
+                    json!([{ "algorithm": "SHA256", "checksumValue": checksum }]);
+            }
+            spdx_package
+        })
+        .collect::<Vec<_>>();
+
+    let mut contract_package = spdx_packages[0].clone();
+    contract_package["checksums"] =
+        json!([{ "algorithm": "BLAKE2b-256", "checksumValue": code_hash }]);
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": spdx_ids[&contract.package.id],
+    })];
+    for package in packages {
+        for dependency in &package.dependencies {
+            relationships.push(json!({
+                "spdxElementId": spdx_ids[&package.package.id],
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": spdx_ids[dependency],
+            }))
+        }
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{}-{}", contract.package.name, contract.package.version),
+        "documentNamespace": format!(
+            "https://use.ink/spdx/{}-{}-{code_hash}",
+            contract.package.name, contract.package.version
+        ),
+        "creationInfo": {
+            "created": timestamp,
+            "creators": [format!("Tool: cargo-contract-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": std::iter::once(contract_package)
+            .chain(spdx_packages.into_iter().skip(1))
+            .collect::<Vec<_>>(),
+        "relationships": relationships,
+    })
+}
+
+/// The creation time of the SBOM in the RFC 3339 format.
+///
+/// The `SOURCE_DATE_EPOCH` environment variable is used if set, for reproducible
+/// builds.
+fn timestamp() -> String {
+    let time = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_else(|| SystemTime::now().into());
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests::with_tmp_dir;
+    use cargo_metadata::MetadataCommand;
+
+    #[test]
+    fn lists_dependencies_without_dev_dependencies() {
+        with_tmp_dir(|dir| {
+            for (name, dependencies) in [
+                (
+                    "contract",
+                    "[dependencies]\nlib_dep = { path = \"../lib_dep\" }\n\
+                    [dev-dependencies]\ndev_dep = { path = \"../dev_dep\" }",
+                ),
+                ("lib_dep", ""),
+                ("dev_dep", ""),
+            ] {
+                let crate_dir = dir.join(name);
+                fs::create_dir(&crate_dir)?;
+                fs::write(
+                    crate_dir.join("Cargo.toml"),
+                    format!(
+                        "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n\
+                        license = \"Apache-2.0\"\n\n[lib]\npath = \"lib.rs\"\n\n\
+                        {dependencies}\n"
+                    ),
+                )?;
+                fs::write(crate_dir.join("lib.rs"), "")?;
+            }
+            let metadata = MetadataCommand::new()
+                .manifest_path(dir.join("contract").join("Cargo.toml"))
+                .exec()?;
+            let root = metadata.resolve.as_ref().unwrap().root.clone().unwrap();
+
+            let checksums = Checksums::new();
+            let packages = collect_packages(&metadata, &root, &checksums)?;
+            let names = packages
+                .iter()
+                .map(|package| package.package.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["contract", "lib_dep"]);
+
+            let bom = cyclonedx(&packages, "00", "1970-01-01T00:00:00Z");
+            assert_eq!(bom["metadata"]["component"]["name"], "contract");
+            assert_eq!(
+                bom["metadata"]["component"]["hashes"][0]["content"],
+                json!("00")
+            );
+            assert_eq!(bom["components"][0]["name"], "lib_dep");
+            assert_eq!(
+                bom["components"][0]["licenses"][0]["expression"],
+                "Apache-2.0"
+            );
+            assert_eq!(
+                bom["dependencies"][0]["dependsOn"][0],
+                json!(packages[1].package.id.repr)
+            );
+
+            let document = spdx(&packages, "00", "1970-01-01T00:00:00Z");
+            assert_eq!(
+                document["packages"][1]["SPDXID"],
+                "SPDXRef-Package-1-lib-dep"
+            );
+            assert_eq!(
+                document["relationships"][1]["relatedSpdxElement"],
+                "SPDXRef-Package-1-lib-dep"
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn reads_lockfile_checksums() {
+        let lockfile = r#"
+version = 3
+
+[[package]]
+name = "contract"
+version = "0.1.0"
+
+[[package]]
+name = "hex"
+version = "0.4.3"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "7f24254aa9a54b5c858eaee2f5bccdb46aaf0e486a595ed5fd8f86ba55232a70"
+"#;
+        let checksums = lockfile_checksums(lockfile).unwrap();
+        assert_eq!(checksums.len(), 1);
+        let key = (
+            "hex".to_string(),
+            "0.4.3".to_string(),
+            CRATES_IO_SOURCE.to_string(),
+        );
+        assert_eq!(
+            checksums[&key],
+            "7f24254aa9a54b5c858eaee2f5bccdb46aaf0e486a595ed5fd8f86ba55232a70"
+        );
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of dependencies which are built with `std` for the on-chain target.
+//!
+//! The features reported by `cargo metadata` are unified over all targets and
+//! dependency kinds, e.g. a dev-dependency on `ink_e2e` enables `std` for most crates.
+//! Therefore the features of the on-chain build, i.e. the normal dependencies of the
+//! contract built without its default features, are taken from the feature graph
+//! printed by `cargo tree`.
+
+use crate::{
+    util,
+    Features,
+    ManifestPath,
+    Target,
+    Verbosity,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+
+/// A dependency which is built with `std` for the on-chain target.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct StdDependency {
+    /// The name and version of the package.
+    pub package: String,
+    /// Describes which dependency enables `std`.
+    pub reason: String,
+    /// The names of the packages from the contract to the dependency.
+    pub path: Vec<String>,
+}
+
+/// Finds the dependencies of the contract which are built with `std` when building the
+/// contract for `target` with `features`.
+///
+/// Dependencies which only enable `std` because the `std` feature of a dependent crate
+/// enables it are not reported, the dependent crate is reported instead.
+pub fn find_std_dependencies(
+    manifest_path: &ManifestPath,
+    features: &Features,
+    target: &Target,
+) -> Result<Vec<StdDependency>> {
+    let mut args = vec![
+        manifest_path.cargo_arg()?,
+        format!("--target={}", target.llvm_target()),
+        "--no-default-features".to_owned(),
+        "--edges=normal,features".to_owned(),
+        "--prefix=depth".to_owned(),
+    ];
+    features.append_to_args(&mut args);
+    let output = util::cargo_cmd(
+        "tree",
+        args,
+        manifest_path.directory(),
+        Verbosity::Quiet,
+        vec![],
+    )
+    .stdout_capture()
+    .run()
+    .with_context(|| {
+        format!(
+            "Error invoking `cargo tree` for {}",
+            manifest_path.as_ref().display()
+        )
+    })?;
+    let tree = String::from_utf8(output.stdout)?;
+    Ok(parse_std_dependencies(&tree))
+}
+
+/// A line of the `cargo tree --edges features --prefix depth` output.
+#[derive(Debug)]
+enum Node<'a> {
+    /// A package, e.g. `ink v5.0.0`.
+    Package {
+        name: &'a str,
+        version: &'a str,
+        proc_macro: bool,
+    },
+    /// A feature of a package, e.g. `ink feature "std"`.
+    Feature { name: &'a str, feature: &'a str },
+}
+
+impl<'a> Node<'a> {
+    fn parse(line: &'a str) -> Option<(usize, Self)> {
+        let depth_len = line.find(|c: char| !c.is_ascii_digit())?;
+        let depth = line[..depth_len].parse().ok()?;
+        let mut words = line[depth_len..].split_whitespace();
+        let name = words.next()?;
+        let node = match words.next()? {
+            "feature" => {
+                let feature = words.next()?.trim_matches('"');
+                Node::Feature { name, feature }
+            }
+            version => {
+                Node::Package {
+                    name,
+                    version,
+                    proc_macro: line.contains("(proc-macro)"),
+                }
+            }
+        };
+        Some((depth, node))
+    }
+
+    fn name(&self) -> &'a str {
+        match self {
+            Node::Package { name, .. } | Node::Feature { name, .. } => name,
+        }
+    }
+}
+
+/// Collects the dependencies which enable `std` from the `cargo tree` output.
+///
+/// In the feature graph a feature node is the parent of its package and of the
+/// features of the same package it enables, it is itself a child of the package or
+/// feature which enables it.
+fn parse_std_dependencies(tree: &str) -> Vec<StdDependency> {
+    let nodes: Vec<_> = tree.lines().filter_map(Node::parse).collect();
+    let mut versions = HashMap::new();
+    let mut proc_macros = HashSet::new();
+    for (_, node) in &nodes {
+        if let Node::Package {
+            name,
+            version,
+            proc_macro,
+        } = node
+        {
+            versions.insert(*name, *version);
+            if *proc_macro {
+                proc_macros.insert(*name);
+            }
+        }
+    }
+    let Some((_, root)) = nodes.first() else {
+        return Vec::new()
+    };
+    let root = root.name();
+
+    let mut std_dependencies = BTreeMap::new();
+    let mut ancestors: Vec<&Node> = Vec::new();
+    let mut skip_below = None;
+    for (depth, node) in &nodes {
+        ancestors.truncate(*depth);
+        if skip_below.is_some_and(|skip| *depth > skip) {
+            continue
+        }
+        skip_below = None;
+        // proc-macros are built for the host
+        if proc_macros.contains(node.name()) {
+            skip_below = Some(*depth);
+            continue
+        }
+        ancestors.push(node);
+
+        let Node::Feature {
+            name,
+            feature: "std",
+        } = node
+        else {
+            continue
+        };
+        if *name == root || std_dependencies.contains_key(name) {
+            continue
+        }
+        // features of the same package are enabled by the outermost one
+        let mut enabled = "std";
+        let mut parents = ancestors[..ancestors.len() - 1].iter().rev();
+        let parent = loop {
+            match parents.next() {
+                Some(Node::Feature {
+                    name: parent,
+                    feature,
+                }) if parent == name => enabled = feature,
+                parent => break parent,
+            }
+        };
+        let reason = match parent {
+            Some(Node::Package { name: parent, .. }) if enabled == "default" => {
+                format!("`{parent}` uses its default features")
+            }
+            Some(Node::Package { name: parent, .. }) => {
+                format!("`{parent}` enables its `{enabled}` feature")
+            }
+            Some(Node::Feature { feature: "std", .. }) | None => continue,
+            Some(Node::Feature {
+                name: parent,
+                feature,
+            }) => {
+                format!(
+                    "the `{feature}` feature of `{parent}` enables its `{enabled}` \
+                    feature"
+                )
+            }
+        };
+        let Some(version) = versions.get(name) else {
+            continue
+        };
+        let mut path: Vec<_> = ancestors
+            .iter()
+            .filter_map(|node| {
+                match node {
+                    Node::Package { name, .. } => Some(name.to_string()),
+                    Node::Feature { .. } => None,
+                }
+            })
+            .collect();
+        path.push(name.to_string());
+        std_dependencies.insert(
+            *name,
+            StdDependency {
+                package: format!("{name} {version}"),
+                reason,
+                path,
+            },
+        );
+    }
+    std_dependencies.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests::with_tmp_dir;
+    use std::{
+        fs,
+        path::Path,
+    };
+
+    fn write_crate(dir: &Path, name: &str, dependencies: &str, features: &str) {
+        let crate_dir = dir.join(name);
+        fs::create_dir_all(&crate_dir).unwrap();
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                [lib]\npath = \"lib.rs\"\n\n\
+                [dependencies]\n{dependencies}\n\n[features]\n{features}\n"
+            ),
+        )
+        .unwrap();
+        fs::write(crate_dir.join("lib.rs"), "").unwrap();
+    }
+
+    #[test]
+    fn finds_dependencies_enabling_std() {
+        with_tmp_dir(|dir| {
+            write_crate(dir, "no_std_dep", "", "default = [\"std\"]\nstd = []");
+            write_crate(dir, "optional_dep", "", "default = [\"std\"]\nstd = []");
+            write_crate(
+                dir,
+                "default_dep",
+                "",
+                "default = [\"alloc\"]\nalloc = [\"std\"]\nstd = []",
+            );
+            write_crate(
+                dir,
+                "unforwarded",
+                "no_std_dep = { path = \"../no_std_dep\", default-features = false, \
+                    features = [\"std\"] }",
+                "",
+            );
+            write_crate(
+                dir,
+                "contract",
+                "no_std_dep = { path = \"../no_std_dep\", default-features = false }\n\
+                default_dep = { path = \"../default_dep\" }\n\
+                unforwarded = { path = \"../unforwarded\" }\n\
+                renamed = { package = \"optional_dep\", path = \"../optional_dep\", \
+                    default-features = false, optional = true }",
+                "default = [\"std\"]\nstd = [\"no_std_dep/std\", \"renamed?/std\"]",
+            );
+
+            let manifest_path =
+                ManifestPath::new(dir.join("contract").join("Cargo.toml"))?;
+            let mut features = Features::default();
+            let std_dependencies =
+                find_std_dependencies(&manifest_path, &features, &Target::Wasm)?;
+            assert_eq!(
+                std_dependencies,
+                [
+                    StdDependency {
+                        package: "default_dep v0.1.0".to_string(),
+                        reason: "`contract` uses its default features".to_string(),
+                        path: vec!["contract".to_string(), "default_dep".to_string()],
+                    },
+                    StdDependency {
+                        package: "no_std_dep v0.1.0".to_string(),
+                        reason: "`unforwarded` enables its `std` feature".to_string(),
+                        path: vec![
+                            "contract".to_string(),
+                            "unforwarded".to_string(),
+                            "no_std_dep".to_string()
+                        ],
+                    },
+                ]
+            );
+
+            // the `std` feature of the contract forwards `std`, which is not reported
+            features.push("std");
+            features.push("renamed");
+            let std_dependencies =
+                find_std_dependencies(&manifest_path, &features, &Target::Wasm)?;
+            assert_eq!(std_dependencies.len(), 2);
+            Ok(())
+        })
+    }
+}
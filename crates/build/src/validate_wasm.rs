@@ -16,12 +16,28 @@
 
 use anyhow::Result;
 use colored::Colorize;
+use contract_analyze::{
+    FunctionId,
+    Module,
+};
 use impl_serde::serialize as serde_hex;
 use wasmparser::{
+    Operator,
     Parser,
     Payload,
+    ValType,
 };
 
+/// The maximum number of globals `pallet-contracts` accepts by default.
+const MAX_GLOBALS: usize = 256;
+
+/// The maximum number of table elements `pallet-contracts` accepts by default.
+const MAX_TABLE_SIZE: u64 = 4096;
+
+/// Data segments larger than a Wasm page are reported, as they are most likely
+/// unintentionally embedded into the code.
+const MAX_DATA_SEGMENT_SIZE: usize = 64 * 1024;
+
 /// Marker inserted by the ink! codegen for an error which can't
 /// be checked at compile time.
 const INK_ENFORCE_ERR: &str = "__ink_enforce_error_";
@@ -147,6 +163,151 @@ fn check_import(module: &str, field: &str) -> Result<(), String> {
     }
 }
 
+/// Validates that the Wasm only uses the deterministic subset of Wasm MVP accepted by
+/// `pallet-contracts`, and stays within its default limits.
+///
+/// Fails for floating-point instructions and types, instructions of post-MVP proposals,
+/// multi-value functions, multiple tables and a start function. Returns warnings for
+/// instructions which only newer runtimes accept, and for exceeding the limits on
+/// globals, tables and data segments. If the Wasm has a name section, the findings
+/// refer to the function names.
+pub fn validate_determinism(module: &[u8]) -> Result<Vec<String>> {
+    let module = Module::new(module)?;
+    let names = module.function_names()?;
+    let function_id = |index: u32| {
+        FunctionId {
+            index,
+            name: names.get(&index).map(ToString::to_string),
+        }
+    };
+    let mut errs = Vec::new();
+    let mut warnings = Vec::new();
+
+    let is_float = |ty: &ValType| matches!(ty, ValType::F32 | ValType::F64);
+    let imported_functions = module.imported_functions_count() as u32;
+    for (i, operators) in module.code_sections.iter().enumerate() {
+        let function = function_id(imported_functions + i as u32);
+        let ty = module
+            .function_sections
+            .get(i)
+            .and_then(|ty| module.type_sections.get(*ty as usize));
+        if let Some(ty) = ty {
+            if ty.params().iter().chain(ty.results()).any(is_float) {
+                errs.push(format!(
+                    "The function {function} has floating-point parameters or results, \
+                    which are not deterministic."
+                ));
+            }
+            if ty.results().len() > 1 {
+                errs.push(format!(
+                    "The function {function} returns multiple values, which requires the \
+                    `multi-value` proposal."
+                ));
+            }
+        }
+
+        let float = operators
+            .iter()
+            .map(operator_info)
+            .find(|(_, name)| name.contains("F32") || name.contains("F64"));
+        if let Some((_, name)) = float {
+            errs.push(format!(
+                "The function {function} uses the floating-point instruction `{name}`, \
+                floating-point instructions are not deterministic."
+            ));
+        }
+        // the first instruction of each post-MVP proposal used by the function
+        let mut post_mvp = Vec::new();
+        for (proposal, name) in operators.iter().map(operator_info) {
+            if proposal != "mvp" && !post_mvp.iter().any(|(p, _)| *p == proposal) {
+                post_mvp.push((proposal, name));
+            }
+        }
+        for (proposal, name) in post_mvp {
+            if proposal == "sign_extension" {
+                warnings.push(format!(
+                    "The function {function} uses the instruction `{name}` of the \
+                    `sign_extension` proposal, which is only accepted by newer versions \
+                    of `pallet-contracts`."
+                ))
+            } else {
+                errs.push(format!(
+                    "The function {function} uses the instruction `{name}` of the \
+                    `{proposal}` proposal, which is not supported by `pallet-contracts`."
+                ))
+            }
+        }
+    }
+
+    if let Some(start) = module.start_section {
+        errs.push(format!(
+            "The Wasm has the start function {}, contracts must not have a start \
+            function.",
+            function_id(start)
+        ));
+    }
+    if module.table_sections.len() > 1 {
+        errs.push(format!(
+            "The Wasm defines {} tables, multiple tables require the `reference-types` \
+            proposal.",
+            module.table_sections.len()
+        ));
+    }
+
+    if module.global_sections.len() > MAX_GLOBALS {
+        warnings.push(format!(
+            "The Wasm defines {} globals, `pallet-contracts` accepts {} by default.",
+            module.global_sections.len(),
+            MAX_GLOBALS
+        ));
+    }
+    for table in &module.table_sections {
+        if table.ty.initial > MAX_TABLE_SIZE {
+            warnings.push(format!(
+                "The Wasm defines a table with {} elements, `pallet-contracts` accepts {} \
+                by default.",
+                table.ty.initial, MAX_TABLE_SIZE
+            ));
+        }
+    }
+    for (i, data) in module.data_sections.iter().enumerate() {
+        if data.data.len() > MAX_DATA_SEGMENT_SIZE {
+            warnings.push(format!(
+                "The data segment {} has a size of {} bytes, check whether large data is \
+                embedded into the contract unintentionally.",
+                i,
+                data.data.len()
+            ));
+        }
+    }
+
+    if !errs.is_empty() {
+        anyhow::bail!(format!(
+            "Validation of the Wasm failed.\n\n\n{}\n\nIgnore with `--skip-wasm-validation`",
+            errs.into_iter()
+                .map(|err| format!("{} {}", "ERROR:".to_string().bold(), err))
+                .collect::<Vec<String>>()
+                .join("\n\n\n")
+        ));
+    }
+    Ok(warnings)
+}
+
+/// Returns the proposal which introduced the instruction, and the name of the
+/// instruction.
+fn operator_info(operator: &Operator) -> (&'static str, &'static str) {
+    macro_rules! define_operator_info {
+        ($( @$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident)*) => {
+            match operator {
+                $(
+                    Operator::$op { .. } => (stringify!($proposal), stringify!($op)),
+                )*
+            }
+        }
+    }
+    wasmparser::for_each_operator!(define_operator_info)
+}
+
 /// Extracts the ink! linker error marker from the `field`, parses it, and
 /// returns a human readable error message for it.
 fn parse_linker_error(field: &str) -> String {
@@ -201,7 +362,10 @@ fn parse_linker_error(field: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::validate_import_section;
+    use super::{
+        validate_determinism,
+        validate_import_section,
+    };
 
     fn create_module(contract: &str) -> Vec<u8> {
         wabt::wat2wasm(contract).expect("Invalid wabt")
@@ -322,4 +486,130 @@ mod tests {
         // then
         assert!(res.is_ok());
     }
+
+    #[test]
+    fn must_catch_non_deterministic_instructions() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func))
+                (type (;1;) (func (param f32) (result f32)))
+                (import "seal0" "foo" (func (;0;) (type 0)))
+                (func $float (type 0)
+                    f32.const 1
+                    f32.const 2
+                    f32.add
+                    drop)
+                (func $float_signature (type 1) local.get 0)
+                (func $simd (type 0)
+                    i32.const 0
+                    i8x16.splat
+                    drop)
+                (func $sign_extension (type 0)
+                    i32.const 0
+                    i32.extend8_s
+                    drop)
+                (start $sign_extension)
+            )"#;
+        let module = create_module(contract);
+
+        // when
+        let res = validate_determinism(&module);
+
+        // then
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains(
+            "The function #1 (float) uses the floating-point instruction `F32Const`"
+        ));
+        assert!(err.contains(
+            "The function #2 (float_signature) has floating-point parameters or results"
+        ));
+        assert!(err.contains(
+            "The function #3 (simd) uses the instruction `I8x16Splat` of the `simd` proposal"
+        ));
+        assert!(err.contains(
+            "The Wasm has the start function #4 (sign_extension), contracts must not have a \
+            start function."
+        ));
+        assert!(!err.contains("I32Extend8S"));
+    }
+
+    #[test]
+    fn must_catch_post_mvp_instructions_after_sign_extension() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func))
+                (func $mixed (type 0)
+                    i32.const 0
+                    i32.extend8_s
+                    drop
+                    i32.const 0
+                    i8x16.splat
+                    drop)
+            )"#;
+        let module = create_module(contract);
+
+        // when
+        let res = validate_determinism(&module);
+
+        // then
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains(
+            "The function #0 (mixed) uses the instruction `I8x16Splat` of the `simd` \
+            proposal"
+        ));
+    }
+
+    #[test]
+    fn must_warn_about_exceeded_limits() {
+        // given
+        let contract = format!(
+            r#"
+            (module
+                (type (;0;) (func))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (table (;0;) 5000 funcref)
+                (func (;0;) (type 0)
+                    i32.const 0
+                    i32.extend8_s
+                    drop)
+                (data (;0;) (i32.const 65536) "{}")
+            )"#,
+            "a".repeat(64 * 1024 + 1)
+        );
+        let module = create_module(&contract);
+
+        // when
+        let warnings = validate_determinism(&module).expect("validation failed");
+
+        // then
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].contains("`I32Extend8S` of the `sign_extension` proposal"));
+        assert!(warnings[1].contains("a table with 5000 elements"));
+        assert!(warnings[2].contains("The data segment 0 has a size of 65537 bytes"));
+    }
+
+    #[test]
+    fn must_validate_determinism_successfully() {
+        // given
+        let contract = r#"
+            (module
+                (type (;0;) (func (param i32) (result i32)))
+                (import "seal0" "foo" (func (;0;) (type 0)))
+                (import "env" "memory" (memory (;0;) 2 16))
+                (func (;1;) (type 0)
+                    local.get 0
+                    call 0)
+                (global (;0;) (mut i32) (i32.const 65536))
+                (data (;0;) (i32.const 65536) "test")
+            )"#;
+        let module = create_module(contract);
+
+        // when
+        let warnings = validate_determinism(&module).expect("validation failed");
+
+        // then
+        assert!(warnings.is_empty());
+    }
 }
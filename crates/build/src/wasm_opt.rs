@@ -23,7 +23,11 @@ use wasm_opt::{
 
 use std::{
     fmt,
-    path::PathBuf,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
     str,
 };
 
@@ -166,3 +170,16 @@ pub struct OptimizationResult {
     /// The Wasm size after optimizations have been applied.
     pub optimized_size: f64,
 }
+
+impl OptimizationResult {
+    /// Compares the sizes of the `original` and the `optimized` code, in kilobytes.
+    pub fn from_files(original: &Path, optimized: &Path) -> Result<Self> {
+        let size = |path: &Path| -> Result<f64> {
+            Ok(fs::metadata(path)?.len() as f64 / 1000.0)
+        };
+        Ok(Self {
+            original_size: size(original)?,
+            optimized_size: size(optimized)?,
+        })
+    }
+}
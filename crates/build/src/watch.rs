@@ -0,0 +1,221 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rebuilding a contract whenever its sources change.
+//!
+//! The watched directories are polled for changes, which works the same on all
+//! platforms and file systems, e.g. for projects mounted into a container.
+
+use crate::{
+    execute,
+    BuildResult,
+    CrateMetadata,
+    ExecuteArgs,
+};
+use anyhow::Result;
+use cargo_metadata::DependencyKind;
+use std::{
+    collections::{
+        BTreeMap,
+        HashSet,
+        VecDeque,
+    },
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    thread,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+};
+
+/// The interval in which the watched directories are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The time without further changes to wait for before rebuilding, so that e.g. saving
+/// several files at once only triggers a single build.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// The modification times and sizes of the files in the watched directories.
+type Snapshot = BTreeMap<PathBuf, (SystemTime, u64)>;
+
+/// Executes the build with `args` and again whenever the sources of the contract, its
+/// path dependencies or their `Cargo.toml` change.
+///
+/// The result of each build and its duration are passed to `on_result`. Only returns
+/// if the directories to watch can not be read.
+pub fn watch<F>(args: ExecuteArgs, mut on_result: F) -> Result<()>
+where
+    F: FnMut(Result<BuildResult>, Duration),
+{
+    loop {
+        // the dependencies might have changed, so the watched directories are
+        // collected for every build
+        let crate_metadata = CrateMetadata::collect(&args.manifest_path, args.target);
+        let (dirs, excluded) = match &crate_metadata {
+            Ok(crate_metadata) => watched_dirs(crate_metadata),
+            Err(_) => {
+                let dir = args.manifest_path.absolute_directory()?;
+                (vec![dir.clone()], vec![dir.join("target")])
+            }
+        };
+        let snapshot = snapshot(&dirs, &excluded)?;
+
+        let started = Instant::now();
+        let result = match crate_metadata {
+            Ok(_) => execute(args.clone()),
+            Err(err) => Err(err),
+        };
+        on_result(result, started.elapsed());
+
+        wait_for_changes(&dirs, &excluded, snapshot)?;
+    }
+}
+
+/// Returns the directories of the contract and its path dependencies, and the
+/// directories excluded from watching.
+fn watched_dirs(crate_metadata: &CrateMetadata) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let metadata = &crate_metadata.cargo_meta;
+    let root = &crate_metadata.root_package.id;
+    let mut dirs = Vec::new();
+    if let Some(resolve) = &metadata.resolve {
+        let mut visited = HashSet::from([root]);
+        let mut queue = VecDeque::from([root]);
+        while let Some(id) = queue.pop_front() {
+            let Some(package) = metadata.packages.iter().find(|p| p.id == *id) else {
+                continue
+            };
+            // packages without a source are path dependencies
+            if id != root && package.source.is_some() {
+                continue
+            }
+            if let Some(dir) = package.manifest_path.parent() {
+                dirs.push(dir.as_std_path().to_path_buf());
+            }
+            let Some(node) = resolve.nodes.iter().find(|node| node.id == *id) else {
+                continue
+            };
+            for dep in &node.deps {
+                let is_dev = dep
+                    .dep_kinds
+                    .iter()
+                    .all(|kind| kind.kind == DependencyKind::Development);
+                if !is_dev && visited.insert(&dep.pkg) {
+                    queue.push_back(&dep.pkg);
+                }
+            }
+        }
+    }
+    // nested directories are already watched through their parent
+    dirs.sort();
+    let mut watched: Vec<PathBuf> = Vec::new();
+    for dir in dirs {
+        if !watched.iter().any(|parent| dir.starts_with(parent)) {
+            watched.push(dir);
+        }
+    }
+    let excluded = vec![metadata.target_directory.as_std_path().to_path_buf()];
+    (watched, excluded)
+}
+
+/// Blocks until a file in `dirs` differs from the `snapshot` and no further changes
+/// happened for the debounce time.
+fn wait_for_changes(
+    dirs: &[PathBuf],
+    excluded: &[PathBuf],
+    mut snapshot: Snapshot,
+) -> Result<()> {
+    let mut changed = false;
+    loop {
+        thread::sleep(if changed { DEBOUNCE } else { POLL_INTERVAL });
+        let current = self::snapshot(dirs, excluded)?;
+        if current != snapshot {
+            snapshot = current;
+            changed = true;
+        } else if changed {
+            return Ok(())
+        }
+    }
+}
+
+/// Records the modification times and sizes of the files in `dirs`.
+///
+/// Hidden directories, e.g. `.git`, and the `excluded` directories are skipped.
+fn snapshot(dirs: &[PathBuf], excluded: &[PathBuf]) -> Result<Snapshot> {
+    fn visit(dir: &Path, excluded: &[PathBuf], snapshot: &mut Snapshot) -> Result<()> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            // the directory was removed in the meantime
+            return Ok(())
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let Ok(metadata) = entry.metadata() else {
+                continue
+            };
+            if metadata.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if !hidden && !excluded.contains(&path) {
+                    visit(&path, excluded, snapshot)?;
+                }
+            } else {
+                let modified = metadata.modified()?;
+                snapshot.insert(path, (modified, metadata.len()));
+            }
+        }
+        Ok(())
+    }
+
+    let mut snapshot = Snapshot::new();
+    for dir in dirs {
+        visit(dir, excluded, &mut snapshot)?;
+    }
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::tests::with_tmp_dir;
+
+    #[test]
+    fn snapshot_detects_changes_outside_of_excluded_dirs() {
+        with_tmp_dir(|dir| {
+            let dir = dir.to_path_buf();
+            let target = dir.join("target");
+            fs::create_dir_all(&target)?;
+            fs::create_dir_all(dir.join(".git"))?;
+            fs::write(dir.join("lib.rs"), "")?;
+            let dirs = [dir.clone()];
+            let excluded = [target.clone()];
+
+            let before = snapshot(&dirs, &excluded)?;
+            assert_eq!(before.keys().collect::<Vec<_>>(), [&dir.join("lib.rs")]);
+
+            fs::write(target.join("flipper.wasm"), "wasm")?;
+            fs::write(dir.join(".git").join("index"), "index")?;
+            assert_eq!(snapshot(&dirs, &excluded)?, before);
+
+            fs::write(dir.join("lib.rs"), "// changed")?;
+            assert_ne!(snapshot(&dirs, &excluded)?, before);
+            Ok(())
+        })
+    }
+}
@@ -137,16 +137,18 @@ impl Manifest {
     pub fn new(manifest_path: ManifestPath) -> Result<Manifest> {
         let toml = fs::read_to_string(&manifest_path).context("Loading Cargo.toml")?;
         let toml: value::Table = toml::from_str(&toml)?;
-        let mut manifest = Manifest {
+        let manifest = Manifest {
             path: manifest_path,
             toml,
             metadata_package: false,
         };
-        let profile = manifest.profile_release_table_mut()?;
-        if profile
-            .get("overflow-checks")
-            .and_then(|val| val.as_bool())
-            .unwrap_or(false)
+        // virtual workspace manifests are not built as contracts
+        if manifest.toml.contains_key("package")
+            && manifest
+                .profile_release_table()
+                .and_then(|profile| profile.get("overflow-checks"))
+                .and_then(|val| val.as_bool())
+                .unwrap_or(false)
         {
             anyhow::bail!("Overflow checks must be disabled. Cargo contract makes sure that no unchecked arithmetic is used.")
         }
@@ -225,6 +227,19 @@ impl Manifest {
             .map(Into::into)
     }
 
+    /// Extract `chain` from `[package.metadata.contract]`
+    pub fn profile_chain(&self) -> Option<&str> {
+        self.toml
+            .get("package")?
+            .as_table()?
+            .get("metadata")?
+            .as_table()?
+            .get("contract")?
+            .as_table()?
+            .get("chain")?
+            .as_str()
+    }
+
     /// Set preferred defaults for the `[profile.release]` section
     ///
     /// # Note
@@ -252,6 +267,11 @@ impl Manifest {
         self
     }
 
+    /// Get a reference to the `[profile.release]` section, if any.
+    fn profile_release_table(&self) -> Option<&value::Table> {
+        self.toml.get("profile")?.get("release")?.as_table()
+    }
+
     /// Get mutable reference to `[profile.release]` section
     fn profile_release_table_mut(&mut self) -> Result<&mut value::Table> {
         let profile = self
@@ -352,18 +372,9 @@ impl Manifest {
         &mut self,
         crate_metadata: &CrateMetadata,
     ) -> Result<&mut Self> {
-        let workspace_manifest_path =
-            crate_metadata.cargo_meta.workspace_root.join("Cargo.toml");
-
-        // If the workspace manifest is the same as the crate manifest, there's not
-        // workspace to fix
-        if workspace_manifest_path == self.path.path {
+        let Some(workspace_toml) = self.workspace_toml(crate_metadata)? else {
             return Ok(self)
-        }
-
-        let workspace_toml =
-            fs::read_to_string(&workspace_manifest_path).context("Loading Cargo.toml")?;
-        let workspace_toml: value::Table = toml::from_str(&workspace_toml)?;
+        };
 
         let workspace_dependencies = workspace_toml
             .get("workspace")
@@ -398,6 +409,357 @@ impl Manifest {
         Ok(self)
     }
 
+    /// Merge the `[profile.release]` section of the workspace into the one of the
+    /// crate.
+    ///
+    /// Cargo ignores the profiles of workspace members, so contracts which are part of
+    /// a workspace are configured in the workspace manifest. Settings defined by the
+    /// crate itself take precedence.
+    pub fn with_merged_workspace_profile(
+        &mut self,
+        crate_metadata: &CrateMetadata,
+    ) -> Result<&mut Self> {
+        let Some(workspace_toml) = self.workspace_toml(crate_metadata)? else {
+            return Ok(self)
+        };
+        let Some(workspace_profile) = workspace_toml
+            .get("profile")
+            .and_then(|profile| profile.get("release"))
+            .and_then(|release| release.as_table())
+        else {
+            return Ok(self)
+        };
+
+        let profile_release = self.profile_release_table_mut()?;
+        for (key, value) in workspace_profile {
+            // overflow checks must be disabled for contracts, see `Manifest::new`
+            if key == "overflow-checks" {
+                continue
+            }
+            profile_release
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+        Ok(self)
+    }
+
+    /// Loads the workspace manifest of the crate, if it is different from the crate
+    /// manifest.
+    fn workspace_toml(
+        &self,
+        crate_metadata: &CrateMetadata,
+    ) -> Result<Option<value::Table>> {
+        let workspace_manifest_path =
+            crate_metadata.cargo_meta.workspace_root.join("Cargo.toml");
+
+        // If the workspace manifest is the same as the crate manifest, there's not
+        // workspace to fix
+        if workspace_manifest_path == self.path.path {
+            return Ok(None)
+        }
+
+        let workspace_toml =
+            fs::read_to_string(&workspace_manifest_path).context("Loading Cargo.toml")?;
+        Ok(Some(toml::from_str(&workspace_toml)?))
+    }
+
+    /// Returns `true` if the manifest defines a `[workspace]`.
+    pub fn is_workspace_root(&self) -> bool {
+        self.toml.contains_key("workspace")
+    }
+
+    /// Get the `[workspace.dependencies]` section, if any.
+    pub fn workspace_dependencies(&self) -> Option<&value::Table> {
+        self.toml.get("workspace")?.get("dependencies")?.as_table()
+    }
+
+    /// Returns `true` if `member`, a path relative to the workspace root, is excluded
+    /// by `[workspace] exclude`.
+    pub fn is_excluded_workspace_member(&self, member: &str) -> bool {
+        let Some(exclude) = self
+            .toml
+            .get("workspace")
+            .and_then(|workspace| workspace.get("exclude"))
+            .and_then(|exclude| exclude.as_array())
+        else {
+            return false
+        };
+        exclude
+            .iter()
+            .filter_map(|v| v.as_str())
+            .any(|excluded| Path::new(member).starts_with(excluded.trim_end_matches('/')))
+    }
+
+    /// Returns `true` if `member`, a path relative to the workspace root, is matched by
+    /// an entry of `[workspace] members`, e.g. by a glob pattern.
+    pub fn is_workspace_member(&self, member: &str) -> bool {
+        self.toml
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(|members| members.as_array())
+            .is_some_and(|members| {
+                members
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .any(|pattern| workspace_member_matches(pattern, member))
+            })
+    }
+
+    /// Add `member`, a path relative to the workspace root, to the `[workspace]
+    /// members = []` section.
+    ///
+    /// If the member is already matched by an entry, does nothing.
+    pub fn with_workspace_member(&mut self, member: &str) -> Result<&mut Self> {
+        if self.is_workspace_member(member) {
+            return Ok(self)
+        }
+        self.toml
+            .entry("workspace")
+            .or_insert(value::Value::Table(Default::default()))
+            .as_table_mut()
+            .context("workspace section should be a table")?
+            .entry("members")
+            .or_insert(value::Value::Array(Default::default()))
+            .as_array_mut()
+            .context("workspace.members should be an array")?
+            .push(member.into());
+        Ok(self)
+    }
+
+    /// Replace the `[dependencies]` and `[dev-dependencies]` which are declared in
+    /// `workspace_dependencies` by `{ workspace = true }`.
+    ///
+    /// Crate specific `features` and `optional` settings are preserved, path
+    /// dependencies are left as they are.
+    ///
+    /// Fails if the version of the workspace does not satisfy the requirement of the
+    /// crate, or if the crate disables the default features which the workspace
+    /// enables, since both would change what the crate is built with.
+    pub fn with_workspace_dependencies(
+        &mut self,
+        workspace_dependencies: &value::Table,
+    ) -> Result<&mut Self> {
+        for section_name in ["dependencies", "dev-dependencies"] {
+            let Some(dependencies) = self.toml.get_mut(section_name) else {
+                continue
+            };
+            let dependencies = dependencies
+                .as_table_mut()
+                .ok_or_else(|| anyhow::anyhow!("{section_name} should be a table"))?;
+
+            for (name, dependency) in dependencies.iter_mut() {
+                let Some(workspace_dependency) = workspace_dependencies.get(name) else {
+                    continue
+                };
+                if let (Some(required), Some(provided)) = (
+                    registry_dependency_version(dependency),
+                    registry_dependency_version(workspace_dependency),
+                ) {
+                    if !version_satisfies(required, provided)? {
+                        anyhow::bail!(
+                            "`{name}` is declared with version `{provided}` in the \
+                            workspace, which does not satisfy the requirement \
+                            `{required}` of `{}`",
+                            self.path.as_ref().display()
+                        )
+                    }
+                }
+                let mut inherited = value::Table::new();
+                inherited.insert("workspace".into(), true.into());
+                if let Some(dependency) = dependency.as_table() {
+                    if dependency.contains_key("path") {
+                        continue
+                    }
+                    for key in ["features", "optional"] {
+                        if let Some(value) = dependency.get(key) {
+                            inherited.insert(key.into(), value.clone());
+                        }
+                    }
+
+                    // `default-features` can only be disabled in the workspace
+                    let default_features = |dependency: Option<&value::Value>| {
+                        dependency.and_then(|v| v.as_bool()).unwrap_or(true)
+                    };
+                    if !default_features(dependency.get("default-features"))
+                        && default_features(workspace_dependency.get("default-features"))
+                    {
+                        anyhow::bail!(
+                            "`{name}` is declared with default features in the \
+                            workspace, add `default-features = false` to \
+                            `[workspace.dependencies]` to build the contract \
+                            without `std`."
+                        )
+                    }
+                }
+                *dependency = value::Value::Table(inherited);
+            }
+        }
+        Ok(self)
+    }
+
+    /// Remove the `[profile]` section, which cargo ignores for workspace members.
+    ///
+    /// Returns `true` if the section existed.
+    pub fn remove_profiles(&mut self) -> bool {
+        self.toml.remove("profile").is_some()
+    }
+
+    /// Get the names of the `[dependencies]` and `[dev-dependencies]`.
+    pub fn dependency_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for section_name in ["dependencies", "dev-dependencies"] {
+            if let Some(dependencies) =
+                self.toml.get(section_name).and_then(|d| d.as_table())
+            {
+                for name in dependencies.keys() {
+                    if !names.contains(&name.as_str()) {
+                        names.push(name.as_str())
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    /// Get the version requirement of the dependency `name` from the `[dependencies]`
+    /// or `[dev-dependencies]` section.
+    ///
+    /// Returns `None` if the dependency is not a registry dependency, e.g. a path, git
+    /// or workspace dependency.
+    pub fn dependency_version(&self, name: &str) -> Option<&str> {
+        ["dependencies", "dev-dependencies"]
+            .iter()
+            .filter_map(|section_name| self.toml.get(*section_name)?.get(name))
+            .find_map(registry_dependency_version)
+    }
+
+    /// Returns `true` if the dependency `name` is inherited from the workspace with
+    /// `workspace = true`.
+    pub fn is_workspace_dependency(&self, name: &str) -> bool {
+        ["dependencies", "dev-dependencies"]
+            .iter()
+            .any(|section_name| {
+                self.toml
+                    .get(*section_name)
+                    .and_then(|dependencies| dependencies.get(name))
+                    .and_then(|dependency| dependency.get("workspace"))
+                    .and_then(|workspace| workspace.as_bool())
+                    .unwrap_or(false)
+            })
+    }
+
+    /// Set the version requirement of the registry dependency `name` in the
+    /// `[dependencies]` and `[dev-dependencies]` sections.
+    ///
+    /// Other kinds of dependencies are left as they are, see
+    /// [`Manifest::dependency_version`].
+    pub fn with_dependency_version(
+        &mut self,
+        name: &str,
+        version: &str,
+    ) -> Result<&mut Self> {
+        for section_name in ["dependencies", "dev-dependencies"] {
+            let Some(dependency) = self
+                .toml
+                .get_mut(section_name)
+                .and_then(|dependencies| dependencies.get_mut(name))
+            else {
+                continue
+            };
+            if registry_dependency_version(dependency).is_none() {
+                continue
+            }
+            match dependency {
+                value::Value::Table(dependency) => {
+                    dependency.insert("version".into(), version.into());
+                }
+                dependency => *dependency = version.into(),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Get the `[dependencies]` section, if any.
+    pub fn dependencies(&self) -> Option<&value::Table> {
+        self.toml.get("dependencies")?.as_table()
+    }
+
+    /// Get the `[lib] crate-type = []` values.
+    pub fn crate_types(&self) -> Vec<&str> {
+        self.toml
+            .get("lib")
+            .and_then(|lib| lib.get("crate-type"))
+            .and_then(|crate_types| crate_types.as_array())
+            .map(|crate_types| crate_types.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the setting `key` of the `[profile.release]` section, if any.
+    pub fn profile_release_setting(&self, key: &str) -> Option<&value::Value> {
+        self.profile_release_table()?.get(key)
+    }
+
+    /// Get the features enabled by the feature `name` of the `[features]` section.
+    ///
+    /// Returns `None` if the feature does not exist.
+    pub fn feature(&self, name: &str) -> Option<Vec<&str>> {
+        let enabled = self.toml.get("features")?.get(name)?.as_array()?;
+        Some(enabled.iter().filter_map(|f| f.as_str()).collect())
+    }
+
+    /// Returns `true` if the `[features]` section contains the feature `name`.
+    pub fn has_feature(&self, name: &str) -> bool {
+        self.toml
+            .get("features")
+            .and_then(|features| features.get(name))
+            .is_some()
+    }
+
+    /// Add the feature `name`, which enables the `enabled` features, to the
+    /// `[features]` section.
+    ///
+    /// If the feature already exists, does nothing.
+    pub fn with_feature(&mut self, name: &str, enabled: &[&str]) -> Result<&mut Self> {
+        self.toml
+            .entry("features")
+            .or_insert(value::Value::Table(Default::default()))
+            .as_table_mut()
+            .context("features section should be a table")?
+            .entry(name)
+            .or_insert_with(|| {
+                value::Value::Array(enabled.iter().map(|&f| f.into()).collect())
+            });
+        Ok(self)
+    }
+
+    /// Rename the feature `from` of the `[features]` section to `to`, including the
+    /// references to it by other features.
+    ///
+    /// Returns `false` if the feature `from` does not exist.
+    pub fn rename_feature(&mut self, from: &str, to: &str) -> Result<bool> {
+        let Some(features) = self.toml.get_mut("features") else {
+            return Ok(false)
+        };
+        let features = features
+            .as_table_mut()
+            .context("features section should be a table")?;
+        let Some(enabled) = features.remove(from) else {
+            return Ok(false)
+        };
+        features.insert(to.into(), enabled);
+        for (_, enabled) in features.iter_mut() {
+            let Some(enabled) = enabled.as_array_mut() else {
+                continue
+            };
+            for feature in enabled {
+                if feature.as_str() == Some(from) {
+                    *feature = to.into();
+                }
+            }
+        }
+        Ok(true)
+    }
+
     /// Replace relative paths with absolute paths with the working directory.
     ///
     /// Enables the use of a temporary amended copy of the manifest.
@@ -470,6 +832,147 @@ impl Manifest {
         fs::write(manifest_path, updated_toml)?;
         Ok(())
     }
+
+    /// Writes the changes back to the manifest file it was loaded from.
+    ///
+    /// In contrast to [`Manifest::write`], the entries which did not change keep
+    /// their formatting and comments, so this is used for manifests of the user.
+    pub fn save(&self) -> Result<()> {
+        let original = fs::read_to_string(&self.path).context("Loading Cargo.toml")?;
+        let mut document: toml_edit::DocumentMut = original.parse()?;
+        sync_table(document.as_table_mut(), &self.toml, TableStyle::TopLevel)?;
+        tracing::debug!("Saving manifest to '{}'", self.path.as_ref().display());
+        fs::write(&self.path, document.to_string())?;
+        Ok(())
+    }
+}
+
+/// Updates the `array` of a `toml_edit` document to hold `values`.
+///
+/// The leading elements which did not change keep their formatting and comments.
+fn sync_array(array: &mut toml_edit::Array, values: &[value::Value]) -> Result<()> {
+    let mut unchanged = 0;
+    for (current, value) in array.iter().zip(values) {
+        if !is_unchanged(current, value)? {
+            break
+        }
+        unchanged += 1;
+    }
+    while array.len() > unchanged {
+        array.remove(unchanged);
+    }
+    // new elements of multi-line arrays go on their own line, after the comment of the
+    // previous one
+    let indent = array
+        .iter()
+        .last()
+        .and_then(|last| last.decor().prefix()?.as_str())
+        .and_then(|prefix| prefix.rfind('\n').map(|line| prefix[line..].to_owned()));
+    for value in &values[unchanged..] {
+        let mut value: toml_edit::Value = value.to_string().parse()?;
+        if let Some(indent) = &indent {
+            let comment = array.trailing().as_str().unwrap_or_default().trim_end();
+            value.decor_mut().set_prefix(format!("{comment}{indent}"));
+            array.set_trailing("\n");
+            array.set_trailing_comma(true);
+        }
+        array.push_formatted(value);
+    }
+    Ok(())
+}
+
+/// Returns `true` if the `current` value of a `toml_edit` document equals `value`.
+fn is_unchanged(current: &toml_edit::Value, value: &value::Value) -> Result<bool> {
+    let mut current = current.clone();
+    current.decor_mut().clear();
+    let current = <value::Value as serde::Deserialize>::deserialize(
+        toml::de::ValueDeserializer::new(&current.to_string()),
+    )?;
+    Ok(current == *value)
+}
+
+/// Where a table of a `toml_edit` document is located, which determines how new
+/// tables are written into it.
+#[derive(Clone, Copy, PartialEq)]
+enum TableStyle {
+    /// The root of the document, new tables become `[sections]`.
+    TopLevel,
+    /// A `[section]`, new tables are written inline.
+    Section,
+    /// An inline table, which can only hold values.
+    Inline,
+}
+
+/// Updates the `table` of a `toml_edit` document to hold the entries of `value`.
+///
+/// Entries which did not change are left untouched, so that they keep their
+/// formatting and comments.
+fn sync_table(
+    table: &mut dyn toml_edit::TableLike,
+    value: &value::Table,
+    style: TableStyle,
+) -> Result<()> {
+    let removed = table
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !value.contains_key(key))
+        .collect::<Vec<_>>();
+    for key in removed {
+        table.remove(&key);
+    }
+    for (key, value) in value {
+        match table.get_mut(key) {
+            Some(item) => sync_item(item, value)?,
+            None => {
+                let item = match value {
+                    value::Value::Table(entries) if style == TableStyle::TopLevel => {
+                        let mut section = toml_edit::Table::new();
+                        sync_table(&mut section, entries, TableStyle::Section)?;
+                        toml_edit::Item::Table(section)
+                    }
+                    value => toml_edit::Item::Value(value.to_string().parse()?),
+                };
+                table.insert(key, item);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Updates the `item` of a `toml_edit` document to `value`, if it changed.
+///
+/// Arrays of tables are never edited and thus left as they are.
+fn sync_item(item: &mut toml_edit::Item, value: &value::Value) -> Result<()> {
+    if let (toml_edit::Item::Table(table), value::Value::Table(entries)) =
+        (&mut *item, value)
+    {
+        return sync_table(table, entries, TableStyle::Section)
+    }
+    if let (
+        toml_edit::Item::Value(toml_edit::Value::InlineTable(table)),
+        value::Value::Table(entries),
+    ) = (&mut *item, value)
+    {
+        return sync_table(table, entries, TableStyle::Inline)
+    }
+    if let (Some(array), value::Value::Array(values)) = (item.as_array_mut(), value) {
+        return sync_array(array, values)
+    }
+    if item.is_array_of_tables() {
+        return Ok(())
+    }
+    if let Some(current) = item.as_value() {
+        if is_unchanged(current, value)? {
+            return Ok(())
+        }
+    }
+    let decor = item.as_value().map(|current| current.decor().clone());
+    let mut updated: toml_edit::Value = value.to_string().parse()?;
+    if let Some(decor) = decor {
+        *updated.decor_mut() = decor;
+    }
+    *item = toml_edit::Item::Value(updated);
+    Ok(())
 }
 
 /// Replace relative paths with absolute paths with the working directory.
@@ -599,6 +1102,71 @@ impl PathRewrite {
     }
 }
 
+/// Returns the version requirement of a dependency, if it is fetched from a registry.
+fn registry_dependency_version(dependency: &value::Value) -> Option<&str> {
+    match dependency {
+        value::Value::String(version) => Some(version),
+        value::Value::Table(dependency) => {
+            if ["path", "git", "workspace"]
+                .iter()
+                .any(|key| dependency.contains_key(*key))
+            {
+                return None
+            }
+            dependency.get("version")?.as_str()
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` if the lowest version allowed by the requirement `provided`
+/// satisfies the requirement `required`.
+fn version_satisfies(required: &str, provided: &str) -> Result<bool> {
+    let required = semver::VersionReq::parse(required)
+        .context(format!("Invalid version requirement `{required}`"))?;
+    let provided = semver::VersionReq::parse(provided)
+        .context(format!("Invalid version requirement `{provided}`"))?;
+    let Some(lowest) = provided.comparators.first() else {
+        // `*` allows any version
+        return Ok(required.comparators.is_empty())
+    };
+    let lowest = semver::Version {
+        major: lowest.major,
+        minor: lowest.minor.unwrap_or(0),
+        patch: lowest.patch.unwrap_or(0),
+        pre: lowest.pre.clone(),
+        build: Default::default(),
+    };
+    Ok(required.matches(&lowest))
+}
+
+/// Returns `true` if the `[workspace] members` entry `pattern` matches the path
+/// `member`.
+///
+/// Supports the `*` and `?` wildcards within path components.
+fn workspace_member_matches(pattern: &str, member: &str) -> bool {
+    fn matches(pattern: &[u8], s: &[u8]) -> bool {
+        match (pattern.first(), s.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => {
+                matches(&pattern[1..], s) || (!s.is_empty() && matches(pattern, &s[1..]))
+            }
+            (Some(b'?'), Some(_)) => matches(&pattern[1..], &s[1..]),
+            (Some(p), Some(c)) if p == c => matches(&pattern[1..], &s[1..]),
+            _ => false,
+        }
+    }
+
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let pattern = pattern.split('/').collect::<Vec<_>>();
+    let member = member.split('/').collect::<Vec<_>>();
+    pattern.len() == member.len()
+        && pattern
+            .iter()
+            .zip(member)
+            .all(|(pattern, component)| matches(pattern.as_bytes(), component.as_bytes()))
+}
+
 fn crate_type_exists(crate_type: &str, crate_types: &[value::Value]) -> bool {
     crate_types
         .iter()
@@ -678,10 +1246,137 @@ fn merge_workspace_with_crate_dependencies(
 
 #[cfg(test)]
 mod test {
-    use super::ManifestPath;
+    use super::{
+        workspace_member_matches,
+        Manifest,
+        ManifestPath,
+    };
     use crate::util::tests::with_tmp_dir;
     use std::fs;
 
+    #[test]
+    fn workspace_member_patterns() {
+        assert!(workspace_member_matches("contracts/*", "contracts/flipper"));
+        assert!(workspace_member_matches(
+            "./contracts/flip?er/",
+            "contracts/flipper"
+        ));
+        assert!(workspace_member_matches("flipper", "flipper"));
+        assert!(!workspace_member_matches("contracts/*", "flipper"));
+        assert!(!workspace_member_matches("contracts/*", "contracts/a/b"));
+        assert!(!workspace_member_matches("flip", "flipper"));
+    }
+
+    #[test]
+    fn adds_workspace_member_and_inherits_dependencies() {
+        with_tmp_dir(|path| {
+            let workspace_toml = r#"
+                [workspace]
+                members = ["contracts/*"]
+                exclude = ["vendor"]
+
+                [workspace.dependencies]
+                ink = { version = "5.0.0", default-features = false }
+            "#;
+            let crate_toml = r#"
+                [package]
+                name = "flipper"
+
+                [dependencies]
+                ink = { version = "5.0.0", default-features = false, features = ["x"] }
+                other = { path = "other" }
+
+                [profile.release]
+                lto = true
+            "#;
+            fs::write(path.join("Cargo.toml"), workspace_toml)?;
+            fs::create_dir(path.join("flipper"))?;
+            fs::write(path.join("flipper").join("Cargo.toml"), crate_toml)?;
+
+            let mut workspace =
+                Manifest::new(ManifestPath::new(path.join("Cargo.toml"))?)?;
+            assert!(workspace.is_workspace_root());
+            assert!(workspace.is_excluded_workspace_member("vendor/ink"));
+            assert!(!workspace.is_excluded_workspace_member("flipper"));
+            workspace
+                .with_workspace_member("contracts/flipper")?
+                .with_workspace_member("flipper")?;
+
+            let mut contract = Manifest::new(ManifestPath::new(
+                path.join("flipper").join("Cargo.toml"),
+            )?)?;
+            contract.with_workspace_dependencies(
+                workspace
+                    .workspace_dependencies()
+                    .expect("dependencies are declared"),
+            )?;
+            assert!(contract.remove_profiles());
+
+            let members = workspace.toml["workspace"]["members"].as_array().unwrap();
+            assert_eq!(members, &vec!["contracts/*".into(), "flipper".into()]);
+            let ink = contract.toml["dependencies"]["ink"].as_table().unwrap();
+            assert_eq!(ink.get("workspace"), Some(&true.into()));
+            assert_eq!(
+                ink.get("features"),
+                Some(&vec![toml::Value::from("x")].into())
+            );
+            assert_eq!(ink.get("version"), None);
+            assert_eq!(
+                contract.toml["dependencies"]["other"]["path"].as_str(),
+                Some("other")
+            );
+            assert!(!contract.toml.contains_key("profile"));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn save_keeps_formatting_and_comments() {
+        with_tmp_dir(|path| {
+            let manifest_path = ManifestPath::new(path.join("Cargo.toml"))?;
+            fs::write(
+                &manifest_path,
+                "# the workspace\n\
+                [workspace]\n\
+                members = [\n    \"a\", # the first member\n]\n\n\
+                [workspace.dependencies]\n\
+                ink = { version = \"5.0.0\",   default-features = false } # pinned\n",
+            )?;
+
+            let mut workspace = Manifest::new(manifest_path.clone())?;
+            workspace.with_workspace_member("b")?.save()?;
+
+            assert_eq!(
+                fs::read_to_string(&manifest_path)?,
+                "# the workspace\n\
+                [workspace]\n\
+                members = [\n    \"a\", # the first member\n    \"b\",\n]\n\n\
+                [workspace.dependencies]\n\
+                ink = { version = \"5.0.0\",   default-features = false } # pinned\n"
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn rejects_workspace_dependency_with_incompatible_version() {
+        with_tmp_dir(|path| {
+            fs::write(path.join("Cargo.toml"), "[dependencies]\nink = \"5.0.0\"\n")?;
+            let mut manifest =
+                Manifest::new(ManifestPath::new(path.join("Cargo.toml"))?)?;
+
+            let workspace_dependencies = toml::toml! { ink = "4.3" };
+            assert!(manifest
+                .with_workspace_dependencies(&workspace_dependencies)
+                .is_err());
+            let workspace_dependencies = toml::toml! { ink = "5.0.2" };
+            assert!(manifest
+                .with_workspace_dependencies(&workspace_dependencies)
+                .is_ok());
+            Ok(())
+        })
+    }
+
     #[test]
     fn must_return_absolute_path_from_absolute_path() {
         with_tmp_dir(|path| {
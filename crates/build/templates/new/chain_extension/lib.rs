@@ -0,0 +1,119 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+use ink::env::Environment;
+
+/// The chain extension exposed by the runtime.
+///
+/// The `extension` id and the `function` ids have to match the ones handled by the
+/// chain extension implemented in the runtime.
+#[ink::chain_extension(extension = 0)]
+pub trait RandomExtension {
+    type ErrorCode = RandomReadErr;
+
+    /// Reads 32 random bytes from the runtime, using `subject` as additional entropy.
+    #[ink(function = 1101)]
+    fn fetch_random(subject: [u8; 32]) -> [u8; 32];
+}
+
+/// The error returned by the chain extension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum RandomReadErr {
+    FailGetRandomSource,
+}
+
+impl ink::env::chain_extension::FromStatusCode for RandomReadErr {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            1 => Err(Self::FailGetRandomSource),
+            _ => panic!("encountered unknown status code"),
+        }
+    }
+}
+
+/// The environment of the chain, extending the default environment with the
+/// `RandomExtension`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(TypeInfo)]
+pub enum CustomEnvironment {}
+
+impl Environment for CustomEnvironment {
+    const MAX_EVENT_TOPICS: usize =
+        <ink::env::DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <ink::env::DefaultEnvironment as Environment>::AccountId;
+    type Balance = <ink::env::DefaultEnvironment as Environment>::Balance;
+    type Hash = <ink::env::DefaultEnvironment as Environment>::Hash;
+    type BlockNumber = <ink::env::DefaultEnvironment as Environment>::BlockNumber;
+    type Timestamp = <ink::env::DefaultEnvironment as Environment>::Timestamp;
+
+    type ChainExtension = RandomExtension;
+}
+
+#[ink::contract(env = crate::CustomEnvironment)]
+mod {{name}} {
+    use super::RandomReadErr;
+
+    #[ink(storage)]
+    pub struct {{camel_name}} {
+        /// The last random value fetched from the runtime.
+        value: [u8; 32],
+    }
+
+    /// Event emitted when a new random value was fetched.
+    #[ink(event)]
+    pub struct RandomUpdated {
+        #[ink(topic)]
+        new: [u8; 32],
+    }
+
+    impl {{camel_name}} {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self { value: [0; 32] }
+        }
+
+        /// Fetches a new random value from the runtime using the chain extension.
+        #[ink(message)]
+        pub fn update(&mut self, subject: [u8; 32]) -> Result<(), RandomReadErr> {
+            let new_random = self.env().extension().fetch_random(subject)?;
+            self.value = new_random;
+            self.env().emit_event(RandomUpdated { new: new_random });
+            Ok(())
+        }
+
+        /// Returns the last random value.
+        #[ink(message)]
+        pub fn get(&self) -> [u8; 32] {
+            self.value
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn update_works() {
+            struct MockedExtension;
+            impl ink::env::test::ChainExtension for MockedExtension {
+                fn ext_id(&self) -> u16 {
+                    0
+                }
+
+                fn call(&mut self, _func_id: u16, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                    let ret: [u8; 32] = [1; 32];
+                    ink::scale::Encode::encode_to(&ret, output);
+                    0
+                }
+            }
+            ink::env::test::register_chain_extension(MockedExtension);
+
+            let mut contract = {{camel_name}}::new();
+            assert_eq!(contract.get(), [0; 32]);
+            assert_eq!(contract.update([0_u8; 32]), Ok(()));
+            assert_eq!(contract.get(), [1; 32]);
+        }
+    }
+}
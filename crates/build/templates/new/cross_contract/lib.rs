@@ -0,0 +1,37 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// A contract which instantiates and calls the contract in `other_contract`.
+///
+/// Both contracts have to be built and the code of `other_contract` uploaded to the
+/// chain, before `{{name}}` can be instantiated with the code hash of
+/// `other_contract`.
+#[ink::contract]
+mod {{name}} {
+    use other_contract::OtherContractRef;
+
+    #[ink(storage)]
+    pub struct {{camel_name}} {
+        /// A reference to the instantiated `other_contract`.
+        other_contract: OtherContractRef,
+    }
+
+    impl {{camel_name}} {
+        /// Instantiates `other_contract` from the uploaded code with `code_hash`.
+        #[ink(constructor)]
+        pub fn new(other_contract_code_hash: Hash) -> Self {
+            let other_contract = OtherContractRef::new(false)
+                .code_hash(other_contract_code_hash)
+                .endowment(0)
+                .salt_bytes([0xDE, 0xAD, 0xBE, 0xEF])
+                .instantiate();
+            Self { other_contract }
+        }
+
+        /// Flips the value of `other_contract` and returns the new value.
+        #[ink(message)]
+        pub fn flip_and_get(&mut self) -> bool {
+            self.other_contract.flip();
+            self.other_contract.get()
+        }
+    }
+}
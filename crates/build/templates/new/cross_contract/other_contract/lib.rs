@@ -0,0 +1,33 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// The contract which is instantiated and called by `{{name}}`.
+#[ink::contract]
+mod other_contract {
+
+    #[ink(storage)]
+    pub struct OtherContract {
+        value: bool,
+    }
+
+    impl OtherContract {
+        /// Creates a new contract with the given `init_value`.
+        #[ink(constructor)]
+        pub fn new(init_value: bool) -> Self {
+            Self { value: init_value }
+        }
+
+        /// Flips the stored value.
+        #[ink(message)]
+        pub fn flip(&mut self) {
+            self.value = !self.value;
+        }
+
+        /// Returns the stored value.
+        #[ink(message)]
+        pub fn get(&self) -> bool {
+            self.value
+        }
+    }
+}
+
+pub use self::other_contract::OtherContractRef;
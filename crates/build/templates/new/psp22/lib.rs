@@ -0,0 +1,312 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// The errors of the PSP22 standard.
+#[derive(Debug, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP22Error {
+    /// Custom error type for implementation-based errors.
+    Custom(ink::prelude::string::String),
+    /// Returned when an account does not have enough tokens to complete the operation.
+    InsufficientBalance,
+    /// Returned if there is not enough allowance to complete the operation.
+    InsufficientAllowance,
+    /// Returned if recipient's address is zero.
+    ZeroRecipientAddress,
+    /// Returned if sender's address is zero.
+    ZeroSenderAddress,
+    /// Returned if a safe transfer check failed.
+    SafeTransferCheckFailed(ink::prelude::string::String),
+}
+
+/// The PSP22 fungible token standard.
+///
+/// See https://github.com/inkdevhub/standards/blob/master/PSPs/psp-22.md
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Returns the total token supply.
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+
+    /// Returns the account balance for the specified `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: ink::primitives::AccountId) -> u128;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    #[ink(message)]
+    fn allowance(
+        &self,
+        owner: ink::primitives::AccountId,
+        spender: ink::primitives::AccountId,
+    ) -> u128;
+
+    /// Transfers `value` amount of tokens from the caller's account to account `to`.
+    #[ink(message)]
+    fn transfer(
+        &mut self,
+        to: ink::primitives::AccountId,
+        value: u128,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Transfers `value` tokens on the behalf of `from` to the account `to`.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: ink::primitives::AccountId,
+        to: ink::primitives::AccountId,
+        value: u128,
+        data: ink::prelude::vec::Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Allows `spender` to withdraw from the caller's account multiple times, up to
+    /// the total amount of `value`.
+    #[ink(message)]
+    fn approve(
+        &mut self,
+        spender: ink::primitives::AccountId,
+        value: u128,
+    ) -> Result<(), PSP22Error>;
+
+    /// Increases the allowance of `spender` by `delta_value`.
+    #[ink(message)]
+    fn increase_allowance(
+        &mut self,
+        spender: ink::primitives::AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+
+    /// Decreases the allowance of `spender` by `delta_value`.
+    #[ink(message)]
+    fn decrease_allowance(
+        &mut self,
+        spender: ink::primitives::AccountId,
+        delta_value: u128,
+    ) -> Result<(), PSP22Error>;
+}
+
+#[ink::contract]
+mod {{name}} {
+    use super::{
+        PSP22Error,
+        PSP22,
+    };
+    use ink::{
+        prelude::vec::Vec,
+        storage::Mapping,
+    };
+
+    /// A PSP22 fungible token.
+    #[ink(storage)]
+    pub struct {{camel_name}} {
+        /// Total token supply.
+        total_supply: Balance,
+        /// Mapping from owner to number of owned tokens.
+        balances: Mapping<AccountId, Balance>,
+        /// Mapping of the token amount which an account is allowed to withdraw
+        /// from another account.
+        allowances: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    impl {{camel_name}} {
+        /// Creates a new token with the specified initial supply, owned by the caller.
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let caller = Self::env().caller();
+            let mut balances = Mapping::default();
+            balances.insert(caller, &total_supply);
+            Self::env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                value: total_supply,
+            });
+            Self {
+                total_supply,
+                balances,
+                allowances: Mapping::default(),
+            }
+        }
+
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let from_balance = self.balance_of(from);
+            if from_balance < value {
+                return Err(PSP22Error::InsufficientBalance)
+            }
+            self.balances.insert(from, &(from_balance - value));
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, &(to_balance.saturating_add(value)));
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.allowances.insert((owner, spender), &value);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+    }
+
+    impl PSP22 for {{camel_name}} {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let from = self.env().caller();
+            self.transfer_from_to(from, to, value)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < value {
+                return Err(PSP22Error::InsufficientAllowance)
+            }
+            self.transfer_from_to(from, to, value)?;
+            self.set_allowance(from, caller, allowance - value);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self.set_allowance(owner, spender, value);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender);
+            self.set_allowance(owner, spender, allowance.saturating_add(delta_value));
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender);
+            if allowance < delta_value {
+                return Err(PSP22Error::InsufficientAllowance)
+            }
+            self.set_allowance(owner, spender, allowance - delta_value);
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(caller);
+        }
+
+        #[ink::test]
+        fn new_assigns_total_supply_to_caller() {
+            let token = {{camel_name}}::new(100);
+            assert_eq!(token.total_supply(), 100);
+            assert_eq!(token.balance_of(accounts().alice), 100);
+            assert_eq!(token.balance_of(accounts().bob), 0);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let mut token = {{camel_name}}::new(100);
+            assert_eq!(token.transfer(accounts().bob, 10, Vec::new()), Ok(()));
+            assert_eq!(token.balance_of(accounts().alice), 90);
+            assert_eq!(token.balance_of(accounts().bob), 10);
+            assert_eq!(
+                token.transfer(accounts().bob, 91, Vec::new()),
+                Err(PSP22Error::InsufficientBalance)
+            );
+        }
+
+        #[ink::test]
+        fn transfer_from_requires_allowance() {
+            let mut token = {{camel_name}}::new(100);
+            let accounts = accounts();
+            assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.eve, 11, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(
+                token.transfer_from(accounts.alice, accounts.eve, 10, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(token.balance_of(accounts.eve), 10);
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+        }
+    }
+}
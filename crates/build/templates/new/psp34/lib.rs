@@ -0,0 +1,285 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+use ink::prelude::{
+    string::String,
+    vec::Vec,
+};
+
+/// The identifier of a PSP34 token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(Vec<u8>),
+}
+
+/// The errors of the PSP34 standard.
+#[derive(Debug, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum PSP34Error {
+    /// Custom error type for cases if writer of traits added own restrictions.
+    Custom(String),
+    /// Returned if owner approves self.
+    SelfApprove,
+    /// Returned if the caller doesn't have allowance for transferring.
+    NotApproved,
+    /// Returned if the owner already own the token.
+    TokenExists,
+    /// Returned if the token doesn't exist.
+    TokenNotExists,
+    /// Returned if safe transfer check fails.
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP34 non-fungible token standard.
+///
+/// See https://github.com/inkdevhub/standards/blob/master/PSPs/psp-34.md
+#[ink::trait_definition]
+pub trait PSP34 {
+    /// Returns the collection `Id` of the NFT token.
+    #[ink(message)]
+    fn collection_id(&self) -> Id;
+
+    /// Returns the number of tokens owned by `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: ink::primitives::AccountId) -> u32;
+
+    /// Returns the owner of the token, if any.
+    #[ink(message)]
+    fn owner_of(&self, id: Id) -> Option<ink::primitives::AccountId>;
+
+    /// Returns `true` if `operator` is approved to transfer the token `id` of
+    /// `owner`, or all of the tokens of `owner` if `id` is `None`.
+    #[ink(message)]
+    fn allowance(
+        &self,
+        owner: ink::primitives::AccountId,
+        operator: ink::primitives::AccountId,
+        id: Option<Id>,
+    ) -> bool;
+
+    /// Approves or disapproves `operator` to transfer the token `id` of the caller, or
+    /// all of the tokens of the caller if `id` is `None`.
+    #[ink(message)]
+    fn approve(
+        &mut self,
+        operator: ink::primitives::AccountId,
+        id: Option<Id>,
+        approved: bool,
+    ) -> Result<(), PSP34Error>;
+
+    /// Transfers the token `id` from the owner to `to`.
+    #[ink(message)]
+    fn transfer(
+        &mut self,
+        to: ink::primitives::AccountId,
+        id: Id,
+        data: Vec<u8>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Returns the current total supply of the NFT.
+    #[ink(message)]
+    fn total_supply(&self) -> u128;
+}
+
+#[ink::contract]
+mod {{name}} {
+    use super::{
+        Id,
+        PSP34Error,
+        PSP34,
+    };
+    use ink::{
+        prelude::vec::Vec,
+        storage::Mapping,
+    };
+
+    /// A PSP34 non-fungible token.
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct {{camel_name}} {
+        /// Mapping from token to owner.
+        token_owner: Mapping<Id, AccountId>,
+        /// Mapping from owner to the number of owned tokens.
+        owned_tokens_count: Mapping<AccountId, u32>,
+        /// Mapping from owner, operator and token to the approval. A `None` token
+        /// approves the operator for all tokens of the owner.
+        operator_approvals: Mapping<(AccountId, AccountId, Option<Id>), ()>,
+        /// The number of existing tokens.
+        total_supply: u128,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        id: Id,
+    }
+
+    /// Event emitted when a token approval occurs.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    }
+
+    impl {{camel_name}} {
+        /// Creates a new, empty collection.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Default::default()
+        }
+
+        /// Mints the token `id` to the caller.
+        #[ink(message)]
+        pub fn mint(&mut self, id: Id) -> Result<(), PSP34Error> {
+            if self.token_owner.contains(&id) {
+                return Err(PSP34Error::TokenExists)
+            }
+            let caller = self.env().caller();
+            self.token_owner.insert(&id, &caller);
+            self.owned_tokens_count
+                .insert(caller, &(self.balance_of(caller) + 1));
+            self.total_supply += 1;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                id,
+            });
+            Ok(())
+        }
+    }
+
+    impl PSP34 for {{camel_name}} {
+        #[ink(message)]
+        fn collection_id(&self) -> Id {
+            Id::Bytes(<_ as AsRef<[u8]>>::as_ref(&self.env().account_id()).to_vec())
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u32 {
+            self.owned_tokens_count.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        fn owner_of(&self, id: Id) -> Option<AccountId> {
+            self.token_owner.get(id)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
+            self.operator_approvals.contains((owner, operator, None::<Id>))
+                || id.is_some() && self.operator_approvals.contains((owner, operator, id))
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            let owner = self.env().caller();
+            if owner == operator {
+                return Err(PSP34Error::SelfApprove)
+            }
+            if let Some(id) = &id {
+                if self.owner_of(id.clone()) != Some(owner) {
+                    return Err(PSP34Error::NotApproved)
+                }
+            }
+            if approved {
+                self.operator_approvals
+                    .insert((owner, operator, id.clone()), &());
+            } else {
+                self.operator_approvals.remove((owner, operator, id.clone()));
+            }
+            self.env().emit_event(Approval {
+                owner,
+                operator,
+                id,
+                approved,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            id: Id,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP34Error> {
+            let owner = self
+                .owner_of(id.clone())
+                .ok_or(PSP34Error::TokenNotExists)?;
+            let caller = self.env().caller();
+            if caller != owner && !self.allowance(owner, caller, Some(id.clone())) {
+                return Err(PSP34Error::NotApproved)
+            }
+            self.operator_approvals
+                .remove((owner, caller, Some(id.clone())));
+            self.owned_tokens_count
+                .insert(owner, &(self.balance_of(owner) - 1));
+            self.owned_tokens_count.insert(to, &(self.balance_of(to) + 1));
+            self.token_owner.insert(&id, &to);
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: Some(to),
+                id,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.total_supply
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut nft = {{camel_name}}::new();
+            assert_eq!(nft.mint(Id::U8(1)), Ok(()));
+            assert_eq!(nft.mint(Id::U8(1)), Err(PSP34Error::TokenExists));
+            assert_eq!(nft.owner_of(Id::U8(1)), Some(accounts().alice));
+            assert_eq!(nft.balance_of(accounts().alice), 1);
+            assert_eq!(nft.total_supply(), 1);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let mut nft = {{camel_name}}::new();
+            let accounts = accounts();
+            assert_eq!(nft.mint(Id::U8(1)), Ok(()));
+            assert_eq!(nft.transfer(accounts.bob, Id::U8(1), Vec::new()), Ok(()));
+            assert_eq!(nft.owner_of(Id::U8(1)), Some(accounts.bob));
+            assert_eq!(nft.balance_of(accounts.alice), 0);
+            assert_eq!(nft.balance_of(accounts.bob), 1);
+            assert_eq!(
+                nft.transfer(accounts.eve, Id::U8(1), Vec::new()),
+                Err(PSP34Error::NotApproved)
+            );
+        }
+    }
+}
@@ -0,0 +1,98 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// An upgradeable contract, which replaces its own code using `set_code_hash`.
+///
+/// The storage layout is kept across upgrades, so a new version of the contract must
+/// keep the layout of the `{{camel_name}}` struct compatible. New fields can only be
+/// appended.
+#[ink::contract]
+mod {{name}} {
+
+    /// The errors of the contract.
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// The caller is not allowed to upgrade the contract.
+        NotOwner,
+        /// The code hash has not been uploaded to the chain.
+        CodeNotFound,
+    }
+
+    #[ink(storage)]
+    pub struct {{camel_name}} {
+        /// The account allowed to upgrade the contract.
+        owner: AccountId,
+        /// A counter, which is preserved across upgrades.
+        count: u32,
+    }
+
+    /// Event emitted when the code of the contract is replaced.
+    #[ink(event)]
+    pub struct Upgraded {
+        #[ink(topic)]
+        code_hash: Hash,
+    }
+
+    impl {{camel_name}} {
+        /// Creates a new contract, owned by the caller.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                owner: Self::env().caller(),
+                count: 0,
+            }
+        }
+
+        /// Increments the counter.
+        #[ink(message)]
+        pub fn inc(&mut self) {
+            self.count = self.count.saturating_add(1);
+        }
+
+        /// Returns the current value of the counter.
+        #[ink(message)]
+        pub fn get(&self) -> u32 {
+            self.count
+        }
+
+        /// Replaces the code of the contract with the code at `code_hash`.
+        ///
+        /// The new code has to be uploaded to the chain beforehand, e.g. with
+        /// `cargo contract upload`. The new code is used starting with the next call.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner)
+            }
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::CodeNotFound)?;
+            self.env().emit_event(Upgraded { code_hash });
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[ink::test]
+        fn inc_works() {
+            let mut contract = {{camel_name}}::new();
+            contract.inc();
+            assert_eq!(contract.get(), 1);
+        }
+
+        #[ink::test]
+        fn only_owner_can_upgrade() {
+            let mut contract = {{camel_name}}::new();
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.set_code(Hash::from([0x42; 32])),
+                Err(Error::NotOwner)
+            );
+        }
+    }
+}
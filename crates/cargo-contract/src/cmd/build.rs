@@ -14,7 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
-use anyhow::Result;
+use anyhow::{
+    Context,
+    Result,
+};
+use colored::Colorize;
 use contract_build::{
     BuildArtifacts,
     BuildMode,
@@ -26,6 +30,7 @@ use contract_build::{
     Network,
     OptimizationPasses,
     OutputType,
+    SbomFormat,
     Target,
     UnstableFlags,
     UnstableOptions,
@@ -35,6 +40,7 @@ use contract_build::{
 use std::{
     convert::TryFrom,
     path::PathBuf,
+    time::Duration,
 };
 
 /// Executes build of the smart contract which produces a Wasm binary that is ready for
@@ -115,12 +121,13 @@ pub struct BuildCommand {
     /// Export the build output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
-    /// Don't perform wasm validation checks e.g. for permitted imports.
-    #[clap(long)]
+    /// Don't perform validation checks of the contract code e.g. for permitted imports.
+    #[clap(long = "skip-validation", visible_alias = "skip-wasm-validation")]
     skip_wasm_validation: bool,
-    /// Which bytecode to build the contract into.
-    #[clap(long, default_value = "wasm")]
-    target: Target,
+    /// Which bytecode to build the contract into. Several targets are separated by
+    /// commas, e.g. `wasm,riscv`, and their code is bundled together.
+    #[clap(long, default_value = "wasm", value_delimiter = ',')]
+    target: Vec<Target>,
     /// The maximum number of pages available for a wasm contract to allocate.
     #[clap(long, default_value_t = contract_build::DEFAULT_MAX_MEMORY_PAGES)]
     max_memory_pages: u64,
@@ -131,10 +138,47 @@ pub struct BuildCommand {
     /// Specify a custom image for the verifiable build
     #[clap(long, default_value = None)]
     image: Option<String>,
+    /// Build the given git revision from a clean checkout of the repository instead
+    /// of the working directory. Can only be used with verifiable builds.
+    #[clap(long, value_name = "REV", requires = "verifiable")]
+    from_git: Option<String>,
+    /// Abort the build if the `Cargo.toml` of the contract is misconfigured, instead of
+    /// only printing warnings.
+    #[clap(long)]
+    deny_manifest_warnings: bool,
+    /// Write a software bill of materials of the contract in the given format, which is
+    /// linked from the build info of the contract bundle.
+    #[clap(long, value_enum)]
+    sbom: Option<SbomFormat>,
+    /// The policy file of the chain to build the contract for, the build fails if the
+    /// contract imports a host function which the chain does not provide.
+    ///
+    /// It can also be set as `chain` in the `[package.metadata.contract]` of your
+    /// `Cargo.toml`.
+    #[clap(long, value_name = "PATH")]
+    chain: Option<String>,
+    /// Rebuild the contract whenever its sources, its path dependencies or its
+    /// `Cargo.toml` change.
+    #[clap(long, conflicts_with_all = ["verifiable", "output_json"])]
+    watch: bool,
 }
 
 impl BuildCommand {
     pub fn exec(&self) -> Result<BuildResult> {
+        contract_build::execute(self.execute_args()?)
+    }
+
+    /// Returns `true` if the contract should be rebuilt on changes.
+    pub fn is_watch(&self) -> bool {
+        self.watch
+    }
+
+    /// Builds the contract and rebuilds it whenever its sources change.
+    pub fn watch(&self) -> Result<()> {
+        contract_build::watch(self.execute_args()?, print_watch_result)
+    }
+
+    fn execute_args(&self) -> Result<ExecuteArgs> {
         let manifest_path = ManifestPath::try_from(self.manifest_path.as_ref())?;
         let unstable_flags: UnstableFlags =
             TryFrom::<&UnstableOptions>::try_from(&self.unstable_options)?;
@@ -168,6 +212,11 @@ impl BuildCommand {
             None => ImageVariant::Default,
         };
 
+        let (target, additional_targets) = self
+            .target
+            .split_first()
+            .context("At least one target is required")?;
+
         let args = ExecuteArgs {
             manifest_path,
             verbosity,
@@ -181,11 +230,16 @@ impl BuildCommand {
             extra_lints: self.lint,
             output_type,
             skip_wasm_validation: self.skip_wasm_validation,
-            target: self.target,
+            target: *target,
+            additional_targets: additional_targets.to_vec(),
             max_memory_pages: self.max_memory_pages,
             image,
+            from_git: self.from_git.clone(),
+            deny_manifest_warnings: self.deny_manifest_warnings,
+            sbom: self.sbom,
+            chain: self.chain.clone(),
         };
-        contract_build::execute(args)
+        Ok(args)
     }
 }
 
@@ -197,10 +251,32 @@ pub struct CheckCommand {
     manifest_path: Option<PathBuf>,
     #[clap(flatten)]
     verbosity: VerbosityFlags,
+    /// Fail if the `Cargo.toml` of the contract is misconfigured, instead of only
+    /// printing warnings.
+    #[clap(long)]
+    deny_manifest_warnings: bool,
+    /// Check the contract again whenever its sources, its path dependencies or its
+    /// `Cargo.toml` change.
+    #[clap(long)]
+    watch: bool,
 }
 
 impl CheckCommand {
     pub fn exec(&self) -> Result<BuildResult> {
+        contract_build::execute(self.execute_args()?)
+    }
+
+    /// Returns `true` if the contract should be checked again on changes.
+    pub fn is_watch(&self) -> bool {
+        self.watch
+    }
+
+    /// Checks the contract and checks it again whenever its sources change.
+    pub fn watch(&self) -> Result<()> {
+        contract_build::watch(self.execute_args()?, print_watch_result)
+    }
+
+    fn execute_args(&self) -> Result<ExecuteArgs> {
         let manifest_path = ManifestPath::try_from(self.manifest_path.as_ref())?;
         let verbosity: Verbosity = TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?;
 
@@ -218,10 +294,54 @@ impl CheckCommand {
             output_type: OutputType::default(),
             skip_wasm_validation: false,
             target: Default::default(),
+            additional_targets: Vec::new(),
             max_memory_pages: 0,
             image: ImageVariant::Default,
+            from_git: None,
+            deny_manifest_warnings: self.deny_manifest_warnings,
+            sbom: None,
+            chain: None,
         };
+        Ok(args)
+    }
+}
 
-        contract_build::execute(args)
+/// Prints a single line summarizing the result of a build in watch mode.
+fn print_watch_result(result: Result<BuildResult>, elapsed: Duration) {
+    let elapsed = format!("{:.1}s", elapsed.as_secs_f64());
+    match result {
+        Ok(result) => {
+            let artifact = result
+                .metadata_result
+                .as_ref()
+                .map(|metadata| &metadata.dest_bundle)
+                .or(result.dest_wasm.as_ref());
+            match artifact {
+                Some(artifact) => {
+                    let unchanged = if result.optimization_result.is_none() {
+                        " (unchanged)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "{} {}{} in {}",
+                        "Built".green().bold(),
+                        artifact.display().to_string().bold(),
+                        unchanged,
+                        elapsed
+                    )
+                }
+                None => println!("{} in {}", "Checked".green().bold(), elapsed),
+            }
+        }
+        Err(err) => {
+            eprintln!("{} in {}", "Failed".bright_red().bold(), elapsed);
+            // the errors of the compilation itself are already printed by `cargo`
+            let err = format!("{err:?}");
+            if !err.trim().is_empty() {
+                eprintln!("{}", err.bright_red())
+            }
+        }
     }
+    println!("{}", "Waiting for changes...".bold());
 }
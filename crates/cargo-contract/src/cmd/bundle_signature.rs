@@ -0,0 +1,234 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    bail,
+    Result,
+};
+use colored::Colorize;
+use contract_extrinsics::{
+    sign_metadata,
+    verify_signature,
+    TrustedKeys,
+};
+use contract_metadata::{
+    BundleFormat,
+    ContractMetadata,
+    SignatureScheme,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+/// Signs a contract bundle with the key of a build pipeline.
+#[derive(Debug, clap::Args)]
+pub struct SignBundleCommand {
+    /// Path to the `.contract` bundle to sign.
+    #[clap(value_parser)]
+    bundle: PathBuf,
+    /// Secret key URI of the signing key.
+    ///
+    /// e.g.
+    /// - for a dev account "//Alice"
+    /// - with a password "//Alice///SECRET_PASSWORD"
+    #[clap(name = "suri", long, short)]
+    suri: String,
+    /// The signature scheme of the key, `sr25519` or `ed25519`.
+    #[clap(long, default_value = "sr25519")]
+    scheme: SignatureScheme,
+    /// Write the signed bundle to this path instead of updating the bundle in place.
+    #[clap(long, value_parser)]
+    output: Option<PathBuf>,
+}
+
+impl SignBundleCommand {
+    pub fn run(&self) -> Result<()> {
+        let format = BundleFormat::detect(&self.bundle)?;
+        let mut metadata = ContractMetadata::load(&self.bundle)?;
+        let signature = sign_metadata(&mut metadata, self.scheme, &self.suri)?;
+        let output = self.output.as_ref().unwrap_or(&self.bundle);
+        // the signed bundle keeps the format of the bundle
+        match format {
+            BundleFormat::Json => fs::write(output, serde_json::to_string(&metadata)?)?,
+            BundleFormat::Compact => metadata.save_compact(output)?,
+        }
+        println!(
+            "{} {} with the {} key 0x{}",
+            "Signed".bright_green().bold(),
+            output.display().to_string().bold(),
+            signature.scheme,
+            hex::encode(&signature.public_key)
+        );
+        Ok(())
+    }
+}
+
+/// Verifies the signatures of a contract bundle.
+#[derive(Debug, clap::Args)]
+pub struct VerifyBundleCommand {
+    /// Path to the `.contract` bundle to verify.
+    #[clap(value_parser)]
+    bundle: PathBuf,
+    /// Require a valid signature by a key from this file, with the signature scheme
+    /// and the hex encoded public key per line, e.g. `sr25519 0xd435..`.
+    #[clap(long, value_parser)]
+    trusted_keys: Option<PathBuf>,
+    /// Output the result in JSON format
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl VerifyBundleCommand {
+    pub fn run(&self) -> Result<BundleVerificationResult> {
+        let metadata = ContractMetadata::load(&self.bundle)?;
+        let trusted_keys = self
+            .trusted_keys
+            .as_deref()
+            .map(TrustedKeys::load)
+            .transpose()?;
+        if metadata.signatures.is_empty() {
+            bail!(
+                "The contract bundle {} is not signed",
+                self.bundle.display()
+            )
+        }
+        let mut signatures = Vec::new();
+        for signature in &metadata.signatures {
+            let public_key = format!("0x{}", hex::encode(&signature.public_key));
+            if !verify_signature(&metadata, signature)? {
+                bail!(
+                    "The {} signature of the key {public_key} is invalid, the contract \
+                    bundle was modified after signing",
+                    signature.scheme
+                )
+            }
+            signatures.push(VerifiedSignature {
+                scheme: signature.scheme,
+                public_key,
+                trusted: trusted_keys.as_ref().map(|keys| keys.is_trusted(signature)),
+            });
+        }
+        if let Some(trusted_keys) = &trusted_keys {
+            trusted_keys.check(&metadata)?;
+        }
+        Ok(BundleVerificationResult {
+            bundle: self.bundle.display().to_string(),
+            signatures,
+            output_json: self.output_json,
+        })
+    }
+}
+
+/// A valid signature of a contract bundle.
+#[derive(serde::Serialize)]
+pub struct VerifiedSignature {
+    pub scheme: SignatureScheme,
+    pub public_key: String,
+    /// Whether the key is trusted, `None` if no trusted keys were given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trusted: Option<bool>,
+}
+
+/// The result of verifying the signatures of a contract bundle.
+#[derive(serde::Serialize)]
+pub struct BundleVerificationResult {
+    pub bundle: String,
+    pub signatures: Vec<VerifiedSignature>,
+    #[serde(skip_serializing)]
+    pub output_json: bool,
+}
+
+impl BundleVerificationResult {
+    /// Display the result in a fancy format
+    pub fn display(&self) -> String {
+        let mut display = format!(
+            "{} {}",
+            "Successfully verified the signatures of"
+                .bright_green()
+                .bold(),
+            format!("`{}`", self.bundle).bold()
+        );
+        for signature in &self.signatures {
+            let trusted = match signature.trusted {
+                Some(true) => " (trusted)",
+                Some(false) => " (untrusted)",
+                None => "",
+            };
+            display.push_str(&format!(
+                "\n  - {} {}{trusted}",
+                signature.scheme, signature.public_key
+            ));
+        }
+        display
+    }
+
+    /// Display the result in a pretty formatted JSON string.
+    pub fn serialize_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_compact_bundle_in_place() {
+        let metadata: ContractMetadata = serde_json::from_str(
+            r#"{
+                "source": {
+                    "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                    "language": "ink! 5.0.0",
+                    "compiler": "rustc 1.78.0",
+                    "wasm": "0x0061736d01000000"
+                },
+                "contract": { "name": "flipper", "version": "5.0.0", "authors": [] },
+                "image": null,
+                "spec": { "messages": [] },
+                "version": 5
+            }"#,
+        )
+        .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("flipper.contractz");
+        metadata.save_compact(&bundle).unwrap();
+
+        SignBundleCommand {
+            bundle: bundle.clone(),
+            suri: "//Alice".to_string(),
+            scheme: SignatureScheme::Sr25519,
+            output: None,
+        }
+        .run()
+        .unwrap();
+
+        assert_eq!(
+            BundleFormat::detect(&bundle).unwrap(),
+            BundleFormat::Compact
+        );
+        let verified = VerifyBundleCommand {
+            bundle: bundle.clone(),
+            trusted_keys: None,
+            output_json: false,
+        }
+        .run()
+        .unwrap();
+        assert_eq!(verified.signatures.len(), 1);
+        let signed = ContractMetadata::load(&bundle).unwrap();
+        assert_eq!(signed.source.wasm, metadata.source.wasm);
+    }
+}
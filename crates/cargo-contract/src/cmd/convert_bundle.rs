@@ -0,0 +1,76 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use colored::Colorize;
+use contract_metadata::{
+    BundleFormat,
+    ContractMetadata,
+    COMPACT_BUNDLE_EXTENSION,
+};
+use std::{
+    fs,
+    path::PathBuf,
+};
+
+/// Converts a contract bundle between the JSON and the compact format.
+#[derive(Debug, clap::Args)]
+pub struct ConvertBundleCommand {
+    /// Path to the contract bundle to convert.
+    #[clap(value_parser)]
+    bundle: PathBuf,
+    /// The format to convert to, `json` or `compact`. Defaults to the other format of
+    /// the bundle.
+    #[clap(long)]
+    to: Option<BundleFormat>,
+    /// Write the converted bundle to this path. Defaults to the bundle path with the
+    /// `.contract` extension for JSON and `.contractz` for compact bundles.
+    #[clap(long, value_parser)]
+    output: Option<PathBuf>,
+}
+
+impl ConvertBundleCommand {
+    pub fn run(&self) -> Result<()> {
+        let from = BundleFormat::detect(&self.bundle)?;
+        let to = self.to.unwrap_or(match from {
+            BundleFormat::Json => BundleFormat::Compact,
+            BundleFormat::Compact => BundleFormat::Json,
+        });
+        let metadata = ContractMetadata::load(&self.bundle)?;
+        let size = fs::metadata(&self.bundle)?.len();
+        let output = self.output.clone().unwrap_or_else(|| {
+            match to {
+                BundleFormat::Json => self.bundle.with_extension("contract"),
+                BundleFormat::Compact => {
+                    self.bundle.with_extension(COMPACT_BUNDLE_EXTENSION)
+                }
+            }
+        });
+        match to {
+            BundleFormat::Json => fs::write(&output, serde_json::to_string(&metadata)?)?,
+            BundleFormat::Compact => metadata.save_compact(&output)?,
+        }
+        println!(
+            "{} {} ({from}, {} bytes) to {} ({to}, {} bytes)",
+            "Converted".bright_green().bold(),
+            self.bundle.display(),
+            size,
+            output.display().to_string().bold(),
+            fs::metadata(&output)?.len()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::call_with_config;
+
+use super::{
+    parse_code_hash,
+    CLIChainOpts,
+};
+use anyhow::{
+    anyhow,
+    bail,
+    Result,
+};
+use contract_analyze::{
+    determine_target,
+    diff_modules,
+    Module,
+    SourceTarget,
+};
+use contract_extrinsics::{
+    fetch_wasm_code,
+    url_to_string,
+    ContractArtifacts,
+    ErrorVariant,
+};
+use ink_env::Environment;
+use std::{
+    fmt::Display,
+    path::PathBuf,
+};
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        rpc::RpcClient,
+    },
+    ext::scale_decode::IntoVisitor,
+    Config,
+    OnlineClient,
+};
+
+#[derive(Debug, clap::Args)]
+#[clap(name = "diff-code", about = "Compare the code of two contracts")]
+pub struct DiffCodeCommand {
+    /// The reference code: a `.wasm` file, a `.contract` bundle or the hash of code
+    /// uploaded to the chain.
+    reference: String,
+    /// The code to compare with the reference, e.g. of a contract upgrade.
+    other: String,
+    /// Export the differences in JSON format.
+    #[clap(name = "output-json", long)]
+    output_json: bool,
+    /// Arguments required for communicating with a Substrate node.
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl DiffCodeCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        let files = [&self.reference, &self.other].map(PathBuf::from);
+        let (reference, other) = if files.iter().all(|file| file.exists()) {
+            let [reference, other] = files;
+            (read_code(reference)?, read_code(other)?)
+        } else {
+            call_with_config!(self, load_code, self.chain_cli_opts.chain().config())?
+        };
+        for code in [&reference, &other] {
+            if determine_target(code)? == SourceTarget::RiscV {
+                return Err(anyhow!(
+                    "Comparing the code of RISC-V contracts is not supported"
+                )
+                .into())
+            }
+        }
+        let diff = diff_modules(&Module::new(&reference)?, &Module::new(&other)?)?;
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+        } else {
+            print!("{diff}");
+        }
+        Ok(())
+    }
+
+    /// Loads the reference and the other code from files or from the chain.
+    async fn load_code<C: Config + Environment>(
+        &self,
+    ) -> Result<(Vec<u8>, Vec<u8>), ErrorVariant>
+    where
+        <C as Config>::Hash: IntoVisitor + Display + From<[u8; 32]>,
+    {
+        let mut codes = Vec::new();
+        let mut client = None;
+        for source in [&self.reference, &self.other] {
+            let path = PathBuf::from(source);
+            let code = if path.exists() {
+                read_code(path)?
+            } else {
+                let code_hash =
+                    parse_code_hash::<<C as Config>::Hash>(source).map_err(|_| {
+                        anyhow!("'{source}' is neither a file nor a code hash")
+                    })?;
+                if client.is_none() {
+                    let rpc_cli = RpcClient::from_url(url_to_string(
+                        &self.chain_cli_opts.chain().url(),
+                    ))
+                    .await?;
+                    client = Some((
+                        OnlineClient::<C>::from_rpc_client(rpc_cli.clone()).await?,
+                        LegacyRpcMethods::<C>::new(rpc_cli),
+                    ));
+                }
+                let (client, rpc) = client.as_ref().expect("client was connected");
+                fetch_wasm_code(client, rpc, &code_hash).await?
+            };
+            codes.push(code);
+        }
+        let other = codes.pop().expect("two codes were loaded");
+        let reference = codes.pop().expect("two codes were loaded");
+        Ok((reference, other))
+    }
+}
+
+/// Reads the code from a `.wasm` file or a `.contract` bundle.
+fn read_code(path: PathBuf) -> Result<Vec<u8>> {
+    let artifacts = ContractArtifacts::from_manifest_or_file(None, Some(&path))?;
+    match artifacts.code {
+        Some(code) => Ok(code.into_bytes()),
+        None => bail!("No contract code found in '{}'", path.display()),
+    }
+}
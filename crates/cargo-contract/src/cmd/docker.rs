@@ -0,0 +1,258 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use colored::Colorize;
+use comfy_table::{
+    ContentArrangement,
+    Table,
+};
+use contract_build::{
+    list_build_containers,
+    list_build_images,
+    remove_build_containers,
+    remove_build_images,
+    BuildContainer,
+    BuildImage,
+    ImageVariant,
+};
+use std::{
+    path::PathBuf,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Manage the docker containers and images used by verifiable builds.
+#[derive(Debug, clap::Subcommand)]
+pub enum DockerCommand {
+    /// List the containers and images used by verifiable builds
+    #[clap(name = "ls")]
+    List {
+        /// Export the output in JSON format.
+        #[clap(long)]
+        output_json: bool,
+    },
+    /// Remove build containers by name or digest, or images by id or tag
+    #[clap(name = "rm")]
+    Remove {
+        /// The names or digests of the containers to remove.
+        #[clap(required = true)]
+        names: Vec<String>,
+        /// Remove the images with the given ids or tags instead of containers.
+        #[clap(long)]
+        images: bool,
+        /// Also stop and remove running containers, aborting their builds.
+        #[clap(long)]
+        force: bool,
+    },
+    /// Remove all build containers matching the filters
+    ///
+    /// Running containers are never removed. Removing a container also removes the
+    /// cargo registry cache kept inside it.
+    #[clap(name = "prune")]
+    Prune {
+        /// Only remove containers which have not been used for the given number of
+        /// days.
+        #[clap(long, value_name = "DAYS")]
+        unused_for: Option<u64>,
+        /// Only remove containers building the project at the given path.
+        #[clap(long)]
+        project: Option<PathBuf>,
+        /// Also remove images which are no longer used by any build container, except
+        /// the default image of verifiable builds.
+        #[clap(long)]
+        images: bool,
+        /// Only print what would be removed.
+        #[clap(long)]
+        dry_run: bool,
+    },
+}
+
+impl DockerCommand {
+    pub fn run(&self) -> Result<()> {
+        match self {
+            Self::List { output_json } => {
+                let containers = list_build_containers()?;
+                let images = list_build_images()?;
+                if *output_json {
+                    let output = serde_json::json!({
+                        "containers": containers,
+                        "images": images,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                } else {
+                    print_containers(&containers);
+                    print_images(&images);
+                }
+                Ok(())
+            }
+            Self::Remove {
+                names,
+                images,
+                force,
+            } => {
+                if *images {
+                    remove_build_images(names)?;
+                } else {
+                    let containers = list_build_containers()?;
+                    let mut selected = Vec::new();
+                    for name in names {
+                        let container = containers
+                            .iter()
+                            .find(|c| &c.name == name || &c.digest == name)
+                            .ok_or_else(|| {
+                                anyhow::anyhow!("No build container `{name}` found")
+                            })?;
+                        if container.running && !*force {
+                            anyhow::bail!(
+                                "The build container `{}` is running a build, pass \
+                                `--force` to remove it anyway",
+                                container.name
+                            )
+                        }
+                        selected.push(container.name.clone());
+                    }
+                    remove_build_containers(&selected, *force)?;
+                }
+                for name in names {
+                    println!("{} {}", "Removed".green().bold(), name);
+                }
+                Ok(())
+            }
+            Self::Prune {
+                unused_for,
+                project,
+                images,
+                dry_run,
+            } => {
+                let project = project.as_ref().map(|p| p.canonicalize()).transpose()?;
+                let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+                let containers = list_build_containers()?;
+                let (selected, remaining): (Vec<_>, Vec<_>) =
+                    containers.into_iter().partition(|c| {
+                        // a container without a finished build may be building right
+                        // now, so it is considered in use
+                        let unused = match (unused_for, c.last_used) {
+                            _ if c.running => false,
+                            (Some(days), Some(last_used)) => {
+                                now.saturating_sub(last_used) >= days * SECONDS_PER_DAY
+                            }
+                            (Some(_), None) => false,
+                            (None, _) => true,
+                        };
+                        let in_project = match (&project, &c.project_path) {
+                            (Some(project), Some(path)) => {
+                                PathBuf::from(path).starts_with(project)
+                            }
+                            (Some(_), None) => false,
+                            (None, _) => true,
+                        };
+                        unused && in_project
+                    });
+                let selected_names =
+                    selected.iter().map(|c| c.name.clone()).collect::<Vec<_>>();
+
+                let mut unused_images = Vec::new();
+                if *images {
+                    // the default image is kept for the next verifiable build
+                    let default_image = ImageVariant::Default.image_name();
+                    unused_images = list_build_images()?
+                        .into_iter()
+                        .filter(|i| {
+                            !i.tags.contains(&default_image)
+                                && !remaining
+                                    .iter()
+                                    .any(|c| i.containers.contains(&c.name))
+                        })
+                        .map(|i| i.id)
+                        .collect();
+                }
+
+                let action = if *dry_run { "Would remove" } else { "Removed" };
+                if !*dry_run {
+                    remove_build_containers(&selected_names, false)?;
+                    remove_build_images(&unused_images)?;
+                }
+                for name in selected_names.iter().chain(&unused_images) {
+                    println!("{} {}", action.green().bold(), name);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Print the build containers as a table.
+fn print_containers(containers: &[BuildContainer]) {
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec![
+        "Container",
+        "Digest",
+        "Project",
+        "Image",
+        "Status",
+        "Last Used",
+    ]);
+    for c in containers {
+        table.add_row(vec![
+            c.name.as_str(),
+            c.digest.as_str(),
+            c.project_path.as_deref().unwrap_or("-"),
+            c.image.as_str(),
+            c.status.as_str(),
+            &c.last_used.map(format_age).unwrap_or_else(|| "-".into()),
+        ]);
+    }
+    println!("{table}");
+}
+
+/// Print the build images as a table.
+fn print_images(images: &[BuildImage]) {
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["Image", "Tags", "Size", "Containers"]);
+    for i in images {
+        table.add_row(vec![
+            i.id.trim_start_matches("sha256:")
+                .chars()
+                .take(12)
+                .collect(),
+            i.tags.join("\n"),
+            format!("{:.1} MB", i.size as f64 / 1_000_000.0),
+            i.containers.len().to_string(),
+        ]);
+    }
+    println!("{table}");
+}
+
+/// Format the time passed since `timestamp` in a human readable way.
+fn format_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let seconds = now.saturating_sub(timestamp);
+    match seconds {
+        s if s < 60 * 60 => format!("{} minutes ago", s / 60),
+        s if s < SECONDS_PER_DAY => format!("{} hours ago", s / (60 * 60)),
+        s => format!("{} days ago", s / SECONDS_PER_DAY),
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use colored::Colorize;
+use contract_build::{
+    diagnose,
+    Diagnostic,
+    DiagnosticLevel,
+    ManifestPath,
+    Target,
+};
+use std::path::PathBuf;
+
+/// Diagnose the environment for building contracts.
+#[derive(Debug, clap::Args)]
+#[clap(name = "doctor")]
+pub struct DoctorCommand {
+    /// Path to the `Cargo.toml` of a contract to check as well. Defaults to the
+    /// `Cargo.toml` in the current directory, if present.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// Which bytecode the contracts are built into.
+    #[clap(long, default_value = "wasm")]
+    target: Target,
+    /// Export the diagnostics in JSON format.
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl DoctorCommand {
+    pub fn run(&self) -> Result<DoctorResult> {
+        let manifest_path = match &self.manifest_path {
+            Some(path) => Some(ManifestPath::new(path)?),
+            // only check the manifest of the current directory if it is a package,
+            // e.g. not a virtual workspace manifest
+            None => {
+                ManifestPath::try_from(None::<PathBuf>).ok().filter(|path| {
+                    std::fs::read_to_string(path)
+                        .map(|manifest| manifest.contains("[package]"))
+                        .unwrap_or_default()
+                })
+            }
+        };
+
+        Ok(DoctorResult {
+            diagnostics: diagnose(manifest_path.as_ref(), self.target),
+            output_json: self.output_json,
+        })
+    }
+}
+
+/// The result of the environment diagnostics.
+#[derive(serde::Serialize)]
+pub struct DoctorResult {
+    pub diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing)]
+    pub output_json: bool,
+}
+
+impl DoctorResult {
+    /// Returns `true` if any check failed with an error.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|d| d.level == DiagnosticLevel::Error)
+    }
+
+    /// Display the diagnostics in a human readable format.
+    pub fn display(&self) -> String {
+        let mut out = String::new();
+        for diagnostic in &self.diagnostics {
+            let level = match diagnostic.level {
+                DiagnosticLevel::Ok => "ok".bright_green().bold(),
+                DiagnosticLevel::Warning => "warning".yellow().bold(),
+                DiagnosticLevel::Error => "error".bright_red().bold(),
+            };
+            out.push_str(&format!(
+                "{:>7} {}: {}\n",
+                level,
+                diagnostic.check.bold(),
+                diagnostic.message
+            ));
+            if let Some(fix) = &diagnostic.fix {
+                for line in fix.lines() {
+                    out.push_str(&format!("{:>7} {}\n", "", line.trim()));
+                }
+            }
+        }
+        out
+    }
+
+    /// Display the diagnostics in a pretty formatted JSON string.
+    pub fn serialize_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
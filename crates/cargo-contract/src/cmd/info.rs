@@ -23,7 +23,19 @@ use super::{
     CLIChainOpts,
 };
 use anyhow::Result;
-use contract_analyze::determine_language;
+use colored::Colorize;
+use contract_analyze::{
+    determine_language,
+    determine_target,
+    estimate_compiler,
+    CompilerEstimate,
+    SourceTarget,
+};
+use contract_build::{
+    verbose_eprintln,
+    Verbosity,
+    VerbosityFlags,
+};
 use contract_extrinsics::{
     fetch_all_contracts,
     fetch_contract_info,
@@ -68,9 +80,9 @@ pub struct InfoCommand {
     )]
     contract: Option<String>,
     /// Export the instantiate output in JSON format.
-    #[clap(name = "output-json", long)]
+    #[clap(name = "output-json", long, conflicts_with = "verbose")]
     output_json: bool,
-    /// Display the contract's Wasm bytecode.
+    /// Display the contract's code, i.e. the Wasm bytecode or the PolkaVM program.
     #[clap(name = "binary", long, conflicts_with = "all")]
     binary: bool,
     /// Display all contracts addresses
@@ -79,6 +91,9 @@ pub struct InfoCommand {
     /// Arguments required for communicating with a Substrate node.
     #[clap(flatten)]
     chain_cli_opts: CLIChainOpts,
+    /// Denotes if output should be printed to stdout.
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
 }
 
 impl InfoCommand {
@@ -132,32 +147,37 @@ impl InfoCommand {
             // Binary flag applied
             if self.binary {
                 if self.output_json {
-                    let wasm = serde_json::json!({
-                        "wasm": format!("0x{}", hex::encode(wasm_code))
+                    // the code is keyed by its target, as in the contract bundle
+                    let target =
+                        determine_target(&wasm_code).unwrap_or(SourceTarget::Wasm);
+                    let code = serde_json::json!({
+                        target.to_string(): format!("0x{}", hex::encode(&wasm_code))
                     });
-                    println!("{}", serde_json::to_string_pretty(&wasm)?);
+                    println!("{}", serde_json::to_string_pretty(&code)?);
                 } else {
                     std::io::stdout()
                         .write_all(&wasm_code)
                         .expect("Writing to stdout failed")
                 }
-            } else if self.output_json {
-                println!(
-                    "{}",
-                    serde_json::to_string_pretty(&ExtendedContractInfo::<
-                        <C as Config>::Hash,
-                        C::Balance,
-                    >::new(
-                        info_to_json, &wasm_code
-                    ))?
-                )
             } else {
-                basic_display_format_extended_contract_info(&ExtendedContractInfo::<
-                    <C as Config>::Hash,
-                    C::Balance,
-                >::new(
-                    info_to_json, &wasm_code
-                ))
+                let info = ExtendedContractInfo::<<C as Config>::Hash, C::Balance>::new(
+                    info_to_json,
+                    &wasm_code,
+                );
+                if self.output_json {
+                    println!("{}", serde_json::to_string_pretty(&info)?)
+                } else {
+                    let verbosity = Verbosity::try_from(&self.verbosity)?;
+                    if let Some(warning) = &info.compatibility_warning {
+                        verbose_eprintln!(
+                            verbosity,
+                            "{} {}",
+                            "warning:".yellow().bold(),
+                            warning.bold()
+                        );
+                    }
+                    basic_display_format_extended_contract_info(&info)
+                }
             }
             Ok(())
         }
@@ -172,6 +192,10 @@ pub struct ExtendedContractInfo<Hash, Balance> {
     pub storage_items_deposit: Balance,
     pub storage_total_deposit: Balance,
     pub source_language: String,
+    pub compiler: Option<CompilerEstimate>,
+    /// Why none of the estimated ink! versions is supported, if so.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatibility_warning: Option<String>,
 }
 
 impl<Hash, Balance> ExtendedContractInfo<Hash, Balance>
@@ -184,6 +208,11 @@ where
             Some(lang) => lang.to_string(),
             None => "Unknown".to_string(),
         };
+        let compiler = estimate_compiler(code).ok();
+        let compatibility_warning = compiler
+            .as_ref()
+            .and_then(|compiler| compiler.check_ink_compatibility().err())
+            .map(|err| err.to_string());
         ExtendedContractInfo {
             trie_id: contract_info.trie_id().clone(),
             code_hash: *contract_info.code_hash(),
@@ -191,6 +220,8 @@ where
             storage_items_deposit: contract_info.storage_items_deposit(),
             storage_total_deposit: contract_info.storage_total_deposit(),
             source_language: language,
+            compiler,
+            compatibility_warning,
         }
     }
 }
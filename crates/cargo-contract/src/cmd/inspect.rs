@@ -0,0 +1,242 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::call_with_config;
+
+use super::{
+    parse_code_hash,
+    CLIChainOpts,
+    MAX_KEY_COL_WIDTH,
+};
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_analyze::{
+    EntryPoint,
+    Inspection,
+    KnownSelectors,
+    RecoveredSelector,
+};
+use contract_build::name_value_println;
+use contract_extrinsics::{
+    fetch_wasm_code,
+    url_to_string,
+    ContractArtifacts,
+    ErrorVariant,
+};
+use ink_env::Environment;
+use std::{
+    fmt::Display,
+    fs,
+    path::PathBuf,
+};
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        rpc::RpcClient,
+    },
+    ext::scale_decode::IntoVisitor,
+    Config,
+    OnlineClient,
+};
+
+#[derive(Debug, clap::Args)]
+#[clap(name = "inspect", about = "Inspect the code of a contract")]
+pub struct InspectCommand {
+    /// Path to the contract code: a `.wasm` or `.riscv` file, or a `.contract` bundle.
+    #[clap(value_parser, conflicts_with_all = ["manifest_path", "code_hash"])]
+    file: Option<PathBuf>,
+    /// Path to the `Cargo.toml` of the contract, to inspect its build artifacts.
+    #[clap(long, value_parser, conflicts_with = "code_hash")]
+    manifest_path: Option<PathBuf>,
+    /// The hash of contract code uploaded to the chain, to inspect that code.
+    #[clap(long)]
+    code_hash: Option<String>,
+    /// A database of known selectors to label the recovered selectors with, with a
+    /// label or a hex encoded selector and its label per line.
+    #[clap(long, value_parser)]
+    known_selectors: Option<PathBuf>,
+    /// Write skeleton metadata with the recovered selectors to this path, to call the
+    /// contract without its metadata, e.g. `unknown.contract`.
+    #[clap(long, value_parser)]
+    skeleton_metadata: Option<PathBuf>,
+    /// Export the inspection output in JSON format.
+    #[clap(name = "output-json", long)]
+    output_json: bool,
+    /// Arguments required for communicating with a Substrate node.
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+}
+
+impl InspectCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        let code = if self.code_hash.is_some() {
+            call_with_config!(self, fetch_code, self.chain_cli_opts.chain().config())?
+        } else {
+            let artifacts = ContractArtifacts::from_manifest_or_file(
+                self.manifest_path.as_ref(),
+                self.file.as_ref(),
+            )?;
+            let path = artifacts.artifact_path().to_path_buf();
+            artifacts
+                .code
+                .ok_or_else(|| anyhow!("No contract code found in '{}'", path.display()))?
+                .into_bytes()
+        };
+        let mut inspection = Inspection::new(&code)?;
+        if let Some(path) = &self.known_selectors {
+            let mut known = KnownSelectors::builtin();
+            known.parse(&fs::read_to_string(path).context(format!(
+                "Reading the selector database {} failed",
+                path.display()
+            ))?)?;
+            if let Some(selectors) = &mut inspection.selectors {
+                selectors.label(&known);
+            }
+        }
+        if let Some(path) = &self.skeleton_metadata {
+            let selectors = inspection.selectors.as_ref().ok_or_else(|| {
+                anyhow!("No selectors could be recovered from the contract code")
+            })?;
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("unknown");
+            let metadata = selectors.skeleton_metadata(name, &code)?;
+            fs::write(path, serde_json::to_string_pretty(&metadata)?)?;
+        }
+        if self.output_json {
+            println!("{}", serde_json::to_string_pretty(&inspection)?);
+        } else {
+            display_inspection(&inspection);
+        }
+        Ok(())
+    }
+
+    /// Fetches the code with the given code hash from the chain.
+    async fn fetch_code<C: Config + Environment>(&self) -> Result<Vec<u8>, ErrorVariant>
+    where
+        <C as Config>::Hash: IntoVisitor + Display + From<[u8; 32]>,
+    {
+        let code_hash = self
+            .code_hash
+            .as_ref()
+            .map(|h| parse_code_hash::<<C as Config>::Hash>(h))
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse code_hash option: {}", e))?
+            .expect("code hash is present");
+        let rpc_cli =
+            RpcClient::from_url(url_to_string(&self.chain_cli_opts.chain().url()))
+                .await?;
+        let client = OnlineClient::<C>::from_rpc_client(rpc_cli.clone()).await?;
+        let rpc = LegacyRpcMethods::<C>::new(rpc_cli);
+        Ok(fetch_wasm_code(&client, &rpc, &code_hash).await?)
+    }
+}
+
+/// Displays the inspection of contract code in a formatted way.
+fn display_inspection(inspection: &Inspection) {
+    name_value_println!("Target", inspection.target, MAX_KEY_COL_WIDTH);
+    name_value_println!(
+        "Language",
+        inspection.language.as_deref().unwrap_or("Unknown"),
+        MAX_KEY_COL_WIDTH
+    );
+    name_value_println!(
+        "Code Size",
+        format!("{} bytes", inspection.code_size),
+        MAX_KEY_COL_WIDTH
+    );
+    if let Some(count) = inspection.function_count {
+        name_value_println!("Functions", count, MAX_KEY_COL_WIDTH);
+    }
+    if let Some(memory) = &inspection.memory {
+        name_value_println!("Memory", memory, MAX_KEY_COL_WIDTH);
+    }
+    for (module, functions) in &inspection.imports {
+        name_value_println!(
+            "Imports",
+            format!("{module}: {}", functions.join(", ")),
+            MAX_KEY_COL_WIDTH
+        );
+    }
+    let exports = inspection
+        .exports
+        .iter()
+        .map(|export| format!("{} ({})", export.name, export.kind))
+        .collect::<Vec<_>>();
+    name_value_println!("Exports", exports.join(", "), MAX_KEY_COL_WIDTH);
+    for (name, size) in &inspection.custom_sections {
+        name_value_println!(
+            "Custom Section",
+            format!("{name} ({size} bytes)"),
+            MAX_KEY_COL_WIDTH
+        );
+    }
+    let display_entry_point = |name: &str, entry_point: &EntryPoint| {
+        name_value_println!(
+            "Reaches",
+            format!("{name}: {}", entry_point.host_functions.join(", ")),
+            MAX_KEY_COL_WIDTH
+        );
+        if !entry_point.dangerous.is_empty() {
+            name_value_println!(
+                "Dangerous",
+                format!("{name}: {}", entry_point.dangerous.join(", ")).bright_yellow(),
+                MAX_KEY_COL_WIDTH
+            );
+        }
+    };
+    for entry_point in &inspection.entry_points {
+        display_entry_point(&entry_point.name, entry_point);
+        for selector in &entry_point.selectors {
+            let name = format!("{} ({})", entry_point.name, selector.name);
+            if selector.resolved {
+                display_entry_point(&name, selector);
+            } else {
+                // the host functions are those of the dispatching export
+                name_value_println!(
+                    "Reaches",
+                    format!("{name}: unresolved, any of `{}`", entry_point.name),
+                    MAX_KEY_COL_WIDTH
+                );
+            }
+        }
+    }
+    if let Some(selectors) = &inspection.selectors {
+        let display = |selectors: &[RecoveredSelector]| {
+            selectors
+                .iter()
+                .map(|recovered| {
+                    let selector = format!("0x{}", hex::encode(recovered.selector));
+                    match &recovered.label {
+                        Some(label) => format!("{label} ({selector})"),
+                        None => selector,
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        name_value_println!(
+            "Constructors",
+            display(&selectors.constructors),
+            MAX_KEY_COL_WIDTH
+        );
+        name_value_println!("Messages", display(&selectors.messages), MAX_KEY_COL_WIDTH);
+    }
+}
@@ -51,6 +51,7 @@ use contract_extrinsics::{
     InstantiateDryRunResult,
     InstantiateExecResult,
     TokenMetadata,
+    TrustedKeys,
 };
 use ink_env::Environment;
 use serde::Serialize;
@@ -60,6 +61,7 @@ use std::{
         Debug,
         Display,
     },
+    path::PathBuf,
     str::FromStr,
 };
 use subxt::{
@@ -101,6 +103,11 @@ pub struct InstantiateCommand {
     /// instances of the same contract code from the same account.
     #[clap(long, value_parser = parse_hex_bytes)]
     salt: Option<Bytes>,
+    /// Only proceed if the contract bundle is signed by a key from this file, with the
+    /// signature scheme and the hex encoded public key per line, e.g. `sr25519
+    /// 0xd435..`.
+    #[clap(long, value_parser)]
+    trusted_keys: Option<PathBuf>,
     /// Export the instantiate output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
@@ -160,6 +167,12 @@ impl InstantiateCommand {
             .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
             .url(chain.url())
             .storage_deposit_limit(storage_deposit_limit)
+            .trusted_keys(
+                self.trusted_keys
+                    .as_deref()
+                    .map(TrustedKeys::load)
+                    .transpose()?,
+            )
             .done();
 
         let instantiate_exec: InstantiateExec<C, C, _> =
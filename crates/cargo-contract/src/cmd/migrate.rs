@@ -0,0 +1,122 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use colored::Colorize;
+use contract_build::{
+    migrate,
+    ManifestPath,
+    MigrationReport,
+};
+use std::path::PathBuf;
+
+/// Migrate a contract to a newer major version of ink!.
+#[derive(Debug, clap::Args)]
+#[clap(name = "migrate")]
+pub struct MigrateCommand {
+    /// Path to the `Cargo.toml` of the contract to migrate.
+    #[clap(long, value_parser)]
+    manifest_path: Option<PathBuf>,
+    /// The major version of ink! to migrate to.
+    #[clap(long)]
+    to: u64,
+    /// Only report the changes, without modifying the `Cargo.toml`.
+    #[clap(long)]
+    dry_run: bool,
+    /// Export the report in JSON format.
+    #[clap(long)]
+    output_json: bool,
+}
+
+impl MigrateCommand {
+    pub fn run(&self) -> Result<MigrateResult> {
+        let manifest_path = ManifestPath::try_from(self.manifest_path.as_ref())?;
+        let report = migrate(&manifest_path, self.to, self.dry_run)?;
+        Ok(MigrateResult {
+            report,
+            dry_run: self.dry_run,
+            output_json: self.output_json,
+        })
+    }
+}
+
+/// The result of a migration.
+#[derive(serde::Serialize)]
+pub struct MigrateResult {
+    #[serde(flatten)]
+    pub report: MigrationReport,
+    pub dry_run: bool,
+    #[serde(skip_serializing)]
+    pub output_json: bool,
+}
+
+impl MigrateResult {
+    /// Display the report in a human readable format.
+    pub fn display(&self) -> String {
+        let report = &self.report;
+        let mut out = format!(
+            "{} the contract from ink! {} to ink! {}\n",
+            if self.dry_run {
+                "Migration of"
+            } else {
+                "Migrated"
+            }
+            .bold(),
+            report.from,
+            report.to
+        );
+        if !report.applied.is_empty() {
+            out.push_str(&format!(
+                "\n{}\n",
+                if self.dry_run {
+                    "Changes to the Cargo.toml (not applied, --dry-run):"
+                } else {
+                    "Changes to the Cargo.toml:"
+                }
+                .bold()
+            ));
+            for change in &report.applied {
+                out.push_str(&format!("  - {change}\n"));
+            }
+        }
+        if !report.manual.is_empty() {
+            out.push_str(&format!(
+                "\n{}\n",
+                "Changes which have to be applied manually:".bold()
+            ));
+            for change in &report.manual {
+                let location = match change.line {
+                    Some(line) => format!("{}:{line}", change.file.display()),
+                    None => change.file.display().to_string(),
+                };
+                out.push_str(&format!("  - {}\n    {}\n", location, change.message));
+            }
+        }
+        if let Some(compatibility) = &report.compatibility {
+            out.push_str(&format!(
+                "\n{} {}\n",
+                "warning:".yellow().bold(),
+                compatibility
+            ));
+        }
+        out
+    }
+
+    /// Display the report in a pretty formatted JSON string.
+    pub fn serialize_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
@@ -18,11 +18,18 @@ mod config;
 mod prod_chains;
 
 pub mod build;
+pub mod bundle_signature;
 pub mod call;
+pub mod convert_bundle;
 pub mod decode;
+pub mod diff_code;
+pub mod docker;
+pub mod doctor;
 pub mod encode;
 pub mod info;
+pub mod inspect;
 pub mod instantiate;
+pub mod migrate;
 pub mod remove;
 pub mod rpc;
 pub mod schema;
@@ -35,13 +42,23 @@ pub(crate) use self::{
         BuildCommand,
         CheckCommand,
     },
+    bundle_signature::{
+        SignBundleCommand,
+        VerifyBundleCommand,
+    },
     call::CallCommand,
+    convert_bundle::ConvertBundleCommand,
     decode::DecodeCommand,
+    diff_code::DiffCodeCommand,
+    docker::DockerCommand,
+    doctor::DoctorCommand,
     info::{
         ExtendedContractInfo,
         InfoCommand,
     },
+    inspect::InspectCommand,
     instantiate::InstantiateCommand,
+    migrate::MigrateCommand,
     prod_chains::ProductionChain,
     remove::RemoveCommand,
     rpc::RpcCommand,
@@ -324,6 +341,12 @@ pub fn basic_display_format_extended_contract_info<Hash, Balance>(
         format!("{}", info.source_language),
         MAX_KEY_COL_WIDTH
     );
+    if let Some(compiler) = &info.compiler {
+        name_value_println!("Compiler", compiler, MAX_KEY_COL_WIDTH);
+        for evidence in &compiler.evidence {
+            name_value_println!("Evidence", evidence, MAX_KEY_COL_WIDTH);
+        }
+    }
 }
 
 /// Display all contracts addresses in a formatted way
@@ -23,6 +23,7 @@ use std::{
         Debug,
         Display,
     },
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -39,6 +40,7 @@ use contract_extrinsics::{
     DisplayEvents,
     ExtrinsicOptsBuilder,
     TokenMetadata,
+    TrustedKeys,
     UploadCommandBuilder,
     UploadExec,
 };
@@ -61,6 +63,11 @@ use subxt::{
 pub struct UploadCommand {
     #[clap(flatten)]
     extrinsic_cli_opts: CLIExtrinsicOpts,
+    /// Only proceed if the contract bundle is signed by a key from this file, with the
+    /// signature scheme and the hex encoded public key per line, e.g. `sr25519
+    /// 0xd435..`.
+    #[clap(long, value_parser)]
+    trusted_keys: Option<PathBuf>,
     /// Export the call output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
@@ -116,6 +123,12 @@ impl UploadCommand {
             .manifest_path(self.extrinsic_cli_opts.manifest_path.clone())
             .url(chain.url())
             .storage_deposit_limit(storage_deposit_limit)
+            .trusted_keys(
+                self.trusted_keys
+                    .as_deref()
+                    .map(TrustedKeys::load)
+                    .transpose()?,
+            )
             .done();
 
         let upload_exec: UploadExec<C, C, _> =
@@ -14,11 +14,22 @@
 // You should have received a copy of the GNU General Public License
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::call_with_config;
+
+use super::{
+    parse_account,
+    CLIChainOpts,
+};
 use anyhow::{
     Context,
     Result,
 };
 use colored::Colorize;
+use contract_analyze::{
+    diff_modules,
+    Module,
+    ModuleDiff,
+};
 use contract_build::{
     code_hash,
     execute,
@@ -28,18 +39,41 @@ use contract_build::{
     BuildMode,
     ExecuteArgs,
     ImageVariant,
+    Manifest,
     ManifestPath,
+    OptimizationPasses,
     Verbosity,
     VerbosityFlags,
 };
+use contract_extrinsics::{
+    fetch_contract_info,
+    fetch_wasm_code,
+    url_to_string,
+    ErrorVariant,
+};
 use contract_metadata::{
     CodeHash,
     ContractMetadata,
 };
-
+use ink_env::Environment;
+use serde::Serialize;
 use std::{
+    fmt::Display,
     fs::File,
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        rpc::RpcClient,
+    },
+    ext::scale_decode::IntoVisitor,
+    Config,
+    OnlineClient,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -53,12 +87,22 @@ pub struct VerifyCommand {
     manifest_path: Option<PathBuf>,
     /// The reference Wasm contract (`*.contract`) that the workspace will be checked
     /// against.
+    ///
+    /// When combined with `--address`, the build info of this bundle is used to
+    /// reproduce the on-chain code.
     #[clap(long)]
     contract: Option<PathBuf>,
     /// The reference Wasm contract binary (`*.wasm`) that the workspace will be checked
     /// against.
-    #[clap(long, conflicts_with = "contract")]
+    #[clap(long, conflicts_with_all = ["contract", "address"])]
     wasm: Option<PathBuf>,
+    /// The address of a deployed contract whose on-chain code the workspace will be
+    /// checked against.
+    #[clap(long)]
+    address: Option<String>,
+    /// Arguments required for communicating with a Substrate node.
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
     /// Denotes if output should be printed to stdout.
     #[clap(flatten)]
     verbosity: VerbosityFlags,
@@ -71,12 +115,14 @@ impl VerifyCommand {
     pub fn run(&self) -> Result<VerificationResult> {
         let manifest_path = ManifestPath::try_from(self.manifest_path.as_ref())?;
         let verbosity: Verbosity = TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?;
-        if let Some(path) = &self.contract {
+        if let Some(address) = &self.address {
+            self.verify_deployed(manifest_path, verbosity, address)
+        } else if let Some(path) = &self.contract {
             self.verify_contract(manifest_path, verbosity, path)
         } else if let Some(path) = &self.wasm {
             self.verify_wasm(manifest_path, verbosity, path)
         } else {
-            anyhow::bail!("Either --wasm or --contract must be specified")
+            anyhow::bail!("Either --wasm, --contract or --address must be specified")
         }
     }
 
@@ -85,7 +131,7 @@ impl VerifyCommand {
         &self,
         manifest_path: ManifestPath,
         verbosity: Verbosity,
-        path: &PathBuf,
+        path: &Path,
     ) -> Result<VerificationResult> {
         // 1. Read code hash binary from the path.
         let ref_buffer = std::fs::read(path)
@@ -93,40 +139,29 @@ impl VerifyCommand {
 
         let reference_code_hash = CodeHash(code_hash(&ref_buffer));
 
-        // 2. Call `cargo contract build` in the release mode.
-        let args = ExecuteArgs {
-            manifest_path: manifest_path.clone(),
-            verbosity,
-            optimization_passes: Some(contract_build::OptimizationPasses::Z),
-            build_mode: BuildMode::Release,
-            build_artifact: BuildArtifacts::CodeOnly,
-            extra_lints: false,
-            ..Default::default()
-        };
-
-        let build_result = execute(args)?;
-
-        // 4. Grab the code hash from the built contract and compare it with the reference
-        //    one.
-        let built_wasm_path = if let Some(m) = build_result.dest_wasm {
-            m
-        } else {
-            // Since we're building the contract ourselves this should always be
-            // populated, but we'll bail out here just in case.
-            anyhow::bail!("\nThe workspace contract does not contain a Wasm binary,\n\
-                therefore we are unable to verify the contract."
-                .to_string()
-                .bright_yellow())
-        };
-
-        let target_buffer = std::fs::read(&built_wasm_path).context(format!(
-            "Failed to read contract binary {}",
-            built_wasm_path.display()
-        ))?;
-
-        let output_code_hash = CodeHash(code_hash(&target_buffer));
+        // 2. Call `cargo contract build` in the release mode and grab the code hash from
+        //    the built contract.
+        let (built_wasm_path, built_code) =
+            build_release_code(&manifest_path, verbosity)?;
+        let output_code_hash = CodeHash(code_hash(&built_code));
 
+        // 3. Compare it with the reference one.
         if output_code_hash != reference_code_hash {
+            let code_diff = code_diff(&ref_buffer, &built_code);
+            if self.output_json {
+                return Ok(VerificationResult {
+                    is_verified: false,
+                    image: None,
+                    contract: built_wasm_path.display().to_string(),
+                    reference_contract: path.display().to_string(),
+                    code_hash: Some(output_code_hash),
+                    url: None,
+                    code_diff,
+                    output_json: self.output_json,
+                    verbosity,
+                })
+            }
+            print_code_diff(verbosity, code_diff.as_ref());
             anyhow::bail!(format!(
                 "\nFailed to verify the authenticity of wasm binary at {} against the workspace \n\
                 found at {}.\n Expected {}, found {}",
@@ -142,6 +177,9 @@ impl VerifyCommand {
             image: None,
             contract: built_wasm_path.display().to_string(),
             reference_contract: path.display().to_string(),
+            code_hash: Some(output_code_hash),
+            url: None,
+            code_diff: None,
             output_json: self.output_json,
             verbosity,
         })
@@ -152,124 +190,18 @@ impl VerifyCommand {
         &self,
         manifest_path: ManifestPath,
         verbosity: Verbosity,
-        path: &PathBuf,
+        path: &Path,
     ) -> Result<VerificationResult> {
-        // 1. Read the given metadata, and pull out the `BuildInfo`
-        let file = File::open(path)
-            .context(format!("Failed to open contract bundle {}", path.display()))?;
-
-        let metadata: ContractMetadata = serde_json::from_reader(&file).context(
-            format!("Failed to deserialize contract bundle {}", path.display()),
-        )?;
-        let build_info = if let Some(info) = metadata.source.build_info {
-            info
-        } else {
-            anyhow::bail!(
-                "\nThe metadata does not contain any build information which can be used to \
-                verify a contract."
-                .to_string()
-                .bright_yellow()
-            )
-        };
+        // 1. Read the given metadata.
+        let metadata = load_metadata(path)?;
 
-        let build_info: BuildInfo =
-            serde_json::from_value(build_info.into()).context(format!(
-                "Failed to deserialize the build info from {}",
-                path.display()
-            ))?;
+        // 2. Build the workspace using the `BuildInfo` from the metadata.
+        let (target_bundle, target_metadata) =
+            build_from_metadata(&manifest_path, verbosity, &metadata, path)?;
+        let target_code_hash = target_metadata.source.hash;
 
-        tracing::debug!(
-            "Parsed the following build info from the metadata: {:?}",
-            &build_info,
-        );
-
-        let build_mode = if metadata.image.is_some() {
-            BuildMode::Verifiable
-        } else {
-            build_info.build_mode
-        };
-
-        // 2. Check that the build info from the metadata matches our current setup.
-        // if the build mode is `Verifiable` we skip
-        if build_mode != BuildMode::Verifiable {
-            let expected_rust_toolchain = build_info.rust_toolchain;
-            let rust_toolchain = contract_build::util::rust_toolchain()
-                .expect("`rustc` always has a version associated with it.");
-
-            let rustc_matches = rust_toolchain == expected_rust_toolchain;
-            let mismatched_rustc = format!(
-            "\nYou are trying to `verify` a contract using the `{rust_toolchain}` toolchain.\n\
-             However, the original contract was built using `{expected_rust_toolchain}`. Please\n\
-             install the correct toolchain (`rustup install {expected_rust_toolchain}`) and\n\
-             re-run the `verify` command.",);
-            anyhow::ensure!(rustc_matches, mismatched_rustc.bright_yellow());
-
-            let expected_cargo_contract_version = build_info.cargo_contract_version;
-            let cargo_contract_version = semver::Version::parse(VERSION)?;
-
-            // Note, assuming both versions of `cargo-contract` were installed with the
-            // same lockfile (e.g `--locked`) then the versions of `wasm-opt`
-            // should also match.
-            let cargo_contract_matches =
-                cargo_contract_version == expected_cargo_contract_version;
-            let mismatched_cargo_contract = format!(
-                "\nYou are trying to `verify` a contract using `cargo-contract` version \
-            `{cargo_contract_version}`.\n\
-             However, the original contract was built using `cargo-contract` version \
-             `{expected_cargo_contract_version}`.\n\
-             Please install the matching version and re-run the `verify` command.",
-            );
-            anyhow::ensure!(
-                cargo_contract_matches,
-                mismatched_cargo_contract.bright_yellow()
-            );
-        }
-
-        // 3a. Call `cargo contract build` with the `BuildInfo` from the metadata.
-        let args = ExecuteArgs {
-            manifest_path: manifest_path.clone(),
-            verbosity,
-            build_mode,
-            build_artifact: BuildArtifacts::All,
-            optimization_passes: Some(build_info.wasm_opt_settings.optimization_passes),
-            keep_debug_symbols: build_info.wasm_opt_settings.keep_debug_symbols,
-            image: ImageVariant::from(metadata.image.clone()),
-            extra_lints: false,
-            ..Default::default()
-        };
-
-        let build_result = execute(args)?;
-
-        // 4. Grab the code hash from the built contract and compare it with the reference
-        //    one.
+        // 3. Compare the code hash of the built contract with the reference one.
         let reference_code_hash = metadata.source.hash;
-        let built_contract_path = if let Some(m) = build_result.metadata_result {
-            m
-        } else {
-            // Since we're building the contract ourselves this should always be
-            // populated, but we'll bail out here just in case.
-            anyhow::bail!(
-                "\nThe metadata for the workspace contract does not contain a Wasm binary,\n\
-                therefore we are unable to verify the contract."
-                .to_string()
-                .bright_yellow()
-            )
-        };
-
-        let target_bundle = &built_contract_path.dest_bundle;
-
-        let file = File::open(target_bundle.clone()).context(format!(
-            "Failed to open contract bundle {}",
-            target_bundle.display()
-        ))?;
-        let built_contract: ContractMetadata =
-            serde_json::from_reader(file).context(format!(
-                "Failed to deserialize contract bundle {}",
-                target_bundle.display()
-            ))?;
-
-        let target_code_hash = built_contract.source.hash;
-
         if reference_code_hash != target_code_hash {
             verbose_eprintln!(
                 verbosity,
@@ -277,6 +209,24 @@ impl VerifyCommand {
                 &reference_code_hash,
                 &target_code_hash
             );
+            let code_diff = match (&metadata.source.wasm, &target_metadata.source.wasm) {
+                (Some(reference), Some(target)) => code_diff(&reference.0, &target.0),
+                _ => None,
+            };
+            if self.output_json {
+                return Ok(VerificationResult {
+                    is_verified: false,
+                    image: metadata.image,
+                    contract: target_bundle.display().to_string(),
+                    reference_contract: path.display().to_string(),
+                    code_hash: Some(target_code_hash),
+                    url: None,
+                    code_diff,
+                    output_json: self.output_json,
+                    verbosity,
+                })
+            }
+            print_code_diff(verbosity, code_diff.as_ref());
             anyhow::bail!(format!(
                 "\nFailed to verify the authenticity of {} contract against the workspace \n\
                 found at {}.",
@@ -290,10 +240,430 @@ impl VerifyCommand {
             image: metadata.image,
             contract: target_bundle.display().to_string(),
             reference_contract: path.display().to_string(),
+            code_hash: Some(target_code_hash),
+            url: None,
+            code_diff: None,
             output_json: self.output_json,
             verbosity,
         })
     }
+
+    /// Verify the code of a contract deployed at `address`.
+    ///
+    /// If a reference `.contract` bundle is supplied, its `BuildInfo` is used to
+    /// reproduce the build, otherwise the workspace is built in release mode.
+    fn verify_deployed(
+        &self,
+        manifest_path: ManifestPath,
+        verbosity: Verbosity,
+        address: &str,
+    ) -> Result<VerificationResult> {
+        // 1. Fetch the code of the deployed contract.
+        let url = self.chain_cli_opts.chain().url();
+        verbose_eprintln!(
+            verbosity,
+            " {} {}",
+            "[==]".bold(),
+            format!("Fetching the code of contract {address} from {url}")
+                .bright_cyan()
+                .bold()
+        );
+        let onchain_code = tokio::runtime::Runtime::new()?
+            .block_on(async {
+                call_with_config!(
+                    self,
+                    fetch_deployed_code,
+                    self.chain_cli_opts.chain().config()
+                )
+            })
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        let onchain_code_hash = CodeHash(code_hash(&onchain_code));
+
+        // 2. Build the workspace, using the `BuildInfo` of the reference metadata if
+        //    present.
+        let (contract, built_code, image) = match &self.contract {
+            Some(path) => {
+                let metadata = load_metadata(path)?;
+                anyhow::ensure!(
+                    metadata.source.hash == onchain_code_hash,
+                    format!(
+                        "\nThe code hash of the reference contract {} does not match the \
+                        code hash of the contract deployed at {}.\n Expected {}, found {}",
+                        format!("`{}`", path.display()).bright_white(),
+                        format!("`{address}`").bright_white(),
+                        format!("{}", onchain_code_hash).bright_white(),
+                        format!("{}", metadata.source.hash).bright_white()
+                    )
+                    .bright_yellow()
+                );
+                let (bundle, target_metadata) =
+                    build_from_metadata(&manifest_path, verbosity, &metadata, path)?;
+                let code = target_metadata
+                    .source
+                    .wasm
+                    .as_ref()
+                    .map(|wasm| wasm.0.clone())
+                    .context(
+                        "The built contract bundle does not contain a Wasm binary",
+                    )?;
+                (bundle, code, metadata.image)
+            }
+            None => {
+                let (wasm, code) = build_release_code(&manifest_path, verbosity)?;
+                (wasm, code, None)
+            }
+        };
+        let target_code_hash = CodeHash(code_hash(&built_code));
+
+        // 3. Compare the code hash of the built contract with the on-chain one.
+        if target_code_hash != onchain_code_hash {
+            verbose_eprintln!(
+                verbosity,
+                "Expected Code Hash: '{}'\n\nGot Code Hash: `{}`",
+                &onchain_code_hash,
+                &target_code_hash
+            );
+            let code_diff = code_diff(&onchain_code, &built_code);
+            if self.output_json {
+                return Ok(VerificationResult {
+                    is_verified: false,
+                    image,
+                    contract: contract.display().to_string(),
+                    reference_contract: address.to_string(),
+                    code_hash: Some(target_code_hash),
+                    url: Some(url_to_string(&url)),
+                    code_diff,
+                    output_json: self.output_json,
+                    verbosity,
+                })
+            }
+            print_code_diff(verbosity, code_diff.as_ref());
+            anyhow::bail!(format!(
+                "\nFailed to verify the authenticity of the contract deployed at {} \
+                against the workspace \nfound at {}.",
+                format!("`{address}`").bright_white(),
+                format!("{:?}", manifest_path.as_ref()).bright_white()
+            )
+            .bright_red());
+        }
+
+        Ok(VerificationResult {
+            is_verified: true,
+            image,
+            contract: contract.display().to_string(),
+            reference_contract: address.to_string(),
+            code_hash: Some(onchain_code_hash),
+            url: Some(url_to_string(&url)),
+            code_diff: None,
+            output_json: self.output_json,
+            verbosity,
+        })
+    }
+
+    /// Fetch the Wasm code of the contract at `--address` from the chain.
+    async fn fetch_deployed_code<C: Config + Environment>(
+        &self,
+    ) -> Result<Vec<u8>, ErrorVariant>
+    where
+        <C as Config>::AccountId: Display + IntoVisitor + AsRef<[u8]> + FromStr,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        <C as Config>::Hash: IntoVisitor + Display,
+        <C as Environment>::Balance: Serialize + IntoVisitor,
+    {
+        let address = self
+            .address
+            .as_ref()
+            .map(|a| parse_account(a))
+            .transpose()?
+            .expect("Address argument shall be present");
+
+        let rpc_cli =
+            RpcClient::from_url(url_to_string(&self.chain_cli_opts.chain().url()))
+                .await?;
+        let client = OnlineClient::<C>::from_rpc_client(rpc_cli.clone()).await?;
+        let rpc = LegacyRpcMethods::<C>::new(rpc_cli);
+
+        let contract_info = fetch_contract_info::<C, C>(&address, &rpc, &client).await?;
+        let code = fetch_wasm_code(&client, &rpc, contract_info.code_hash()).await?;
+        Ok(code)
+    }
+}
+
+/// Read the reference `.contract` bundle at `path`.
+fn load_metadata(path: &Path) -> Result<ContractMetadata> {
+    let file = File::open(path)
+        .context(format!("Failed to open contract bundle {}", path.display()))?;
+
+    serde_json::from_reader(&file).context(format!(
+        "Failed to deserialize contract bundle {}",
+        path.display()
+    ))
+}
+
+/// Build the workspace with the `BuildInfo` found in the reference `metadata`.
+///
+/// Returns the path to the resulting bundle and its metadata.
+fn build_from_metadata(
+    manifest_path: &ManifestPath,
+    verbosity: Verbosity,
+    metadata: &ContractMetadata,
+    path: &Path,
+) -> Result<(PathBuf, ContractMetadata)> {
+    let build_info = if let Some(info) = metadata.source.build_info.clone() {
+        info
+    } else {
+        anyhow::bail!(
+            "\nThe metadata does not contain any build information which can be used to \
+            verify a contract."
+                .to_string()
+                .bright_yellow()
+        )
+    };
+
+    let build_info: BuildInfo =
+        serde_json::from_value(build_info.into()).context(format!(
+            "Failed to deserialize the build info from {}",
+            path.display()
+        ))?;
+
+    tracing::debug!(
+        "Parsed the following build info from the metadata: {:?}",
+        &build_info,
+    );
+
+    let build_mode = if metadata.image.is_some() {
+        BuildMode::Verifiable
+    } else {
+        build_info.build_mode
+    };
+
+    warn_build_info_differences(
+        manifest_path,
+        verbosity,
+        &build_info,
+        build_mode,
+        metadata.image.as_deref(),
+    )?;
+
+    // Check that the build info from the metadata matches our current setup.
+    // if the build mode is `Verifiable` we skip
+    if build_mode != BuildMode::Verifiable {
+        let expected_rust_toolchain = build_info.rust_toolchain;
+        let rust_toolchain = contract_build::util::rust_toolchain()
+            .expect("`rustc` always has a version associated with it.");
+
+        let rustc_matches = rust_toolchain == expected_rust_toolchain;
+        let mismatched_rustc = format!(
+        "\nYou are trying to `verify` a contract using the `{rust_toolchain}` toolchain.\n\
+         However, the original contract was built using `{expected_rust_toolchain}`. Please\n\
+         install the correct toolchain (`rustup install {expected_rust_toolchain}`) and\n\
+         re-run the `verify` command.",);
+        anyhow::ensure!(rustc_matches, mismatched_rustc.bright_yellow());
+
+        let expected_cargo_contract_version = build_info.cargo_contract_version;
+        let cargo_contract_version = semver::Version::parse(VERSION)?;
+
+        // Note, assuming both versions of `cargo-contract` were installed with the
+        // same lockfile (e.g `--locked`) then the versions of `wasm-opt`
+        // should also match.
+        let cargo_contract_matches =
+            cargo_contract_version == expected_cargo_contract_version;
+        let mismatched_cargo_contract = format!(
+            "\nYou are trying to `verify` a contract using `cargo-contract` version \
+        `{cargo_contract_version}`.\n\
+         However, the original contract was built using `cargo-contract` version \
+         `{expected_cargo_contract_version}`.\n\
+         Please install the matching version and re-run the `verify` command.",
+        );
+        anyhow::ensure!(
+            cargo_contract_matches,
+            mismatched_cargo_contract.bright_yellow()
+        );
+    }
+
+    // A verifiable build which recorded a clean git commit is reproduced from a clean
+    // checkout of that commit, so uncommitted changes of the workspace do not matter.
+    let from_git = match (&build_mode, &build_info.git) {
+        (BuildMode::Verifiable, Some(git)) if git.dirty => {
+            verbose_eprintln!(
+                verbosity,
+                "{} {}",
+                "warning:".yellow().bold(),
+                format!(
+                    "The reference contract was built from the git commit {} with \
+                    uncommitted changes, verifying against the working directory instead.",
+                    git.commit
+                )
+                .bold()
+            );
+            None
+        }
+        (BuildMode::Verifiable, Some(git)) => Some(git.commit.clone()),
+        _ => None,
+    };
+
+    // Call `cargo contract build` with the `BuildInfo` from the metadata.
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        verbosity,
+        build_mode,
+        build_artifact: BuildArtifacts::All,
+        optimization_passes: Some(build_info.wasm_opt_settings.optimization_passes),
+        keep_debug_symbols: build_info.wasm_opt_settings.keep_debug_symbols,
+        image: ImageVariant::from(metadata.image.clone()),
+        from_git,
+        extra_lints: false,
+        ..Default::default()
+    };
+
+    let build_result = execute(args)?;
+
+    // Grab the code hash from the built contract.
+    let built_contract_path = if let Some(m) = build_result.metadata_result {
+        m
+    } else {
+        // Since we're building the contract ourselves this should always be
+        // populated, but we'll bail out here just in case.
+        anyhow::bail!(
+            "\nThe metadata for the workspace contract does not contain a Wasm binary,\n\
+            therefore we are unable to verify the contract."
+                .to_string()
+                .bright_yellow()
+        )
+    };
+
+    let target_bundle = built_contract_path.dest_bundle;
+    let built_contract = load_metadata(&target_bundle)?;
+
+    Ok((target_bundle, built_contract))
+}
+
+/// Compare the reference `BuildInfo` and `image` with the local environment and
+/// print a warning for every field which differs.
+///
+/// A verifiable build runs in the docker `image`, so the local toolchain and
+/// `cargo-contract` version only matter for the other build modes.
+fn warn_build_info_differences(
+    manifest_path: &ManifestPath,
+    verbosity: Verbosity,
+    build_info: &BuildInfo,
+    build_mode: BuildMode,
+    image: Option<&str>,
+) -> Result<()> {
+    let optimization_passes = Manifest::new(manifest_path.clone())?
+        .profile_optimization_passes()
+        .unwrap_or_default();
+
+    let mut differences = vec![
+        (
+            "wasm-opt optimization passes",
+            build_info.wasm_opt_settings.optimization_passes.to_string(),
+            optimization_passes.to_string(),
+        ),
+        (
+            "wasm-opt keep debug symbols",
+            build_info.wasm_opt_settings.keep_debug_symbols.to_string(),
+            false.to_string(),
+        ),
+    ];
+    if build_mode == BuildMode::Verifiable {
+        if let Some(image) = image {
+            differences.push((
+                "docker image",
+                image.to_string(),
+                ImageVariant::Default.image_name(),
+            ));
+        }
+    } else {
+        differences.push((
+            "rust toolchain",
+            build_info.rust_toolchain.clone(),
+            contract_build::util::rust_toolchain()?,
+        ));
+        differences.push((
+            "cargo-contract version",
+            build_info.cargo_contract_version.to_string(),
+            VERSION.to_string(),
+        ));
+    }
+
+    for (field, reference, local) in differences {
+        if reference != local {
+            verbose_eprintln!(
+                verbosity,
+                "{} {}",
+                "warning:".yellow().bold(),
+                format!(
+                    "The {field} of the reference contract `{reference}` differs from \
+                    the local one `{local}`."
+                )
+                .bold()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Returns the section level differences between the reference and the built code.
+fn code_diff(reference: &[u8], built: &[u8]) -> Option<ModuleDiff> {
+    let diff = Module::new(reference).and_then(|reference| {
+        let built = Module::new(built)?;
+        diff_modules(&reference, &built)
+    });
+    diff.map_err(|err| tracing::debug!("Failed to diff the Wasm code: {err:?}"))
+        .ok()
+}
+
+/// Print the section level differences between the reference and the built code.
+fn print_code_diff(verbosity: Verbosity, diff: Option<&ModuleDiff>) {
+    if let Some(diff) = diff {
+        verbose_eprintln!(
+            verbosity,
+            "\n{}\n{}",
+            "Differences between the reference and the built code:"
+                .bright_yellow()
+                .bold(),
+            diff
+        );
+    }
+}
+
+/// Build the workspace code in release mode with the default optimization settings.
+///
+/// Returns the path to the resulting Wasm binary and its code.
+fn build_release_code(
+    manifest_path: &ManifestPath,
+    verbosity: Verbosity,
+) -> Result<(PathBuf, Vec<u8>)> {
+    let args = ExecuteArgs {
+        manifest_path: manifest_path.clone(),
+        verbosity,
+        optimization_passes: Some(OptimizationPasses::Z),
+        build_mode: BuildMode::Release,
+        build_artifact: BuildArtifacts::CodeOnly,
+        extra_lints: false,
+        ..Default::default()
+    };
+
+    let build_result = execute(args)?;
+
+    let built_wasm_path = if let Some(m) = build_result.dest_wasm {
+        m
+    } else {
+        // Since we're building the contract ourselves this should always be
+        // populated, but we'll bail out here just in case.
+        anyhow::bail!("\nThe workspace contract does not contain a Wasm binary,\n\
+            therefore we are unable to verify the contract."
+            .to_string()
+            .bright_yellow())
+    };
+
+    let target_buffer = std::fs::read(&built_wasm_path).context(format!(
+        "Failed to read contract binary {}",
+        built_wasm_path.display()
+    ))?;
+
+    Ok((built_wasm_path, target_buffer))
 }
 
 /// The result of verification process
@@ -303,6 +673,13 @@ pub struct VerificationResult {
     pub image: Option<String>,
     pub contract: String,
     pub reference_contract: String,
+    /// The code hash which was verified.
+    pub code_hash: Option<CodeHash>,
+    /// The url of the node the reference code was fetched from, if any.
+    pub url: Option<String>,
+    /// The differences between the reference and the built code, if they differ.
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub code_diff: Option<ModuleDiff>,
     #[serde(skip_serializing, skip_deserializing)]
     pub output_json: bool,
     #[serde(skip_serializing, skip_deserializing)]
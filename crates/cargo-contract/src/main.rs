@@ -22,15 +22,23 @@ use self::cmd::{
     BuildCommand,
     CallCommand,
     CheckCommand,
+    ConvertBundleCommand,
     DecodeCommand,
+    DiffCodeCommand,
+    DockerCommand,
+    DoctorCommand,
     ErrorVariant,
     GenerateSchemaCommand,
     InfoCommand,
+    InspectCommand,
     InstantiateCommand,
+    MigrateCommand,
     RemoveCommand,
     RpcCommand,
+    SignBundleCommand,
     StorageCommand,
     UploadCommand,
+    VerifyBundleCommand,
     VerifyCommand,
     VerifySchemaCommand,
 };
@@ -108,10 +116,18 @@ enum Command {
     #[clap(name = "new")]
     New {
         /// The name of the newly created smart contract
-        name: String,
+        #[clap(required_unless_present = "list_templates")]
+        name: Option<String>,
         /// The optional target directory for the contract project
         #[clap(short, long, value_parser)]
         target_dir: Option<PathBuf>,
+        /// The template to create the project from: the name of a built-in template,
+        /// the URL of a git repository or the path to a local directory
+        #[clap(long, conflicts_with = "list_templates")]
+        template: Option<String>,
+        /// List the built-in templates
+        #[clap(long)]
+        list_templates: bool,
     },
     /// Compiles the contract, generates metadata, bundles both together in a
     /// `<name>.contract` file
@@ -142,6 +158,13 @@ enum Command {
     /// Display information about a contract
     #[clap(name = "info")]
     Info(InfoCommand),
+    /// Inspect the code of a contract: its language, imports, exports and sections
+    #[clap(name = "inspect")]
+    Inspect(InspectCommand),
+    /// Compare the code of two contracts: their imports, exports, memory, functions
+    /// and data
+    #[clap(name = "diff-code")]
+    DiffCode(DiffCodeCommand),
     /// Inspect the on-chain storage of a contract.
     #[clap(name = "storage")]
     Storage(StorageCommand),
@@ -149,6 +172,15 @@ enum Command {
     /// workspace.
     #[clap(name = "verify")]
     Verify(VerifyCommand),
+    /// Sign a contract bundle to prove its origin.
+    #[clap(name = "sign-bundle")]
+    SignBundle(SignBundleCommand),
+    /// Verify the signatures of a contract bundle.
+    #[clap(name = "verify-bundle")]
+    VerifyBundle(VerifyBundleCommand),
+    /// Convert a contract bundle between the JSON and the compact format.
+    #[clap(name = "convert-bundle")]
+    ConvertBundle(ConvertBundleCommand),
     /// Generates schema from the current metadata specification.
     #[clap(name = "generate-schema")]
     GenerateSchema(GenerateSchemaCommand),
@@ -158,6 +190,15 @@ enum Command {
     /// Make a raw RPC call.
     #[clap(name = "rpc")]
     Rpc(RpcCommand),
+    /// Manage the docker containers and images used by verifiable builds.
+    #[clap(name = "docker", subcommand)]
+    Docker(DockerCommand),
+    /// Diagnose the environment for building contracts and suggest fixes.
+    #[clap(name = "doctor")]
+    Doctor(DoctorCommand),
+    /// Migrate the contract to a newer major version of ink!.
+    #[clap(name = "migrate")]
+    Migrate(MigrateCommand),
 }
 
 fn main() {
@@ -177,11 +218,34 @@ fn main() {
 fn exec(cmd: Command) -> Result<()> {
     let runtime = Runtime::new().expect("Failed to create Tokio runtime");
     match &cmd {
-        Command::New { name, target_dir } => {
-            contract_build::new_contract_project(name, target_dir.as_ref())?;
+        Command::New {
+            name,
+            target_dir,
+            template,
+            list_templates,
+        } => {
+            if *list_templates {
+                for template in contract_build::templates() {
+                    println!("{:<20}{}", template.name.bold(), template.description);
+                }
+                return Ok(())
+            }
+            let name = name.as_deref().expect("name is required by clap");
+            let workspace = contract_build::new_contract_project_with_template(
+                name,
+                target_dir.as_ref(),
+                template.as_deref(),
+            )?;
             println!("Created contract {name}");
+            if let Some(workspace) = workspace {
+                println!(
+                    "Added {name} to the workspace at {}",
+                    workspace.as_ref().display()
+                );
+            }
             Ok(())
         }
+        Command::Build(build) if build.is_watch() => build.watch().map_err(format_err),
         Command::Build(build) => {
             let result = build.exec().map_err(format_err)?;
 
@@ -192,6 +256,7 @@ fn exec(cmd: Command) -> Result<()> {
             }
             Ok(())
         }
+        Command::Check(check) if check.is_watch() => check.watch().map_err(format_err),
         Command::Check(check) => {
             let res = check.exec().map_err(format_err)?;
             assert!(
@@ -236,6 +301,12 @@ fn exec(cmd: Command) -> Result<()> {
         Command::Info(info) => {
             runtime.block_on(async { info.handle().await.map_err(format_err) })
         }
+        Command::Inspect(inspect) => {
+            runtime.block_on(async { inspect.handle().await.map_err(format_err) })
+        }
+        Command::DiffCode(diff_code) => {
+            runtime.block_on(async { diff_code.handle().await.map_err(format_err) })
+        }
         Command::Storage(storage) => {
             runtime.block_on(async { storage.handle().await.map_err(format_err) })
         }
@@ -243,12 +314,30 @@ fn exec(cmd: Command) -> Result<()> {
             let result = verify.run().map_err(format_err)?;
 
             if result.output_json {
-                println!("{}", result.serialize_json()?)
+                println!("{}", result.serialize_json()?);
+                if !result.is_verified {
+                    return Err(anyhow!(
+                        "Failed to verify the contract against {}",
+                        result.reference_contract
+                    ))
+                }
             } else if result.verbosity.is_verbose() {
                 println!("{}", result.display())
             }
             Ok(())
         }
+        Command::SignBundle(sign) => sign.run().map_err(format_err),
+        Command::VerifyBundle(verify) => {
+            let result = verify.run().map_err(format_err)?;
+
+            if result.output_json {
+                println!("{}", result.serialize_json()?)
+            } else {
+                println!("{}", result.display())
+            }
+            Ok(())
+        }
+        Command::ConvertBundle(convert) => convert.run().map_err(format_err),
         Command::GenerateSchema(generate) => {
             let result = generate.run().map_err(format_err)?;
             println!("{}", result);
@@ -267,6 +356,30 @@ fn exec(cmd: Command) -> Result<()> {
         Command::Rpc(rpc) => {
             runtime.block_on(async { rpc.run().await.map_err(format_err) })
         }
+        Command::Docker(docker) => docker.run().map_err(format_err),
+        Command::Doctor(doctor) => {
+            let result = doctor.run().map_err(format_err)?;
+
+            if result.output_json {
+                println!("{}", result.serialize_json()?)
+            } else {
+                print!("{}", result.display())
+            }
+            if result.has_errors() {
+                anyhow::bail!("{}", "Found problems in the environment".bright_red())
+            }
+            Ok(())
+        }
+        Command::Migrate(migrate) => {
+            let result = migrate.run().map_err(format_err)?;
+
+            if result.output_json {
+                println!("{}", result.serialize_json()?)
+            } else {
+                print!("{}", result.display())
+            }
+            Ok(())
+        }
     }
 }
 
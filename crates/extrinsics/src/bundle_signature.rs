@@ -0,0 +1,240 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use contract_metadata::{
+    BundleSignature,
+    ContractMetadata,
+    SignatureScheme,
+};
+use sp_core::{
+    ed25519,
+    sr25519,
+    Pair,
+};
+use std::path::Path;
+
+/// Signs the contract metadata with the key pair of the secret URI.
+///
+/// An earlier signature by the same key is replaced.
+pub fn sign_metadata(
+    metadata: &mut ContractMetadata,
+    scheme: SignatureScheme,
+    suri: &str,
+) -> Result<BundleSignature> {
+    let payload = metadata.signing_payload()?;
+    let suri_err = |err| anyhow!("Failed to parse the secret URI: {err:?}");
+    let (public_key, signature) = match scheme {
+        SignatureScheme::Sr25519 => {
+            let pair = sr25519::Pair::from_string(suri, None).map_err(suri_err)?;
+            (pair.public().0.to_vec(), pair.sign(&payload).0.to_vec())
+        }
+        SignatureScheme::Ed25519 => {
+            let pair = ed25519::Pair::from_string(suri, None).map_err(suri_err)?;
+            (pair.public().0.to_vec(), pair.sign(&payload).0.to_vec())
+        }
+    };
+    let signature = BundleSignature {
+        scheme,
+        public_key,
+        signature,
+    };
+    metadata.signatures.retain(|signed| {
+        signed.scheme != signature.scheme || signed.public_key != signature.public_key
+    });
+    metadata.signatures.push(signature.clone());
+    Ok(signature)
+}
+
+/// Checks whether the signature of the contract metadata is valid.
+pub fn verify_signature(
+    metadata: &ContractMetadata,
+    signature: &BundleSignature,
+) -> Result<bool> {
+    let payload = metadata.signing_payload()?;
+    let (Ok(public_key), Ok(raw_signature)) = (
+        <[u8; 32]>::try_from(&signature.public_key[..]),
+        <[u8; 64]>::try_from(&signature.signature[..]),
+    ) else {
+        return Ok(false)
+    };
+    let valid = match signature.scheme {
+        SignatureScheme::Sr25519 => {
+            sr25519::Pair::verify(
+                &sr25519::Signature(raw_signature),
+                &payload,
+                &sr25519::Public(public_key),
+            )
+        }
+        SignatureScheme::Ed25519 => {
+            ed25519::Pair::verify(
+                &ed25519::Signature(raw_signature),
+                &payload,
+                &ed25519::Public(public_key),
+            )
+        }
+    };
+    Ok(valid)
+}
+
+/// The public keys whose signatures of contract bundles are trusted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrustedKeys {
+    keys: Vec<(SignatureScheme, Vec<u8>)>,
+}
+
+impl TrustedKeys {
+    /// Reads the trusted keys from a file, see [`TrustedKeys::parse`].
+    pub fn load(path: &Path) -> Result<Self> {
+        let keys = std::fs::read_to_string(path).context(format!(
+            "Failed to read the trusted keys from {}",
+            path.display()
+        ))?;
+        Self::parse(&keys)
+    }
+
+    /// Parses the trusted keys, with the signature scheme and the hex encoded public
+    /// key per line, e.g. `sr25519 0xd435...`. Empty lines and lines starting with `#`
+    /// are ignored.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut trusted = Self::default();
+        for line in input.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            let (scheme, public_key) =
+                line.split_once(char::is_whitespace).ok_or_else(|| {
+                    anyhow!("Expected a scheme and a public key in '{line}'")
+                })?;
+            let public_key = public_key.trim();
+            let public_key = hex::decode(public_key.trim_start_matches("0x"))
+                .context(format!("Invalid public key '{public_key}'"))?;
+            trusted.insert(scheme.parse()?, public_key);
+        }
+        Ok(trusted)
+    }
+
+    /// Trusts the public key of the signature scheme.
+    pub fn insert(&mut self, scheme: SignatureScheme, public_key: Vec<u8>) {
+        self.keys.push((scheme, public_key));
+    }
+
+    /// Returns `true` if no key is trusted.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns `true` if the signature was made by a trusted key.
+    pub fn is_trusted(&self, signature: &BundleSignature) -> bool {
+        self.keys.iter().any(|(scheme, public_key)| {
+            *scheme == signature.scheme && *public_key == signature.public_key
+        })
+    }
+
+    /// Returns the first valid signature of the contract metadata by a trusted key.
+    ///
+    /// ## Errors
+    /// - The metadata has no valid signature by any of the trusted keys.
+    pub fn check<'a>(
+        &self,
+        metadata: &'a ContractMetadata,
+    ) -> Result<&'a BundleSignature> {
+        for signature in &metadata.signatures {
+            if self.is_trusted(signature) && verify_signature(metadata, signature)? {
+                return Ok(signature)
+            }
+        }
+        anyhow::bail!(
+            "The contract bundle has no valid signature by a trusted key, found {} \
+            signature(s)",
+            metadata.signatures.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use contract_metadata::{
+        CodeHash,
+        Compiler,
+        Contract,
+        Language,
+        Source,
+        SourceCompiler,
+        SourceLanguage,
+        SourceWasm,
+    };
+    use semver::Version;
+
+    fn metadata() -> ContractMetadata {
+        let source = Source::new(
+            Some(SourceWasm::new(vec![0, 1, 2])),
+            CodeHash([0; 32]),
+            SourceLanguage::new(Language::Ink, Version::new(5, 0, 0)),
+            SourceCompiler::new(Compiler::RustC, Version::new(1, 78, 0)),
+            None,
+        );
+        let contract = Contract::builder()
+            .name("flipper")
+            .version(Version::new(5, 0, 0))
+            .authors(vec!["Use Ink <admin@use.ink>"])
+            .build()
+            .unwrap();
+        ContractMetadata::new(source, contract, None, None, Default::default())
+    }
+
+    #[test]
+    fn signs_and_verifies_bundles() {
+        let mut metadata = metadata();
+        let alice = sign_metadata(&mut metadata, SignatureScheme::Sr25519, "//Alice")
+            .expect("signing failed");
+        sign_metadata(&mut metadata, SignatureScheme::Ed25519, "//Bob").unwrap();
+        // signing again replaces the signature of the same key
+        sign_metadata(&mut metadata, SignatureScheme::Sr25519, "//Alice").unwrap();
+        assert_eq!(metadata.signatures.len(), 2);
+        for signature in &metadata.signatures {
+            assert!(verify_signature(&metadata, signature).unwrap());
+        }
+
+        let trusted = TrustedKeys::parse(&format!(
+            "# CI\nsr25519 0x{}\n",
+            hex::encode(&alice.public_key)
+        ))
+        .unwrap();
+        assert_eq!(
+            trusted.check(&metadata).unwrap().public_key,
+            alice.public_key
+        );
+
+        let mut tampered = metadata.clone();
+        tampered.contract.name = "flopper".to_string();
+        assert!(!verify_signature(&tampered, &alice).unwrap());
+        assert!(trusted.check(&tampered).is_err());
+        // a trusted key of another scheme does not match
+        assert!(TrustedKeys::parse(&format!(
+            "ed25519 {}",
+            hex::encode(&alice.public_key)
+        ))
+        .unwrap()
+        .check(&metadata)
+        .is_err());
+    }
+}
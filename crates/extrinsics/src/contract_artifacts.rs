@@ -15,16 +15,23 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::{
+    ContractCode,
     ContractMessageTranscoder,
     ContractMetadata,
     CrateMetadata,
-    WasmCode,
+    TrustedKeys,
 };
 use anyhow::{
     Context,
     Result,
 };
 use colored::Colorize;
+use contract_build::Target;
+use contract_metadata::{
+    BundleFormat,
+    BundleSignature,
+    COMPACT_BUNDLE_EXTENSION,
+};
 use ink_metadata::InkProject;
 use std::path::{
     Path,
@@ -40,8 +47,8 @@ pub struct ContractArtifacts {
     metadata_path: PathBuf,
     /// The deserialized contract metadata if the expected metadata file exists.
     metadata: Option<ContractMetadata>,
-    /// The Wasm code of the contract if available.
-    pub code: Option<WasmCode>,
+    /// The code of the contract if available.
+    pub code: Option<ContractCode>,
 }
 
 impl ContractArtifacts {
@@ -52,13 +59,16 @@ impl ContractArtifacts {
     ) -> Result<ContractArtifacts> {
         let artifact_path = match (manifest_path, file) {
             (manifest_path, None) => {
-                let crate_metadata = CrateMetadata::from_manifest_path(
-                    manifest_path,
-                    contract_build::Target::Wasm,
-                )?;
+                let crate_metadata =
+                    CrateMetadata::from_manifest_path(manifest_path, Target::Wasm)?;
 
+                let compact_bundle_path = crate_metadata
+                    .contract_bundle_path()
+                    .with_extension(COMPACT_BUNDLE_EXTENSION);
                 if crate_metadata.contract_bundle_path().exists() {
                     crate_metadata.contract_bundle_path()
+                } else if compact_bundle_path.exists() {
+                    compact_bundle_path
                 } else if crate_metadata.metadata_path().exists() {
                     crate_metadata.metadata_path()
                 } else {
@@ -77,21 +87,32 @@ impl ContractArtifacts {
     }
     /// Given a contract artifact path, load the contract code and metadata where
     /// possible.
+    ///
+    /// Compact bundles are detected by their content, whatever their extension.
     fn from_artifact_path(path: &Path) -> Result<Self> {
         tracing::debug!("Loading contracts artifacts from `{}`", path.display());
+        let extension =
+            if path.is_file() && BundleFormat::detect(path)? == BundleFormat::Compact {
+                Some(COMPACT_BUNDLE_EXTENSION)
+            } else {
+                path.extension().and_then(|ext| ext.to_str())
+            };
         let (metadata_path, metadata, code) =
-            match path.extension().and_then(|ext| ext.to_str()) {
-                Some("contract") | Some("json") => {
+            match extension {
+                Some("contract" | "json" | COMPACT_BUNDLE_EXTENSION) => {
                     let metadata = ContractMetadata::load(path)?;
-                    let code = metadata.clone().source.wasm.map(|wasm| WasmCode(wasm.0));
+                    let code = metadata.source.code().map(|code| {
+                        ContractCode::new(metadata.source.target.into(), code.to_vec())
+                    });
                     (PathBuf::from(path), Some(metadata), code)
                 }
-                Some("wasm") => {
+                Some(ext @ ("wasm" | "riscv")) => {
+                    let target = if ext == "riscv" { Target::RiscV } else { Target::Wasm };
                     let file_name = path.file_stem()
-                        .context("WASM bundle file has unreadable name")?
+                        .context("Contract code file has unreadable name")?
                         .to_str()
                         .context("Error parsing filename string")?;
-                    let code = Some(WasmCode(std::fs::read(path)?));
+                    let code = Some(ContractCode::new(target, std::fs::read(path)?));
                     let dir = path.parent().map_or_else(PathBuf::new, PathBuf::from);
                     let metadata_path = dir.join(format!("{file_name}.json"));
                     if !metadata_path.exists() {
@@ -102,11 +123,11 @@ impl ContractArtifacts {
                     }
                 }
                 Some(ext) => anyhow::bail!(
-                    "Invalid artifact extension {ext}, expected `.contract`, `.json` or `.wasm`"
+                    "Invalid artifact extension {ext}, expected `.contract`, `.{COMPACT_BUNDLE_EXTENSION}`, `.json`, `.wasm` or `.riscv`"
                 ),
                 None => {
                     anyhow::bail!(
-                        "Artifact path has no extension, expected `.contract`, `.json`, or `.wasm`"
+                        "Artifact path has no extension, expected `.contract`, `.{COMPACT_BUNDLE_EXTENSION}`, `.json`, `.wasm` or `.riscv`"
                     )
                 }
             };
@@ -157,8 +178,12 @@ impl ContractArtifacts {
         Ok(ink_project)
     }
 
-    /// Get the code hash from the contract metadata.
+    /// Get the code hash of the contract code, or from the contract metadata if the code
+    /// is not available.
     pub fn code_hash(&self) -> Result<[u8; 32]> {
+        if let Some(code) = &self.code {
+            return Ok(code.code_hash())
+        }
         let metadata = self.metadata()?;
         Ok(metadata.source.hash.0)
     }
@@ -179,4 +204,104 @@ impl ContractArtifacts {
             Err(_) => false,
         }
     }
+
+    /// Requires a valid signature of the contract metadata by one of the trusted keys,
+    /// which covers the contract code by its code hash.
+    ///
+    /// ## Errors
+    /// - No contract metadata could be found.
+    /// - The metadata has no valid signature by a trusted key.
+    /// - The code hash of the contract code differs from the signed code hash.
+    pub fn require_trusted_signature(
+        &self,
+        trusted_keys: &TrustedKeys,
+    ) -> Result<BundleSignature> {
+        let metadata = self.metadata()?;
+        let signature = trusted_keys.check(&metadata).context(format!(
+            "Untrusted contract artifacts {}",
+            self.artifacts_path.display()
+        ))?;
+        if let Some(code) = &self.code {
+            let signed_hash = metadata.source.hash_for(code.target().into());
+            if signed_hash.map(|hash| hash.0) != Some(code.code_hash()) {
+                anyhow::bail!(
+                    "The code hash of the contract code {} does not match the code hash \
+                    signed in the contract metadata {}",
+                    self.artifacts_path.display(),
+                    self.metadata_path.display()
+                )
+            }
+        }
+        Ok(signature.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_riscv_code() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flipper.riscv");
+        std::fs::write(&path, b"PVM\0").unwrap();
+
+        let artifacts = ContractArtifacts::from_artifact_path(&path).unwrap();
+        let code = artifacts.code.as_ref().unwrap();
+
+        assert_eq!(code.target(), Target::RiscV);
+        assert_eq!(code.as_bytes(), b"PVM\0");
+        assert_eq!(
+            artifacts.code_hash().unwrap(),
+            contract_build::code_hash(b"PVM\0")
+        );
+        assert!(artifacts.metadata().is_err());
+    }
+
+    #[test]
+    fn rejects_code_swapped_next_to_signed_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let code = b"\0asm\x01\0\0\0".to_vec();
+        let mut metadata: ContractMetadata = serde_json::from_value(serde_json::json!({
+            "source": {
+                "hash": format!("0x{}", hex::encode(contract_build::code_hash(&code))),
+                "language": "ink! 5.0.0",
+                "compiler": "rustc 1.78.0",
+            },
+            "contract": {
+                "name": "flipper",
+                "version": "5.0.0",
+                "authors": [],
+            },
+        }))
+        .unwrap();
+        let signature = crate::sign_metadata(
+            &mut metadata,
+            contract_metadata::SignatureScheme::Sr25519,
+            "//Alice",
+        )
+        .unwrap();
+        let trusted_keys = TrustedKeys::parse(&format!(
+            "sr25519 0x{}",
+            hex::encode(&signature.public_key)
+        ))
+        .unwrap();
+        std::fs::write(
+            dir.path().join("flipper.json"),
+            serde_json::to_string(&metadata).unwrap(),
+        )
+        .unwrap();
+        let path = dir.path().join("flipper.wasm");
+
+        std::fs::write(&path, &code).unwrap();
+        let artifacts = ContractArtifacts::from_artifact_path(&path).unwrap();
+        assert!(artifacts.require_trusted_signature(&trusted_keys).is_ok());
+
+        std::fs::write(&path, b"\0asm\x01\0\0\0\0").unwrap();
+        let artifacts = ContractArtifacts::from_artifact_path(&path).unwrap();
+        let err = artifacts
+            .require_trusted_signature(&trusted_keys)
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match the code hash"));
+    }
 }
@@ -16,7 +16,7 @@
 
 use crate::{
     upload::Determinism,
-    WasmCode,
+    ContractCode,
 };
 use subxt::{
     ext::{
@@ -85,12 +85,12 @@ pub(crate) struct UploadCode<Balance> {
 
 impl<Balance> UploadCode<Balance> {
     pub fn new(
-        code: WasmCode,
+        code: ContractCode,
         storage_deposit_limit: Option<Balance>,
         determinism: Determinism,
     ) -> Self {
         Self {
-            code: code.0,
+            code: code.into_bytes(),
             storage_deposit_limit: storage_deposit_limit.map(Into::into),
             determinism,
         }
@@ -27,6 +27,7 @@ use url::Url;
 use crate::{
     url_to_string,
     ContractArtifacts,
+    TrustedKeys,
 };
 use std::{
     marker::PhantomData,
@@ -44,6 +45,7 @@ pub struct ExtrinsicOpts<C: Config, E: Environment, Signer: Clone> {
     signer: Signer,
     storage_deposit_limit: Option<E::Balance>,
     verbosity: Verbosity,
+    trusted_keys: Option<TrustedKeys>,
     _marker: PhantomData<C>,
 }
 
@@ -66,6 +68,7 @@ where
                 signer,
                 storage_deposit_limit: None,
                 verbosity: Verbosity::Default,
+                trusted_keys: None,
                 _marker: PhantomData,
             },
         }
@@ -110,6 +113,13 @@ where
         this
     }
 
+    /// Requires the contract artifacts to be signed by one of the trusted keys.
+    pub fn trusted_keys(self, trusted_keys: Option<TrustedKeys>) -> Self {
+        let mut this = self;
+        this.opts.trusted_keys = trusted_keys;
+        this
+    }
+
     pub fn done(self) -> ExtrinsicOpts<C, E, Signer> {
         self.opts
     }
@@ -120,11 +130,17 @@ where
     Signer: tx::Signer<C> + Clone,
 {
     /// Load contract artifacts.
+    ///
+    /// Fails if trusted keys are set, but the artifacts are not signed by any of them.
     pub fn contract_artifacts(&self) -> Result<ContractArtifacts> {
-        ContractArtifacts::from_manifest_or_file(
+        let artifacts = ContractArtifacts::from_manifest_or_file(
             self.manifest_path.as_ref(),
             self.file.as_ref(),
-        )
+        )?;
+        if let Some(trusted_keys) = &self.trusted_keys {
+            artifacts.require_trusted_signature(trusted_keys)?;
+        }
+        Ok(artifacts)
     }
 
     /// Return the file path of the contract artifact.
@@ -160,7 +160,7 @@ where
         let data = transcoder.encode(&self.constructor, &self.args)?;
         let url = self.extrinsic_opts.url();
         let code = if let Some(code) = artifacts.code {
-            Code::Upload(code.0)
+            Code::Upload(code.into_bytes())
         } else {
             let code_hash = artifacts.code_hash()?;
             Code::Existing(code_hash.into())
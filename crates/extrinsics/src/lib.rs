@@ -15,6 +15,7 @@
 // along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
 
 mod balance;
+mod bundle_signature;
 mod call;
 mod contract_artifacts;
 mod contract_info;
@@ -42,6 +43,7 @@ use env_check::compare_node_env_with_contract;
 use anyhow::Result;
 use contract_build::{
     CrateMetadata,
+    Target,
     Verbosity,
     DEFAULT_KEY_COL_WIDTH,
 };
@@ -66,6 +68,11 @@ pub use balance::{
     BalanceVariant,
     TokenMetadata,
 };
+pub use bundle_signature::{
+    sign_metadata,
+    verify_signature,
+    TrustedKeys,
+};
 pub use call::{
     CallCommandBuilder,
     CallExec,
@@ -117,14 +124,41 @@ pub use rpc::{
     RpcRequest,
 };
 
-/// The Wasm code of a contract.
+/// The code of a contract, compiled for a target.
 #[derive(Debug, Clone)]
-pub struct WasmCode(Vec<u8>);
+pub struct ContractCode {
+    target: Target,
+    code: Vec<u8>,
+}
+
+/// The Wasm code of a contract.
+#[deprecated(note = "Use ContractCode instead")]
+pub type WasmCode = ContractCode;
+
+impl ContractCode {
+    /// Creates the contract code compiled for the `target`.
+    pub fn new(target: Target, code: Vec<u8>) -> Self {
+        Self { target, code }
+    }
+
+    /// The target the contract code was compiled for.
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    /// The raw bytes of the contract code.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.code
+    }
+
+    /// Returns the raw bytes of the contract code.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.code
+    }
 
-impl WasmCode {
     /// The hash of the contract code: uniquely identifies the contract code on-chain.
     pub fn code_hash(&self) -> [u8; 32] {
-        contract_build::code_hash(&self.0)
+        contract_build::code_hash(&self.code)
     }
 }
 
@@ -19,8 +19,8 @@ use super::{
     pallet_contracts_primitives::CodeUploadResult,
     state_call,
     submit_extrinsic,
+    ContractCode,
     ErrorVariant,
-    WasmCode,
 };
 use crate::{
     check_env_types,
@@ -107,7 +107,7 @@ pub struct UploadExec<C: Config, E: Environment, Signer: Clone> {
     opts: ExtrinsicOpts<C, E, Signer>,
     rpc: LegacyRpcMethods<C>,
     client: OnlineClient<C>,
-    code: WasmCode,
+    code: ContractCode,
     transcoder: ContractMessageTranscoder,
 }
 
@@ -130,7 +130,7 @@ where
         let storage_deposit_limit = self.opts.storage_deposit_limit();
         let call_request = CodeUploadRequest {
             origin: self.opts.signer().account_id(),
-            code: self.code.0.clone(),
+            code: self.code.as_bytes().to_vec(),
             storage_deposit_limit,
             determinism: Determinism::Enforced,
         };
@@ -174,7 +174,7 @@ where
     }
 
     /// Returns the code.
-    pub fn code(&self) -> &WasmCode {
+    pub fn code(&self) -> &ContractCode {
         &self.code
     }
 
@@ -0,0 +1,339 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The compact contract bundle format.
+//!
+//! A compact bundle stores the contract code as binary instead of hex encoding it
+//! inside the metadata JSON, and compresses each part with zlib:
+//!
+//! ```text
+//! magic "\0ink" | version: u8 | entry count: u8 | entries
+//! entry: name length: u8 | name | compressed length: u32 (LE) | zlib data
+//! ```
+//!
+//! The first entry is `metadata.json`, the metadata without the code, followed by
+//! `code.wasm` and `code.riscv` for the targets the contract was built for. The
+//! metadata can therefore be read without reading the code.
+
+use super::{
+    ContractMetadata,
+    SourceRiscV,
+    SourceWasm,
+};
+use anyhow::{
+    anyhow,
+    bail,
+    Context,
+    Result,
+};
+use miniz_oxide::inflate::TINFLStatus;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    fs::File,
+    io::{
+        BufRead,
+        BufReader,
+        BufWriter,
+        Read,
+        Write,
+    },
+    path::Path,
+    str::FromStr,
+};
+
+/// The file extension of compact contract bundles.
+pub const COMPACT_BUNDLE_EXTENSION: &str = "contractz";
+
+/// The magic bytes at the start of a compact bundle.
+const MAGIC: &[u8; 4] = b"\0ink";
+/// The version of the compact bundle format.
+const VERSION: u8 = 1;
+/// The zlib compression level, trading compression speed for size.
+const COMPRESSION_LEVEL: u8 = 9;
+
+/// The maximum decompressed size of an entry, far above the size of any contract code
+/// or metadata, to bound the memory a malicious bundle can make the reader allocate.
+const MAX_ENTRY_SIZE: usize = 32 * 1024 * 1024;
+
+const METADATA_ENTRY: &str = "metadata.json";
+const WASM_ENTRY: &str = "code.wasm";
+const RISCV_ENTRY: &str = "code.riscv";
+
+/// The format of a contract bundle file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleFormat {
+    /// The contract metadata as JSON, with the code hex encoded in the `source`.
+    Json,
+    /// The compressed bundle with the binary code, see the [module docs](self).
+    Compact,
+}
+
+impl BundleFormat {
+    /// Detects the format of the bundle file from its first bytes.
+    pub fn detect<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let (format, _) = open(path.as_ref())?;
+        Ok(format)
+    }
+}
+
+impl Display for BundleFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Compact => write!(f, "compact"),
+        }
+    }
+}
+
+impl FromStr for BundleFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "compact" => Ok(Self::Compact),
+            _ => bail!("Unknown bundle format '{s}', expected `json` or `compact`"),
+        }
+    }
+}
+
+/// Opens the bundle file and detects its format without consuming the read bytes.
+pub(crate) fn open(path: &Path) -> Result<(BundleFormat, BufReader<File>)> {
+    let file = File::open(path)
+        .context(format!("Failed to open metadata file {}", path.display()))?;
+    let mut reader = BufReader::new(file);
+    let format = if reader.fill_buf()?.starts_with(MAGIC) {
+        BundleFormat::Compact
+    } else {
+        BundleFormat::Json
+    };
+    Ok((format, reader))
+}
+
+impl ContractMetadata {
+    /// Reads the bundle file like [`ContractMetadata::load`], but without the contract
+    /// code.
+    ///
+    /// Only the metadata entry of a compact bundle is read.
+    pub fn load_without_code<P>(metadata_path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = metadata_path.as_ref();
+        let mut metadata = match open(path)? {
+            (BundleFormat::Compact, reader) => Self::read_compact(reader, false),
+            (BundleFormat::Json, _) => Self::load(path),
+        }?;
+        metadata.remove_source_wasm_attribute();
+        Ok(metadata)
+    }
+
+    /// Writes the metadata as a compact bundle to the file.
+    pub fn save_compact<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .context(format!("Failed to create bundle file {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        self.write_compact(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the metadata in the compact bundle format.
+    pub fn write_compact<W: Write>(&self, mut writer: W) -> Result<()> {
+        let mut metadata = self.clone();
+        metadata.remove_source_wasm_attribute();
+        let mut entries = vec![(METADATA_ENTRY, serde_json::to_vec(&metadata)?)];
+        if let Some(wasm) = &self.source.wasm {
+            entries.push((WASM_ENTRY, wasm.0.clone()));
+        }
+        if let Some(riscv) = &self.source.riscv {
+            entries.push((RISCV_ENTRY, riscv.0.clone()));
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION, entries.len() as u8])?;
+        for (name, data) in entries {
+            let compressed =
+                miniz_oxide::deflate::compress_to_vec_zlib(&data, COMPRESSION_LEVEL);
+            let len = u32::try_from(compressed.len())
+                .map_err(|_| anyhow!("The {name} entry exceeds 4 GiB"))?;
+            writer.write_all(&[name.len() as u8])?;
+            writer.write_all(name.as_bytes())?;
+            writer.write_all(&len.to_le_bytes())?;
+            writer.write_all(&compressed)?;
+        }
+        Ok(())
+    }
+
+    /// Reads metadata in the compact bundle format.
+    ///
+    /// Without `with_code` the reader is not consumed beyond the metadata entry.
+    pub fn read_compact<R: Read>(mut reader: R, with_code: bool) -> Result<Self> {
+        let mut header = [0u8; 6];
+        reader
+            .read_exact(&mut header)
+            .context("Failed to read the compact bundle header")?;
+        if &header[..4] != MAGIC {
+            bail!("Not a compact contract bundle")
+        }
+        if header[4] != VERSION {
+            bail!(
+                "Unsupported compact bundle version {}, expected {VERSION}",
+                header[4]
+            )
+        }
+        let (name, data) = read_entry(&mut reader)?;
+        if name != METADATA_ENTRY {
+            bail!("Expected the `{METADATA_ENTRY}` entry first, found `{name}`")
+        }
+        let mut metadata: Self = serde_json::from_slice(&data)
+            .context("Failed to deserialize the metadata of the compact bundle")?;
+        if with_code {
+            for _ in 1..header[5] {
+                match read_entry(&mut reader)? {
+                    (name, code) if name == WASM_ENTRY => {
+                        metadata.source.wasm = Some(SourceWasm::new(code))
+                    }
+                    (name, code) if name == RISCV_ENTRY => {
+                        metadata.source.riscv = Some(SourceRiscV::new(code))
+                    }
+                    // entries of later versions of the format
+                    _ => {}
+                }
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// Reads and decompresses the next entry of a compact bundle.
+fn read_entry<R: Read>(reader: &mut R) -> Result<(String, Vec<u8>)> {
+    let mut name_len = [0u8; 1];
+    reader.read_exact(&mut name_len)?;
+    let mut name = vec![0u8; name_len[0] as usize];
+    reader.read_exact(&mut name)?;
+    let name = String::from_utf8(name).context("Invalid compact bundle entry name")?;
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len);
+    // the length is untrusted, so the buffer only grows with the data actually read
+    let mut compressed = Vec::new();
+    reader
+        .by_ref()
+        .take(len.into())
+        .read_to_end(&mut compressed)?;
+    if compressed.len() != len as usize {
+        bail!("Truncated compact bundle entry `{name}`")
+    }
+    let data = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+        &compressed,
+        MAX_ENTRY_SIZE,
+    )
+    .map_err(|err| {
+        if err.status == TINFLStatus::HasMoreOutput {
+            anyhow!(
+                "The `{name}` entry exceeds the maximum size of {MAX_ENTRY_SIZE} bytes"
+            )
+        } else {
+            anyhow!("Failed to decompress the `{name}` entry: {err}")
+        }
+    })?;
+    Ok((name, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_bundle_roundtrip() {
+        let mut metadata: ContractMetadata = serde_json::from_str(
+            r#"{
+                "source": {
+                    "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                    "language": "ink! 5.0.0",
+                    "compiler": "rustc 1.78.0",
+                    "wasm": "0x0061736d01000000"
+                },
+                "contract": { "name": "flipper", "version": "5.0.0", "authors": [] },
+                "image": null,
+                "spec": { "messages": [] },
+                "version": 5
+            }"#,
+        )
+        .unwrap();
+        metadata.source.riscv = Some(SourceRiscV::new(vec![0x50, 0x56, 0x4d, 0x00]));
+
+        let mut bundle = Vec::new();
+        metadata.write_compact(&mut bundle).unwrap();
+
+        assert!(bundle.starts_with(MAGIC));
+        let read = ContractMetadata::read_compact(&bundle[..], true).unwrap();
+        assert_eq!(
+            serde_json::to_value(&read).unwrap(),
+            serde_json::to_value(&metadata).unwrap()
+        );
+
+        // the metadata entry is read without the code entries following it
+        let metadata_len = 6 + 1 + METADATA_ENTRY.len() + 4 + {
+            let len = &bundle[6 + 1 + METADATA_ENTRY.len()..][..4];
+            u32::from_le_bytes(len.try_into().unwrap()) as usize
+        };
+        let without_code =
+            ContractMetadata::read_compact(&bundle[..metadata_len], false).unwrap();
+        assert_eq!(without_code.source.wasm, None);
+        assert_eq!(without_code.source.riscv, None);
+        assert_eq!(without_code.abi, metadata.abi);
+        assert!(ContractMetadata::read_compact(&bundle[..metadata_len], true).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_entries() {
+        let entry = |name: &str, len: u32, data: &[u8]| {
+            let mut bundle = MAGIC.to_vec();
+            bundle.extend([VERSION, 1, name.len() as u8]);
+            bundle.extend(name.as_bytes());
+            bundle.extend(len.to_le_bytes());
+            bundle.extend(data);
+            bundle
+        };
+
+        // a length beyond the end of the bundle is not allocated upfront
+        let truncated = entry(METADATA_ENTRY, u32::MAX, b"x");
+        let err = ContractMetadata::read_compact(&truncated[..], false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Truncated compact bundle entry `metadata.json`"
+        );
+
+        let bomb =
+            miniz_oxide::deflate::compress_to_vec_zlib(&vec![0; MAX_ENTRY_SIZE + 1], 1);
+        let oversized = entry(METADATA_ENTRY, bomb.len() as u32, &bomb);
+        let err = ContractMetadata::read_compact(&oversized[..], false).unwrap_err();
+        assert!(err.to_string().contains("exceeds the maximum size"));
+    }
+}
@@ -67,7 +67,20 @@
 #![deny(unused_crate_dependencies)]
 
 pub mod byte_str;
+pub mod compact;
 pub mod compatibility;
+pub mod signature;
+
+pub use self::{
+    compact::{
+        BundleFormat,
+        COMPACT_BUNDLE_EXTENSION,
+    },
+    signature::{
+        BundleSignature,
+        SignatureScheme,
+    },
+};
 
 use anyhow::{
     Context,
@@ -85,12 +98,12 @@ use serde_json::{
     Value,
 };
 use std::{
+    collections::BTreeMap,
     fmt::{
         Display,
         Formatter,
         Result as DisplayResult,
     },
-    fs::File,
     path::Path,
     str::FromStr,
 };
@@ -109,6 +122,9 @@ pub struct ContractMetadata {
     /// Additional user-defined metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<User>,
+    /// Signatures of the bundle by the keys of build pipelines.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signatures: Vec<BundleSignature>,
     /// Raw JSON of the contract's abi metadata, generated during contract compilation.
     #[serde(flatten)]
     pub abi: Map<String, Value>,
@@ -128,23 +144,31 @@ impl ContractMetadata {
             contract,
             image,
             user,
+            signatures: Vec::new(),
             abi,
         }
     }
 
+    /// Removes the bundled code of the contract, for all targets.
     pub fn remove_source_wasm_attribute(&mut self) {
         self.source.wasm = None;
+        self.source.riscv = None;
     }
 
     /// Reads the file and tries to parse it as instance of `ContractMetadata`.
+    ///
+    /// The file is either JSON or a compact bundle, see [`BundleFormat`].
     pub fn load<P>(metadata_path: P) -> Result<Self>
     where
         P: AsRef<Path>,
     {
         let path = metadata_path.as_ref();
-        let file = File::open(path)
-            .context(format!("Failed to open metadata file {}", path.display()))?;
-        serde_json::from_reader(file).context(format!(
+        let (format, reader) = compact::open(path)?;
+        let metadata = match format {
+            BundleFormat::Json => serde_json::from_reader(reader).map_err(Into::into),
+            BundleFormat::Compact => Self::read_compact(reader, true),
+        };
+        metadata.context(format!(
             "Failed to deserialize metadata file {}",
             path.display()
         ))
@@ -192,11 +216,19 @@ impl Display for CodeHash {
     }
 }
 
-/// Information about the contract's Wasm code.
+/// Information about the contract's code.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Source {
-    /// The hash of the contract's Wasm code.
+    /// The hash of the contract's code, as computed by the runtime of the `target`.
     pub hash: CodeHash,
+    /// The target the contract's code was compiled for.
+    #[serde(default, skip_serializing_if = "SourceTarget::is_wasm")]
+    pub target: SourceTarget,
+    /// The code hashes of all targets, if the contract was built for several targets.
+    ///
+    /// The code of each target is bundled in the field of the target, e.g. `riscv`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hashes: BTreeMap<SourceTarget, CodeHash>,
     /// The language used to write the contract.
     pub language: SourceLanguage,
     /// The compiler used to compile the contract.
@@ -205,6 +237,10 @@ pub struct Source {
     /// with the metadata.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wasm: Option<SourceWasm>,
+    /// The actual RISC-V code of the contract, for optionally bundling the code
+    /// with the metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub riscv: Option<SourceRiscV>,
     /// Extra information about the environment in which the contract was built.
     ///
     /// Useful for producing deterministic builds.
@@ -223,12 +259,126 @@ impl Source {
     ) -> Self {
         Source {
             hash,
+            target: SourceTarget::Wasm,
+            hashes: BTreeMap::new(),
             language,
             compiler,
             wasm,
+            riscv: None,
             build_info,
         }
     }
+
+    /// Constructs a new `Source` for contract code compiled to RISC-V.
+    pub fn new_riscv(
+        riscv: Option<SourceRiscV>,
+        hash: CodeHash,
+        language: SourceLanguage,
+        compiler: SourceCompiler,
+        build_info: Option<Map<String, Value>>,
+    ) -> Self {
+        Source {
+            hash,
+            target: SourceTarget::RiscV,
+            hashes: BTreeMap::new(),
+            language,
+            compiler,
+            wasm: None,
+            riscv,
+            build_info,
+        }
+    }
+
+    /// Adds the code of a further target the contract was built for.
+    pub fn add_target(&mut self, target: SourceTarget, code: Vec<u8>, hash: CodeHash) {
+        self.hashes.insert(self.target, self.hash);
+        self.hashes.insert(target, hash);
+        match target {
+            SourceTarget::Wasm => self.wasm = Some(SourceWasm::new(code)),
+            SourceTarget::RiscV => self.riscv = Some(SourceRiscV::new(code)),
+        }
+    }
+
+    /// Returns the targets the contract was built for, starting with its `target`.
+    pub fn targets(&self) -> Vec<SourceTarget> {
+        let others = self.hashes.keys().filter(|target| **target != self.target);
+        std::iter::once(self.target)
+            .chain(others.copied())
+            .collect()
+    }
+
+    /// Returns the bundled code of the contract for its `target`, if any.
+    pub fn code(&self) -> Option<&[u8]> {
+        self.code_for(self.target)
+    }
+
+    /// Returns the bundled code of the contract for the `target`, if any.
+    pub fn code_for(&self, target: SourceTarget) -> Option<&[u8]> {
+        match target {
+            SourceTarget::Wasm => self.wasm.as_ref().map(|wasm| wasm.0.as_slice()),
+            SourceTarget::RiscV => self.riscv.as_ref().map(|riscv| riscv.0.as_slice()),
+        }
+    }
+
+    /// Returns the code hash of the contract for the `target`, if it was built for it.
+    pub fn hash_for(&self, target: SourceTarget) -> Option<CodeHash> {
+        if target == self.target {
+            Some(self.hash)
+        } else {
+            self.hashes.get(&target).copied()
+        }
+    }
+}
+
+/// The target a smart contract is compiled for.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Deserialize,
+    Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceTarget {
+    /// WebAssembly, executed by `pallet-contracts`.
+    #[default]
+    Wasm,
+    /// RISC-V, linked into a PolkaVM program blob.
+    RiscV,
+}
+
+impl SourceTarget {
+    /// Returns `true` if the target is WebAssembly.
+    pub fn is_wasm(&self) -> bool {
+        matches!(self, Self::Wasm)
+    }
+}
+
+impl Display for SourceTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Wasm => write!(f, "wasm"),
+            Self::RiscV => write!(f, "riscv"),
+        }
+    }
+}
+
+impl FromStr for SourceTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wasm" => Ok(Self::Wasm),
+            "riscv" => Ok(Self::RiscV),
+            _ => Err(format!("Invalid target '{s}'")),
+        }
+    }
 }
 
 /// The bytes of the compiled Wasm smart contract.
@@ -259,6 +409,34 @@ impl Display for SourceWasm {
     }
 }
 
+/// The bytes of the compiled RISC-V smart contract, i.e. the PolkaVM program blob.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SourceRiscV(
+    #[serde(
+        serialize_with = "byte_str::serialize_as_byte_str",
+        deserialize_with = "byte_str::deserialize_from_byte_str"
+    )]
+    /// The raw bytes of the RISC-V code.
+    pub Vec<u8>,
+);
+
+impl SourceRiscV {
+    /// Constructs a new `SourceRiscV`.
+    pub fn new(riscv: Vec<u8>) -> Self {
+        SourceRiscV(riscv)
+    }
+}
+
+impl Display for SourceRiscV {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        write!(f, "0x").expect("failed writing to string");
+        for byte in &self.0 {
+            write!(f, "{byte:02x}").expect("failed writing to string");
+        }
+        write!(f, "")
+    }
+}
+
 /// The language and version in which a smart contract is written.
 #[derive(Clone, Debug)]
 pub struct SourceLanguage {
@@ -943,4 +1121,80 @@ mod tests {
         let decoded = serde_json::from_value::<ContractMetadata>(json);
         assert!(decoded.is_ok())
     }
+
+    #[test]
+    fn riscv_source_roundtrips() {
+        let language = SourceLanguage::new(Language::Ink, Version::new(5, 0, 0));
+        let compiler =
+            SourceCompiler::new(Compiler::RustC, Version::parse("1.78.0").unwrap());
+        let riscv = SourceRiscV::new(vec![b'P', b'V', b'M', 0u8]);
+
+        let source =
+            Source::new_riscv(Some(riscv), CodeHash([0u8; 32]), language, compiler, None);
+        let json = serde_json::to_value(&source).unwrap();
+
+        assert_eq!(json["target"], "riscv");
+        assert_eq!(json["riscv"], "0x50564d00");
+        assert!(json.get("wasm").is_none());
+
+        let decoded = serde_json::from_value::<Source>(json).unwrap();
+        assert_eq!(decoded.target, SourceTarget::RiscV);
+        assert_eq!(decoded.code(), Some(&b"PVM\0"[..]));
+    }
+
+    #[test]
+    fn source_bundles_several_targets() {
+        let language = SourceLanguage::new(Language::Ink, Version::new(5, 0, 0));
+        let compiler =
+            SourceCompiler::new(Compiler::RustC, Version::parse("1.78.0").unwrap());
+        let wasm = SourceWasm::new(b"\0asm".to_vec());
+        let mut source =
+            Source::new(Some(wasm), CodeHash([1u8; 32]), language, compiler, None);
+
+        source.add_target(SourceTarget::RiscV, b"PVM\0".to_vec(), CodeHash([2u8; 32]));
+        let json = serde_json::to_value(&source).unwrap();
+        let decoded = serde_json::from_value::<Source>(json.clone()).unwrap();
+
+        assert_eq!(json["wasm"], "0x0061736d");
+        assert_eq!(json["riscv"], "0x50564d00");
+        assert_eq!(
+            json["hashes"]["riscv"],
+            "0x0202020202020202020202020202020202020202020202020202020202020202"
+        );
+        assert_eq!(
+            decoded.targets(),
+            vec![SourceTarget::Wasm, SourceTarget::RiscV]
+        );
+        assert_eq!(
+            decoded.hash_for(SourceTarget::Wasm),
+            Some(CodeHash([1u8; 32]))
+        );
+        assert_eq!(
+            decoded.hash_for(SourceTarget::RiscV),
+            Some(CodeHash([2u8; 32]))
+        );
+        assert_eq!(decoded.code_for(SourceTarget::RiscV), Some(&b"PVM\0"[..]));
+        assert_eq!(decoded.code(), Some(&b"\0asm"[..]));
+    }
+
+    #[test]
+    fn source_without_target_is_wasm() {
+        let json = json! {
+            {
+                "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "language": "ink! 5.0.0",
+                "compiler": "rustc 1.78.0",
+                "wasm": "0x0061736d"
+            }
+        };
+
+        let source = serde_json::from_value::<Source>(json).unwrap();
+
+        assert_eq!(source.target, SourceTarget::Wasm);
+        assert_eq!(source.code(), Some(&b"\0asm"[..]));
+        assert!(serde_json::to_value(&source)
+            .unwrap()
+            .get("target")
+            .is_none());
+    }
 }
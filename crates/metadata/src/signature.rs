@@ -0,0 +1,190 @@
+// Copyright (C) Use Ink (UK) Ltd.
+// This file is part of cargo-contract.
+//
+// cargo-contract is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// cargo-contract is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with cargo-contract.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signatures of contract bundles, to prove the origin of a bundle.
+//!
+//! A signature covers the canonical JSON encoding of the `source`, `contract` and
+//! `abi` of the [`ContractMetadata`]: the object keys are sorted and no whitespace is
+//! inserted. The `image`, `user` and `signatures` are not signed, so several
+//! signatures can be added to a bundle.
+
+use super::{
+    byte_str,
+    ContractMetadata,
+};
+use anyhow::{
+    bail,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+use std::{
+    fmt::{
+        Display,
+        Formatter,
+        Result as DisplayResult,
+    },
+    str::FromStr,
+};
+
+/// The signature scheme of a [`BundleSignature`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureScheme {
+    /// Schnorr signatures on Ristretto, as used by Substrate accounts.
+    Sr25519,
+    /// EdDSA signatures on Curve25519.
+    Ed25519,
+}
+
+impl Display for SignatureScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> DisplayResult {
+        match self {
+            Self::Sr25519 => write!(f, "sr25519"),
+            Self::Ed25519 => write!(f, "ed25519"),
+        }
+    }
+}
+
+impl FromStr for SignatureScheme {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sr25519" => Ok(Self::Sr25519),
+            "ed25519" => Ok(Self::Ed25519),
+            _ => bail!("Unknown signature scheme '{s}', expected `sr25519` or `ed25519`"),
+        }
+    }
+}
+
+/// A signature of the contract metadata by the key of a build pipeline.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BundleSignature {
+    /// The signature scheme of the key.
+    pub scheme: SignatureScheme,
+    /// The public key of the signer.
+    #[serde(
+        serialize_with = "byte_str::serialize_as_byte_str",
+        deserialize_with = "byte_str::deserialize_from_byte_str"
+    )]
+    pub public_key: Vec<u8>,
+    /// The signature of the [`ContractMetadata::signing_payload`].
+    #[serde(
+        serialize_with = "byte_str::serialize_as_byte_str",
+        deserialize_with = "byte_str::deserialize_from_byte_str"
+    )]
+    pub signature: Vec<u8>,
+}
+
+impl ContractMetadata {
+    /// Returns the canonical JSON encoding of the `source`, `contract` and `abi`, which
+    /// is signed by a [`BundleSignature`].
+    pub fn signing_payload(&self) -> Result<Vec<u8>> {
+        let signed = serde_json::json!({
+            "source": self.source,
+            "contract": self.contract,
+            "abi": self.abi,
+        });
+        let mut payload = String::new();
+        write_canonical(&signed, &mut payload)?;
+        Ok(payload.into_bytes())
+    }
+}
+
+/// Writes the JSON value with sorted object keys and without whitespace.
+fn write_canonical(value: &Value, out: &mut String) -> Result<()> {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            out.push('{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key)?);
+                out.push(':');
+                write_canonical(item, out)?;
+            }
+            out.push('}');
+        }
+        _ => out.push_str(&serde_json::to_string(value)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_payload_is_canonical_and_excludes_unsigned_fields() {
+        let json = |abi: &str, image: &str| {
+            serde_json::from_str::<ContractMetadata>(&format!(
+                r#"{{
+                    "source": {{
+                        "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                        "language": "ink! 5.0.0",
+                        "compiler": "rustc 1.78.0"
+                    }},
+                    "contract": {{ "name": "flipper", "version": "5.0.0", "authors": [] }},
+                    "image": "{image}",
+                    {abi}
+                }}"#
+            ))
+            .unwrap()
+        };
+        let mut metadata =
+            json(r#""spec": { "b": [1, 2], "a": "x" }, "version": 5"#, "a");
+        let reordered = json(r#""version": 5, "spec": { "a": "x", "b": [1, 2] }"#, "b");
+        metadata.signatures.push(BundleSignature {
+            scheme: SignatureScheme::Ed25519,
+            public_key: vec![1; 32],
+            signature: vec![2; 64],
+        });
+
+        let payload = String::from_utf8(metadata.signing_payload().unwrap()).unwrap();
+
+        assert_eq!(
+            payload,
+            String::from_utf8(reordered.signing_payload().unwrap()).unwrap()
+        );
+        assert!(
+            payload.starts_with(r#"{"abi":{"spec":{"a":"x","b":[1,2]},"version":5},"#)
+        );
+        assert!(!payload.contains("image") && !payload.contains("signatures"));
+
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["signatures"][0]["scheme"], "ed25519");
+        let roundtrip: ContractMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(roundtrip.signatures, metadata.signatures);
+        assert!(!roundtrip.abi.contains_key("signatures"));
+    }
+}